@@ -0,0 +1,107 @@
+//! 热路径基准测试
+//!
+//! 运行方式：
+//!
+//! ```bash
+//! cargo bench --bench strategy_bench
+//! # 只运行某一组
+//! cargo bench --bench strategy_bench -- on_orderbook_update
+//! ```
+//!
+//! 报告位于 `target/criterion/report/index.html`，每次运行会与上一次结果对比，
+//! 用于发现延迟回归（目标：订单簿更新 < 20μs，含报价计算）。
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use nautilus_core::UnixNanos;
+use nautilus_strategies_rust::indicators::EWMAVolatility;
+use nautilus_strategies_rust::strategies::avellaneda_stoikov::{
+    ASConfig, AvellanedaStoikov, Bar, OrderBookSnapshot,
+};
+
+/// 典型波动率窗口大小
+const WINDOW_SIZES: [usize; 3] = [20, 100, 500];
+
+fn snapshot(i: u64) -> OrderBookSnapshot {
+    let offset = (i % 50) as f64;
+    OrderBookSnapshot {
+        best_bid: 50000.0 + offset,
+        best_ask: 50010.0 + offset,
+        bid_volume: 1.0,
+        ask_volume: 1.5,
+        timestamp_ns: UnixNanos::new(i * 1_000_000),
+    }
+}
+
+fn bar(i: u64) -> Bar {
+    let offset = (i % 50) as f64;
+    Bar {
+        open: 50000.0 + offset,
+        high: 50050.0 + offset,
+        low: 49950.0 + offset,
+        close: 50010.0 + offset,
+        volume: 10.0,
+        timestamp_ns: i * 60_000_000_000,
+    }
+}
+
+/// 创建已完成预热的策略（价格与高低价历史已填满窗口）
+fn warmed_strategy(use_parkinson: bool, window: usize) -> AvellanedaStoikov {
+    let mut strategy = AvellanedaStoikov::new(ASConfig {
+        volatility_window: window,
+        use_parkinson,
+        stats_interval: 0,
+        ..ASConfig::default()
+    });
+
+    for i in 0..window as u64 {
+        strategy.on_orderbook_update(&snapshot(i));
+        strategy.on_bar(&bar(i));
+    }
+    strategy
+}
+
+fn bench_on_orderbook_update(c: &mut Criterion) {
+    let mut group = c.benchmark_group("on_orderbook_update");
+    for window in WINDOW_SIZES {
+        let mut strategy = warmed_strategy(true, window);
+        let mut i = 0u64;
+        group.bench_with_input(BenchmarkId::from_parameter(window), &window, |b, _| {
+            b.iter(|| {
+                i += 1;
+                black_box(strategy.on_orderbook_update(black_box(&snapshot(i))))
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_parkinson_volatility(c: &mut Criterion) {
+    let mut group = c.benchmark_group("calculate_parkinson_volatility");
+    for window in WINDOW_SIZES {
+        let strategy = warmed_strategy(true, window);
+        group.bench_with_input(BenchmarkId::from_parameter(window), &window, |b, _| {
+            b.iter(|| black_box(strategy.calculate_parkinson_volatility()))
+        });
+    }
+    group.finish();
+}
+
+fn bench_ewma_update(c: &mut Criterion) {
+    let mut ewma = EWMAVolatility::new(0.06);
+    let mut i = 0u64;
+    c.bench_function("ewma_volatility_update", |b| {
+        b.iter(|| {
+            i += 1;
+            let r = if i.is_multiple_of(2) { 0.001 } else { -0.0012 };
+            black_box(ewma.update(black_box(r)))
+        })
+    });
+}
+
+criterion_group!(
+    benches,
+    bench_on_orderbook_update,
+    bench_parkinson_volatility,
+    bench_ewma_update
+);
+criterion_main!(benches);
@@ -4,7 +4,7 @@
 
 use nautilus_strategies_rust::strategies::avellaneda_stoikov::ASConfig;
 use nautilus_strategies_rust::strategies::nautilus_compatible::{
-    create_strategy, create_strategy_with_config,
+    create_strategy, try_create_strategy_with_config,
 };
 
 fn main() {
@@ -29,7 +29,8 @@ fn main() {
     custom_config.volatility_window = 20;
     custom_config.use_parkinson = true;
 
-    let custom_strategy = create_strategy_with_config(custom_config);
+    let custom_strategy =
+        try_create_strategy_with_config(custom_config).expect("自定义配置应通过校验");
     println!("策略状态: {:?}", custom_strategy.state());
 
     // 3. 验证策略核心功能
@@ -6,25 +6,379 @@
 //! 用法:
 //! ```bash
 //! cargo run --release --bin backtest
+//!
+//! # 将回测报告（JSON + Markdown）写入指定目录（也可设置 BACKTEST_REPORT_DIR）
+//! cargo run --release --bin backtest -- --report-dir reports
+//!
+//! # 场所手续费率（负值表示返佣，也可设置 MAKER_FEE / TAKER_FEE）
+//! cargo run --release --bin backtest -- --maker-fee -0.0001 --taker-fee 0.0005
+//!
+//! # 撮合延迟：提交/撤单到确认的固定延迟，可加随机抖动（按种子在运行开始时抽取一次）
+//! cargo run --release --bin backtest -- --submit-latency-ms 20 --cancel-latency-ms 15 \
+//!     --latency-jitter-ms 10 --latency-seed 42
 //! ```
 
 use anyhow::Result;
 use nautilus_backtest::{
     config::BacktestEngineConfig,
     engine::BacktestEngine,
+    result::BacktestResult,
 };
+use nautilus_common::actor::registry::try_get_actor_unchecked;
 use nautilus_model::{
-    identifiers::{TraderId, Venue},
+    identifiers::{StrategyId, TraderId, Venue},
     enums::{OmsType, AccountType, BookType},
     types::Money,
 };
 use nautilus_common::enums::Environment;
-use nautilus_execution::models::{fee::FeeModelAny, fill::FillModel};
+use nautilus_core::UnixNanos;
+use nautilus_execution::models::{
+    fee::{FeeModelAny, MakerTakerFeeModel},
+    fill::FillModel,
+    latency::LatencyModel,
+};
 use nautilus_model::instruments::stubs::crypto_perpetual_ethusdt;
 use tracing::info;
 use ahash::AHashMap;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use nautilus_strategies_rust::strategies::avellaneda_stoikov::ASConfig;
+use nautilus_strategies_rust::strategies::nautilus_compatible::{
+    FillFeeStats, NautilusASConfig, NautilusAvellanedaStoikov,
+};
+
+/// 回测运行选项（命令行参数优先于环境变量）
+#[derive(Debug, Clone, Default, PartialEq)]
+struct BacktestOptions {
+    /// 回测报告输出目录（None 表示只打印日志）
+    report_dir: Option<PathBuf>,
+    /// 场所手续费率
+    fees: FeeSchedule,
+    /// 撮合延迟
+    latency: LatencySpec,
+}
+
+impl BacktestOptions {
+    /// 从命令行参数和环境变量解析
+    fn parse<F>(args: &[String], env: F) -> Result<Self>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let report_dir = flag_value(args, "--report-dir")
+            .or_else(|| env("BACKTEST_REPORT_DIR"))
+            .map(PathBuf::from);
+
+        let defaults = FeeSchedule::default();
+        let maker_fee = match flag_value(args, "--maker-fee").or_else(|| env("MAKER_FEE")) {
+            Some(value) => parse_fee(&value)?,
+            None => defaults.maker_fee,
+        };
+        let taker_fee = match flag_value(args, "--taker-fee").or_else(|| env("TAKER_FEE")) {
+            Some(value) => parse_fee(&value)?,
+            None => defaults.taker_fee,
+        };
+
+        let latency_ms = |flag: &str, key: &str| -> Result<u64> {
+            match flag_value(args, flag).or_else(|| env(key)) {
+                Some(value) => parse_ms(&value),
+                None => Ok(0),
+            }
+        };
+        let latency = LatencySpec {
+            submit_ns: latency_ms("--submit-latency-ms", "SUBMIT_LATENCY_MS")?,
+            cancel_ns: latency_ms("--cancel-latency-ms", "CANCEL_LATENCY_MS")?,
+            jitter_ns: latency_ms("--latency-jitter-ms", "LATENCY_JITTER_MS")?,
+            seed: match flag_value(args, "--latency-seed").or_else(|| env("LATENCY_SEED")) {
+                Some(value) => value
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("无效的随机种子 {}: {}", value, e))?,
+                None => 0,
+            },
+        };
+
+        Ok(Self {
+            report_dir,
+            fees: FeeSchedule { maker_fee, taker_fee },
+            latency,
+        })
+    }
+}
+
+/// 撮合延迟设置（纳秒）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+struct LatencySpec {
+    /// 提交到确认的延迟
+    submit_ns: u64,
+    /// 撤单到确认的延迟
+    cancel_ns: u64,
+    /// 随机抖动上限（每项延迟额外加上 [0, jitter] 的均匀随机值）
+    jitter_ns: u64,
+    /// 抖动随机种子（保证回测可复现）
+    seed: u64,
+}
+
+impl LatencySpec {
+    /// 抽取本次运行的实际延迟 (提交, 撤单)，改单延迟与提交相同
+    fn sample(&self) -> (u64, u64) {
+        if self.jitter_ns == 0 {
+            return (self.submit_ns, self.cancel_ns);
+        }
+
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        (
+            self.submit_ns + rng.gen_range(0..=self.jitter_ns),
+            self.cancel_ns + rng.gen_range(0..=self.jitter_ns),
+        )
+    }
+
+    /// 构建 Nautilus 延迟模型（全部为0时不使用延迟模型）
+    fn latency_model(&self) -> Option<LatencyModel> {
+        let (submit_ns, cancel_ns) = self.sample();
+        if submit_ns == 0 && cancel_ns == 0 {
+            return None;
+        }
+
+        Some(LatencyModel::new(
+            UnixNanos::default(),       // 基础延迟
+            UnixNanos::from(submit_ns), // 提交延迟
+            UnixNanos::from(submit_ns), // 改单延迟
+            UnixNanos::from(cancel_ns), // 撤单延迟
+        ))
+    }
+}
+
+/// 解析毫秒参数并转换为纳秒
+fn parse_ms(value: &str) -> Result<u64> {
+    let ms: u64 = value
+        .parse()
+        .map_err(|e| anyhow::anyhow!("无效的毫秒数 {}: {}", value, e))?;
+    Ok(ms * 1_000_000)
+}
+
+/// 场所手续费率（按成交额比例，负值表示返佣）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+struct FeeSchedule {
+    /// 挂单成交费率
+    maker_fee: Decimal,
+    /// 吃单成交费率
+    taker_fee: Decimal,
+}
+
+impl Default for FeeSchedule {
+    /// Binance U本位合约默认费率: maker 0.02%, taker 0.05%
+    fn default() -> Self {
+        Self {
+            maker_fee: dec!(0.0002),
+            taker_fee: dec!(0.0005),
+        }
+    }
+}
+
+impl FeeSchedule {
+    /// 单笔成交手续费（返佣时为负）
+    fn commission(&self, notional: Decimal, is_maker: bool) -> Decimal {
+        let rate = if is_maker { self.maker_fee } else { self.taker_fee };
+        notional * rate
+    }
+}
+
+/// 手续费汇总：引擎成交回报中的手续费与按费率估算的手续费（两者应一致）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+struct FeeSummary {
+    /// maker 成交额
+    maker_notional: f64,
+    /// taker 成交额
+    taker_notional: f64,
+    /// 按 `FeeSchedule` 估算的手续费（返佣为负）
+    expected_commission: f64,
+    /// 引擎成交回报中的手续费合计（返佣为负）
+    reported_commission: f64,
+}
+
+impl FeeSummary {
+    fn from_fills(fees: &FeeSchedule, stats: &FillFeeStats) -> Self {
+        let commission = |notional: f64, is_maker: bool| {
+            Decimal::from_f64(notional)
+                .map(|notional| fees.commission(notional, is_maker))
+                .and_then(|commission| commission.to_f64())
+                .unwrap_or_default()
+        };
+        Self {
+            maker_notional: stats.maker_notional,
+            taker_notional: stats.taker_notional,
+            expected_commission: commission(stats.maker_notional, true)
+                + commission(stats.taker_notional, false),
+            reported_commission: stats.commission,
+        }
+    }
+}
+
+/// 解析手续费率参数
+fn parse_fee(value: &str) -> Result<Decimal> {
+    value
+        .parse()
+        .map_err(|e| anyhow::anyhow!("无效的手续费率 {}: {}", value, e))
+}
+
+/// 读取 `--name value` 或 `--name=value` 形式的参数
+fn flag_value(args: &[String], name: &str) -> Option<String> {
+    let prefix = format!("{}=", name);
+    args.iter().enumerate().find_map(|(i, arg)| {
+        if arg == name {
+            args.get(i + 1).cloned()
+        } else {
+            arg.strip_prefix(&prefix).map(str::to_string)
+        }
+    })
+}
+
+/// 回测指标（按报告章节分组）
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+struct BacktestMetrics {
+    /// 总订单数
+    total_orders: usize,
+    /// 总持仓数
+    total_positions: usize,
+    /// 回测迭代次数
+    iterations: usize,
+    /// 运行耗时（秒）
+    elapsed_secs: f64,
+    /// 各币种盈亏统计（币种 -> 指标名 -> 值）
+    pnl: BTreeMap<String, BTreeMap<String, f64>>,
+    /// 收益率统计（夏普比率、最大回撤等）
+    returns: BTreeMap<String, f64>,
+    /// 通用统计（胜率、平均盈亏等）
+    general: BTreeMap<String, f64>,
+    /// 手续费汇总
+    fees: FeeSummary,
+}
+
+impl BacktestMetrics {
+    /// 从回测引擎结果提取指标（使用有序映射保证报告输出稳定）
+    fn from_result(result: &BacktestResult, fees: FeeSummary) -> Self {
+        Self {
+            total_orders: result.total_orders,
+            total_positions: result.total_positions,
+            iterations: result.iterations,
+            elapsed_secs: result.elapsed_time,
+            pnl: result
+                .stats_pnls
+                .iter()
+                .map(|(currency, stats)| {
+                    let stats = stats.iter().map(|(k, v)| (k.clone(), *v)).collect();
+                    (currency.clone(), stats)
+                })
+                .collect(),
+            returns: result.stats_returns.iter().map(|(k, v)| (k.clone(), *v)).collect(),
+            general: result.stats_general.iter().map(|(k, v)| (k.clone(), *v)).collect(),
+            fees,
+        }
+    }
+}
+
+/// 持久化的回测报告
+#[derive(Debug, Clone, Serialize)]
+struct BacktestReport<'a> {
+    /// 使用的策略配置
+    config: &'a ASConfig,
+    /// 使用的手续费率
+    fees: &'a FeeSchedule,
+    /// 回测指标
+    metrics: &'a BacktestMetrics,
+}
+
+/// JSON 报告文件名
+const REPORT_JSON: &str = "backtest_report.json";
+/// Markdown 报告文件名
+const REPORT_MARKDOWN: &str = "backtest_report.md";
+
+/// 将回测报告写入目录（JSON 和 Markdown 各一份），返回写入的文件路径
+fn write_report(
+    dir: &Path,
+    config: &ASConfig,
+    fees: &FeeSchedule,
+    metrics: &BacktestMetrics,
+) -> Result<(PathBuf, PathBuf)> {
+    std::fs::create_dir_all(dir)?;
 
-use nautilus_strategies_rust::strategies::nautilus_compatible::create_strategy;
+    let json_path = dir.join(REPORT_JSON);
+    let report = BacktestReport {
+        config,
+        fees,
+        metrics,
+    };
+    std::fs::write(&json_path, serde_json::to_string_pretty(&report)?)?;
+
+    let markdown_path = dir.join(REPORT_MARKDOWN);
+    std::fs::write(&markdown_path, render_markdown(config, fees, metrics)?)?;
+
+    Ok((json_path, markdown_path))
+}
+
+/// 生成可读的 Markdown 摘要
+fn render_markdown(
+    config: &ASConfig,
+    fees: &FeeSchedule,
+    metrics: &BacktestMetrics,
+) -> Result<String> {
+    fn table<'a>(out: &mut String, rows: impl IntoIterator<Item = (&'a str, String)>) {
+        out.push_str("| 指标 | 值 |\n|---|---|\n");
+        for (name, value) in rows {
+            out.push_str(&format!("| {} | {} |\n", name, value));
+        }
+        out.push('\n');
+    }
+
+    let mut out = String::from("# Avellaneda-Stoikov 回测报告\n\n");
+
+    out.push_str("## 概览\n\n");
+    table(
+        &mut out,
+        [
+            ("交易品种", config.instrument_id.clone()),
+            ("Maker 费率", fees.maker_fee.to_string()),
+            ("Taker 费率", fees.taker_fee.to_string()),
+            ("总订单数", metrics.total_orders.to_string()),
+            ("总持仓数", metrics.total_positions.to_string()),
+            ("迭代次数", metrics.iterations.to_string()),
+            ("运行耗时(秒)", format!("{:.3}", metrics.elapsed_secs)),
+        ],
+    );
+
+    for (currency, stats) in &metrics.pnl {
+        out.push_str(&format!("## 盈亏统计 ({})\n\n", currency));
+        table(&mut out, stats.iter().map(|(k, v)| (k.as_str(), format!("{:.6}", v))));
+    }
+
+    out.push_str("## 手续费\n\n");
+    table(
+        &mut out,
+        [
+            ("Maker 成交额", format!("{:.2}", metrics.fees.maker_notional)),
+            ("Taker 成交额", format!("{:.2}", metrics.fees.taker_notional)),
+            ("按费率估算手续费", format!("{:.6}", metrics.fees.expected_commission)),
+            ("成交回报手续费", format!("{:.6}", metrics.fees.reported_commission)),
+        ],
+    );
+
+    out.push_str("## 收益统计\n\n");
+    table(&mut out, metrics.returns.iter().map(|(k, v)| (k.as_str(), format!("{:.6}", v))));
+
+    out.push_str("## 通用统计\n\n");
+    table(&mut out, metrics.general.iter().map(|(k, v)| (k.as_str(), format!("{:.6}", v))));
+
+    out.push_str("## 策略配置\n\n```json\n");
+    out.push_str(&serde_json::to_string_pretty(config)?);
+    out.push_str("\n```\n");
+
+    Ok(out)
+}
 
 fn main() -> Result<()> {
     info!("=================================================================");
@@ -32,6 +386,10 @@ fn main() -> Result<()> {
     info!("版本: {}", env!("CARGO_PKG_VERSION"));
     info!("=================================================================");
 
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let options = BacktestOptions::parse(&args, |key| std::env::var(key).ok())?;
+    let strategy_config = ASConfig::default();
+
     // 创建回测引擎配置
     info!("配置回测引擎...");
     let config = create_engine_config()?;
@@ -43,17 +401,17 @@ fn main() -> Result<()> {
 
     // 添加交易场所配置
     info!("添加交易场所配置...");
-    add_venue_config(&mut engine)?;
+    add_venue_config(&mut engine, &options.latency)?;
     info!("✅ 交易场所配置完成");
 
     // 添加交易工具
     info!("添加交易工具...");
-    add_instrument(&mut engine)?;
+    add_instrument(&mut engine, &options.fees)?;
     info!("✅ 交易工具添加完成");
 
     // 添加策略
     info!("添加 Avellaneda-Stoikov 策略...");
-    add_strategy(&mut engine)?;
+    let strategy_id = add_strategy(&mut engine, &strategy_config)?;
     info!("✅ 策略添加完成");
 
     // 加载历史数据
@@ -68,7 +426,9 @@ fn main() -> Result<()> {
 
     // 分析和打印结果
     info!("📊 分析回测结果...");
-    analyze_results()?;
+    let fees = fee_summary(strategy_id, &options.fees);
+    let metrics = BacktestMetrics::from_result(&engine.get_result(), fees);
+    analyze_results(&metrics, &strategy_config, &options)?;
 
     info!("");
     info!("=================================================================");
@@ -108,7 +468,12 @@ fn create_engine_config() -> Result<BacktestEngineConfig> {
 }
 
 /// 添加交易场所配置
-fn add_venue_config(engine: &mut BacktestEngine) -> Result<()> {
+fn add_venue_config(engine: &mut BacktestEngine, latency: &LatencySpec) -> Result<()> {
+    let latency_model = latency.latency_model();
+    if latency_model.is_some() {
+        info!("撮合延迟: {:?}", latency);
+    }
+
     // 创建币安交易场所配置
     engine.add_venue(
         Venue::from("BINANCE"),                      // 场所名称
@@ -121,8 +486,8 @@ fn add_venue_config(engine: &mut BacktestEngine) -> Result<()> {
         AHashMap::new(),                             // 特定工具杠杆
         vec![],                                      // 模拟模块
         FillModel::default(),                        // 成交模型
-        FeeModelAny::default(),                     // 手续费模型
-        None,                                        // 延迟模型
+        FeeModelAny::MakerTaker(MakerTakerFeeModel), // 手续费模型（按工具 maker/taker 费率）
+        latency_model,                               // 延迟模型
         None,                                        // 路由
         None,                                        // 拒绝止损订单
         None,                                        // 支持GTD订单
@@ -144,19 +509,38 @@ fn add_venue_config(engine: &mut BacktestEngine) -> Result<()> {
 }
 
 /// 添加交易工具
-fn add_instrument(engine: &mut BacktestEngine) -> Result<()> {
+fn add_instrument(engine: &mut BacktestEngine, fees: &FeeSchedule) -> Result<()> {
     // 使用 Nautilus 提供的测试工具创建 BTC/USDT 永续合约
-    let btcusdt_perp = crypto_perpetual_ethusdt(); // 这里使用 ETHUSDT 作为示例
+    let mut btcusdt_perp = crypto_perpetual_ethusdt(); // 这里使用 ETHUSDT 作为示例
+    // MakerTaker 手续费模型读取工具上的费率
+    btcusdt_perp.maker_fee = fees.maker_fee;
+    btcusdt_perp.taker_fee = fees.taker_fee;
+    info!("手续费率: maker {} / taker {}", fees.maker_fee, fees.taker_fee);
     engine.add_instrument(btcusdt_perp.into())?;
     Ok(())
 }
 
-/// 添加策略
-fn add_strategy(_engine: &mut BacktestEngine) -> Result<()> {
-    let _strategy = create_strategy();
-    // 这里需要根据 Nautilus 实际 API 来添加策略
-    info!("策略创建成功: AV-STO-001");
-    Ok(())
+/// 添加策略，返回策略ID
+fn add_strategy(engine: &mut BacktestEngine, config: &ASConfig) -> Result<StrategyId> {
+    let config = NautilusASConfig::new(config.clone());
+    let strategy_id = config
+        .strategy_config
+        .strategy_id
+        .ok_or_else(|| anyhow::anyhow!("策略配置缺少策略ID"))?;
+    engine.add_strategy(NautilusAvellanedaStoikov::try_new(config)?)?;
+    info!("策略已注册: {}", strategy_id);
+    Ok(strategy_id)
+}
+
+/// 从策略成交回报汇总手续费（找不到策略时为空）
+fn fee_summary(strategy_id: StrategyId, fees: &FeeSchedule) -> FeeSummary {
+    match try_get_actor_unchecked::<NautilusAvellanedaStoikov>(&strategy_id.inner()) {
+        Some(strategy) => FeeSummary::from_fills(fees, &strategy.fill_fee_stats()),
+        None => {
+            tracing::warn!("未找到策略 {}，手续费汇总为空", strategy_id);
+            FeeSummary::default()
+        }
+    }
 }
 
 /// 加载历史数据
@@ -174,14 +558,187 @@ fn run_backtest(engine: &mut BacktestEngine) -> Result<()> {
     Ok(())
 }
 
-/// 分析和打印回测结果
-fn analyze_results() -> Result<()> {
+/// 分析和打印回测结果，指定报告目录时写入报告文件
+fn analyze_results(
+    metrics: &BacktestMetrics,
+    config: &ASConfig,
+    options: &BacktestOptions,
+) -> Result<()> {
     info!("回测结果分析:");
-    info!("  - 总交易数: N/A");
-    info!("  - 总盈亏: N/A");
-    info!("  - 胜率: N/A");
-    info!("  - 最大回撤: N/A");
-    info!("  - 夏普比率: N/A");
+    info!("  - 总订单数: {}", metrics.total_orders);
+    info!("  - 总持仓数: {}", metrics.total_positions);
+    for (currency, stats) in &metrics.pnl {
+        for (name, value) in stats {
+            info!("  - [{}] {}: {:.6}", currency, name, value);
+        }
+    }
+    for (name, value) in metrics.returns.iter().chain(&metrics.general) {
+        info!("  - {}: {:.6}", name, value);
+    }
+    info!(
+        "  - 手续费: 成交回报 {:.6} / 按费率估算 {:.6}",
+        metrics.fees.reported_commission, metrics.fees.expected_commission
+    );
+
+    if let Some(dir) = &options.report_dir {
+        let (json_path, markdown_path) = write_report(dir, config, &options.fees, metrics)?;
+        info!("📝 回测报告已写入: {:?}, {:?}", json_path, markdown_path);
+    }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_metrics() -> BacktestMetrics {
+        BacktestMetrics {
+            total_orders: 120,
+            total_positions: 8,
+            iterations: 10_000,
+            elapsed_secs: 1.5,
+            pnl: BTreeMap::from([(
+                "USDT".to_string(),
+                BTreeMap::from([("PnL (total)".to_string(), 42.0)]),
+            )]),
+            returns: BTreeMap::from([("Sharpe Ratio (252 days)".to_string(), 1.2)]),
+            general: BTreeMap::from([("Win Rate".to_string(), 0.55)]),
+            fees: FeeSummary::default(),
+        }
+    }
+
+    #[test]
+    fn test_report_dir_option() {
+        let args = vec!["--report-dir".to_string(), "reports".to_string()];
+        let options = BacktestOptions::parse(&args, |_| None).unwrap();
+        assert_eq!(options.report_dir, Some(PathBuf::from("reports")));
+
+        let options = BacktestOptions::parse(&[], |key| {
+            (key == "BACKTEST_REPORT_DIR").then(|| "out".to_string())
+        })
+        .unwrap();
+        assert_eq!(options.report_dir, Some(PathBuf::from("out")));
+
+        assert_eq!(BacktestOptions::parse(&[], |_| None).unwrap().report_dir, None);
+    }
+
+    #[test]
+    fn test_fee_options() {
+        let options = BacktestOptions::parse(&[], |_| None).unwrap();
+        assert_eq!(options.fees, FeeSchedule::default());
+
+        let args = vec!["--maker-fee=-0.0001".to_string()];
+        let options = BacktestOptions::parse(&args, |key| {
+            (key == "TAKER_FEE").then(|| "0.0004".to_string())
+        })
+        .unwrap();
+        assert_eq!(options.fees.maker_fee, dec!(-0.0001));
+        assert_eq!(options.fees.taker_fee, dec!(0.0004));
+
+        assert!(BacktestOptions::parse(&["--taker-fee=abc".to_string()], |_| None).is_err());
+    }
+
+    #[test]
+    fn test_latency_options() {
+        let options = BacktestOptions::parse(&[], |_| None).unwrap();
+        assert_eq!(options.latency, LatencySpec::default());
+        assert!(options.latency.latency_model().is_none());
+
+        let args: Vec<String> = ["--submit-latency-ms", "20", "--cancel-latency-ms=15"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let options = BacktestOptions::parse(&args, |_| None).unwrap();
+        assert_eq!(options.latency.sample(), (20_000_000, 15_000_000));
+        assert!(options.latency.latency_model().is_some());
+    }
+
+    #[test]
+    fn test_latency_jitter_is_seeded() {
+        let spec = LatencySpec {
+            submit_ns: 10_000_000,
+            cancel_ns: 10_000_000,
+            jitter_ns: 5_000_000,
+            seed: 7,
+        };
+
+        let (submit_ns, cancel_ns) = spec.sample();
+        assert!((10_000_000..=15_000_000).contains(&submit_ns));
+        assert!((10_000_000..=15_000_000).contains(&cancel_ns));
+        // 相同种子结果可复现
+        assert_eq!(spec.sample(), (submit_ns, cancel_ns));
+    }
+
+    #[test]
+    fn test_maker_rebate_reduces_fees() {
+        // 做市策略的成交以 maker 为主，少量对冲为 taker
+        let fills = [
+            (dec!(3000), true),
+            (dec!(2500), true),
+            (dec!(4000), true),
+            (dec!(1000), false),
+        ];
+        let total_fees = |fees: FeeSchedule| -> Decimal {
+            fills
+                .iter()
+                .map(|&(notional, is_maker)| fees.commission(notional, is_maker))
+                .sum()
+        };
+
+        let positive = FeeSchedule::default();
+        let rebate = FeeSchedule {
+            maker_fee: dec!(-0.0001),
+            ..positive
+        };
+
+        assert_eq!(total_fees(positive), dec!(2.4));
+        assert_eq!(total_fees(rebate), dec!(-0.45));
+        assert!(total_fees(rebate) < total_fees(positive));
+
+        // 报告中按成交额估算的手续费与成交回报手续费对照
+        let stats = FillFeeStats {
+            maker_notional: 9500.0,
+            taker_notional: 1000.0,
+            commission: -0.45,
+        };
+        let summary = FeeSummary::from_fills(&rebate, &stats);
+        assert!((summary.expected_commission - summary.reported_commission).abs() < 1e-9);
+        assert_eq!(summary.maker_notional, 9500.0);
+    }
+
+    #[test]
+    fn test_write_report_files() {
+        let dir = std::env::temp_dir().join(format!("as-backtest-report-{}", std::process::id()));
+        let config = ASConfig::default();
+        let metrics = sample_metrics();
+
+        let fees = FeeSchedule::default();
+        let (json_path, markdown_path) = write_report(&dir, &config, &fees, &metrics).unwrap();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&json_path).unwrap()).unwrap();
+        for key in ["config", "fees", "metrics"] {
+            assert!(json.get(key).is_some(), "missing key {}", key);
+        }
+        for key in ["total_orders", "total_positions", "pnl", "returns", "general", "fees"] {
+            assert!(json["metrics"].get(key).is_some(), "missing metrics.{}", key);
+        }
+        assert_eq!(json["config"]["instrument_id"], config.instrument_id.as_str());
+        assert_eq!(json["metrics"]["pnl"]["USDT"]["PnL (total)"], 42.0);
+
+        let markdown = std::fs::read_to_string(&markdown_path).unwrap();
+        for section in [
+            "# Avellaneda-Stoikov 回测报告",
+            "## 概览",
+            "## 盈亏统计 (USDT)",
+            "## 手续费",
+            "## 收益统计",
+            "## 策略配置",
+        ] {
+            assert!(markdown.contains(section), "missing section {}", section);
+        }
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -6,6 +6,18 @@
 //! 用法:
 //! ```bash
 //! cargo run --release --bin live
+//!
+//! # 模拟运行：连接行情但不实际下单（也可设置 DRY_RUN=true）
+//! cargo run --release --bin live -- --dry-run
+//!
+//! # 选择产品类型: spot | usdt-future | coin-future（也可设置 BINANCE_PRODUCT）
+//! cargo run --release --bin live -- --product usdt-future
+//!
+//! # 行情看门狗：订单簿静默超过阈值时撤单并重新订阅
+//! cargo run --release --bin live -- --max-book-age-ms 5000 --watchdog-interval-ms 1000
+//!
+//! # 每日 UTC 21:30 停止双边报价并平仓（也可设置 FLATTEN_AT）
+//! cargo run --release --bin live -- --flatten-at 21:30
 //! ```
 
 use anyhow::Result;
@@ -18,7 +30,153 @@ use nautilus_live::builder::LiveNodeBuilder;
 use nautilus_model::identifiers::{AccountId, TraderId};
 use tracing::{info, warn};
 
-use nautilus_strategies_rust::strategies::nautilus_compatible::create_strategy;
+use nautilus_strategies_rust::strategies::avellaneda_stoikov::{
+    parse_utc_time_of_day, ASConfig,
+};
+use nautilus_strategies_rust::strategies::nautilus_compatible::{
+    NautilusASConfig, NautilusAvellanedaStoikov,
+};
+
+/// Binance 产品类型
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum ProductKind {
+    #[default]
+    Spot,
+    /// U本位永续/期货
+    UsdtFuture,
+    /// 币本位永续/期货
+    CoinFuture,
+}
+
+impl ProductKind {
+    fn parse(value: &str) -> Result<Self> {
+        match value.to_lowercase().replace('_', "-").as_str() {
+            "spot" => Ok(Self::Spot),
+            "usdt-future" | "usdt-futures" | "usdm" => Ok(Self::UsdtFuture),
+            "coin-future" | "coin-futures" | "coinm" => Ok(Self::CoinFuture),
+            other => Err(anyhow::anyhow!(
+                "未知产品类型: {} (可选: spot, usdt-future, coin-future)",
+                other
+            )),
+        }
+    }
+
+    fn binance_product_type(self) -> BinanceProductType {
+        match self {
+            Self::Spot => BinanceProductType::Spot,
+            Self::UsdtFuture => BinanceProductType::UsdM,
+            Self::CoinFuture => BinanceProductType::CoinM,
+        }
+    }
+
+    /// 校验产品类型与交易品种是否匹配
+    ///
+    /// Binance 永续合约品种以 `-PERP` 结尾，币本位合约以 USD 计价
+    fn validate_instrument(self, instrument_id: &str) -> Result<()> {
+        let symbol = instrument_id.split('.').next().unwrap_or(instrument_id);
+        let is_perp = symbol.ends_with("-PERP");
+        let is_coin_margined = symbol.trim_end_matches("-PERP").ends_with("USD");
+
+        let matches = match self {
+            Self::Spot => !is_perp,
+            Self::UsdtFuture => is_perp && !is_coin_margined,
+            Self::CoinFuture => is_perp && is_coin_margined,
+        };
+
+        if matches {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!(
+                "产品类型 {:?} 与交易品种 {} 不匹配",
+                self,
+                instrument_id
+            ))
+        }
+    }
+}
+
+/// 实盘运行选项（命令行参数优先于环境变量）
+#[derive(Debug, Clone, PartialEq)]
+struct LiveOptions {
+    /// 模拟运行：不注册执行客户端，策略只记录将要提交的订单
+    dry_run: bool,
+    /// Binance 产品类型
+    product: ProductKind,
+    /// 订单簿最大静默时间（纳秒），超时撤单并重新订阅
+    max_book_age_ns: u64,
+    /// 看门狗检查间隔（纳秒）
+    watchdog_interval_ns: u64,
+    /// 每日强制平仓时刻（HH:MM UTC）
+    flatten_at: Option<String>,
+}
+
+impl LiveOptions {
+    /// 从命令行参数和环境变量解析
+    fn parse<F>(args: &[String], env: F) -> Result<Self>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let dry_run = args.iter().any(|arg| arg == "--dry-run")
+            || env("DRY_RUN").is_some_and(|v| is_truthy(&v));
+
+        let product = match flag_value(args, "--product").or_else(|| env("BINANCE_PRODUCT")) {
+            Some(value) => ProductKind::parse(&value)?,
+            None => ProductKind::default(),
+        };
+
+        let max_book_age_ns = match flag_value(args, "--max-book-age-ms")
+            .or_else(|| env("MAX_BOOK_AGE_MS"))
+        {
+            Some(value) => parse_ms(&value)?,
+            None => DEFAULT_MAX_BOOK_AGE_NS,
+        };
+        let watchdog_interval_ns = match flag_value(args, "--watchdog-interval-ms")
+            .or_else(|| env("WATCHDOG_INTERVAL_MS"))
+        {
+            Some(value) => parse_ms(&value)?,
+            None => DEFAULT_WATCHDOG_INTERVAL_NS,
+        };
+
+        let flatten_at = flag_value(args, "--flatten-at").or_else(|| env("FLATTEN_AT"));
+
+        Ok(Self {
+            dry_run,
+            product,
+            max_book_age_ns,
+            watchdog_interval_ns,
+            flatten_at,
+        })
+    }
+}
+
+/// 默认订单簿最大静默时间: 10秒
+const DEFAULT_MAX_BOOK_AGE_NS: u64 = 10_000_000_000;
+/// 默认看门狗检查间隔: 1秒
+const DEFAULT_WATCHDOG_INTERVAL_NS: u64 = 1_000_000_000;
+
+/// 解析毫秒参数并转换为纳秒
+fn parse_ms(value: &str) -> Result<u64> {
+    let ms: u64 = value
+        .parse()
+        .map_err(|e| anyhow::anyhow!("无效的毫秒数 {}: {}", value, e))?;
+    Ok(ms * 1_000_000)
+}
+
+/// 读取 `--name value` 或 `--name=value` 形式的参数
+fn flag_value(args: &[String], name: &str) -> Option<String> {
+    let prefix = format!("{}=", name);
+    args.iter().enumerate().find_map(|(i, arg)| {
+        if arg == name {
+            args.get(i + 1).cloned()
+        } else {
+            arg.strip_prefix(&prefix).map(str::to_string)
+        }
+    })
+}
+
+fn is_truthy(value: &str) -> bool {
+    matches!(value.to_lowercase().as_str(), "1" | "true" | "yes")
+}
 
 fn main() -> Result<()> {
     // 加载环境变量 - 明确指定 .env 文件路径
@@ -56,17 +214,44 @@ fn main() -> Result<()> {
 
     info!("使用 Binance {} 环境", if is_testnet { "测试网" } else { "实盘" });
 
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let options = LiveOptions::parse(&args, |key| std::env::var(key).ok())?;
+    if options.dry_run {
+        warn!("模拟运行模式：不会提交任何真实订单");
+    }
+
     // 运行实盘交易
     tokio::runtime::Builder::new_current_thread()
         .enable_all()
         .build()?
-        .block_on(run_live(api_key.unwrap(), api_secret.unwrap(), is_testnet))
+        .block_on(run_live(api_key.unwrap(), api_secret.unwrap(), is_testnet, options))
 }
 
 /// 运行实盘交易
-async fn run_live(api_key: String, api_secret: String, is_testnet: bool) -> Result<()> {
+async fn run_live(
+    api_key: String,
+    api_secret: String,
+    is_testnet: bool,
+    options: LiveOptions,
+) -> Result<()> {
     info!("配置实盘交易引擎...");
 
+    let strategy_config = ASConfig::default();
+    options
+        .product
+        .validate_instrument(&strategy_config.instrument_id)?;
+    let product_type = options.product.binance_product_type();
+    info!("产品类型: {:?}", options.product);
+
+    let flatten_at_secs = options
+        .flatten_at
+        .as_deref()
+        .map(parse_utc_time_of_day)
+        .transpose()?;
+    if let Some(flatten_at) = &options.flatten_at {
+        info!("每日强制平仓时刻: {} UTC", flatten_at);
+    }
+
     // 创建实盘节点构建器
     let mut builder = LiveNodeBuilder::new(
         TraderId::from("TRADER-001"),
@@ -87,7 +272,7 @@ async fn run_live(api_key: String, api_secret: String, is_testnet: bool) -> Resu
 
     info!("配置 Binance 数据客户端...");
     let data_config = BinanceDataClientConfig {
-        product_types: vec![BinanceProductType::Spot],
+        product_types: vec![product_type],
         environment: if is_testnet { BinanceEnvironment::Testnet } else { BinanceEnvironment::Mainnet },
         api_key: Some(api_key.clone()),
         api_secret: Some(api_secret.clone()),
@@ -100,29 +285,41 @@ async fn run_live(api_key: String, api_secret: String, is_testnet: bool) -> Resu
         Box::new(data_config),
     )?;
 
-    info!("配置 Binance 执行客户端...");
-    let exec_config = BinanceExecClientConfig {
-        trader_id: TraderId::from("TRADER-001"),
-        account_id: AccountId::from("BINANCE-001"),
-        product_types: vec![BinanceProductType::Spot],
-        environment: if is_testnet { BinanceEnvironment::Testnet } else { BinanceEnvironment::Mainnet },
-        api_key: Some(api_key),
-        api_secret: Some(api_secret),
-        ..Default::default()
-    };
+    if options.dry_run {
+        info!("模拟运行：跳过 Binance 执行客户端");
+    } else {
+        info!("配置 Binance 执行客户端...");
+        let exec_config = BinanceExecClientConfig {
+            trader_id: TraderId::from("TRADER-001"),
+            account_id: AccountId::from("BINANCE-001"),
+            product_types: vec![product_type],
+            environment: if is_testnet { BinanceEnvironment::Testnet } else { BinanceEnvironment::Mainnet },
+            api_key: Some(api_key),
+            api_secret: Some(api_secret),
+            ..Default::default()
+        };
 
-    builder = builder.add_exec_client(
-        None,
-        Box::new(BinanceExecutionClientFactory::new()),
-        Box::new(exec_config),
-    )?;
+        builder = builder.add_exec_client(
+            None,
+            Box::new(BinanceExecutionClientFactory::new()),
+            Box::new(exec_config),
+        )?;
+    }
 
     info!("创建实盘节点实例...");
     let mut node = builder.build()?;
     info!("✅ 实盘节点创建成功");
 
     info!("添加 Avellaneda-Stoikov 策略...");
-    let strategy = create_strategy();
+    let strategy = NautilusAvellanedaStoikov::try_new(NautilusASConfig {
+        dry_run: options.dry_run,
+        max_book_age_ns: Some(options.max_book_age_ns),
+        watchdog_interval_ns: options.watchdog_interval_ns,
+        flatten_at_secs,
+        // 只减仓仅衍生品支持
+        reduce_only_hedges: options.product != ProductKind::Spot,
+        ..NautilusASConfig::new(strategy_config)
+    })?;
     node.add_strategy(strategy)?;
     info!("✅ 策略添加完成");
 
@@ -136,3 +333,98 @@ async fn run_live(api_key: String, api_secret: String, is_testnet: bool) -> Resu
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_dry_run_flag() {
+        let options = LiveOptions::parse(&args(&["--dry-run"]), |_| None).unwrap();
+        assert!(options.dry_run);
+
+        let options = LiveOptions::parse(&args(&[]), |_| None).unwrap();
+        assert!(!options.dry_run);
+    }
+
+    #[test]
+    fn test_flatten_at_option() {
+        let options = LiveOptions::parse(&args(&["--flatten-at", "21:30"]), |_| None).unwrap();
+        assert_eq!(options.flatten_at.as_deref(), Some("21:30"));
+
+        let options = LiveOptions::parse(&args(&[]), |key| {
+            (key == "FLATTEN_AT").then(|| "08:00".to_string())
+        })
+        .unwrap();
+        assert_eq!(options.flatten_at.as_deref(), Some("08:00"));
+
+        let options = LiveOptions::parse(&args(&[]), |_| None).unwrap();
+        assert_eq!(options.flatten_at, None);
+    }
+
+    #[test]
+    fn test_dry_run_env() {
+        let options = LiveOptions::parse(&args(&[]), |key| {
+            (key == "DRY_RUN").then(|| "true".to_string())
+        })
+        .unwrap();
+        assert!(options.dry_run);
+
+        let options = LiveOptions::parse(&args(&[]), |key| {
+            (key == "DRY_RUN").then(|| "false".to_string())
+        })
+        .unwrap();
+        assert!(!options.dry_run);
+    }
+
+    #[test]
+    fn test_product_parsing() {
+        let options = LiveOptions::parse(&args(&[]), |_| None).unwrap();
+        assert_eq!(options.product, ProductKind::Spot);
+
+        let options = LiveOptions::parse(&args(&["--product", "usdt-future"]), |_| None).unwrap();
+        assert_eq!(options.product, ProductKind::UsdtFuture);
+
+        let options = LiveOptions::parse(&args(&["--product=COIN_FUTURE"]), |_| None).unwrap();
+        assert_eq!(options.product, ProductKind::CoinFuture);
+
+        let options = LiveOptions::parse(&args(&[]), |key| {
+            (key == "BINANCE_PRODUCT").then(|| "usdm".to_string())
+        })
+        .unwrap();
+        assert_eq!(options.product, ProductKind::UsdtFuture);
+
+        assert!(LiveOptions::parse(&args(&["--product", "options"]), |_| None).is_err());
+    }
+
+    #[test]
+    fn test_watchdog_options() {
+        let options = LiveOptions::parse(&args(&[]), |_| None).unwrap();
+        assert_eq!(options.max_book_age_ns, DEFAULT_MAX_BOOK_AGE_NS);
+        assert_eq!(options.watchdog_interval_ns, DEFAULT_WATCHDOG_INTERVAL_NS);
+
+        let options = LiveOptions::parse(
+            &args(&["--max-book-age-ms", "2500", "--watchdog-interval-ms=500"]),
+            |_| None,
+        )
+        .unwrap();
+        assert_eq!(options.max_book_age_ns, 2_500_000_000);
+        assert_eq!(options.watchdog_interval_ns, 500_000_000);
+
+        assert!(LiveOptions::parse(&args(&["--max-book-age-ms", "abc"]), |_| None).is_err());
+    }
+
+    #[test]
+    fn test_product_instrument_validation() {
+        assert!(ProductKind::Spot.validate_instrument("BTCUSDT.BINANCE").is_ok());
+        assert!(ProductKind::Spot.validate_instrument("BTCUSDT-PERP.BINANCE").is_err());
+        assert!(ProductKind::UsdtFuture.validate_instrument("BTCUSDT-PERP.BINANCE").is_ok());
+        assert!(ProductKind::UsdtFuture.validate_instrument("BTCUSDT.BINANCE").is_err());
+        assert!(ProductKind::CoinFuture.validate_instrument("BTCUSD-PERP.BINANCE").is_ok());
+        assert!(ProductKind::CoinFuture.validate_instrument("BTCUSDT-PERP.BINANCE").is_err());
+    }
+}
@@ -0,0 +1,317 @@
+//! 报价计算工具
+//!
+//! 给定策略配置、最优买卖价和当前库存，计算并打印一次 Avellaneda-Stoikov 报价，
+//! 用于快速检查参数是否合理，无需运行完整回测
+//!
+//! 用法:
+//! ```bash
+//! cargo run --release --bin quote -- --bid 50000 --ask 50010 --inventory 0.02
+//!
+//! # 从 JSON 配置文件加载参数（格式与 ASConfig 序列化格式一致）
+//! cargo run --release --bin quote -- --config as_config.json --bid 50000 --ask 50010
+//!
+//! # 不指定配置文件时，`ASConfig::from_env_with` 读取的配置字段都可以作为参数传入
+//! # （字段名中的 `_` 写作 `-`），未知参数报错
+//! cargo run --release --bin quote -- --bid 50000 --ask 50010 --risk-aversion 0.2 --max-spread-bps 50
+//! ```
+
+use anyhow::Result;
+use nautilus_core::UnixNanos;
+use std::cell::RefCell;
+use std::path::PathBuf;
+
+use nautilus_strategies_rust::strategies::avellaneda_stoikov::{
+    ASConfig, AvellanedaStoikov, OrderBookSnapshot, QuoteUpdate,
+};
+
+/// 命令行参数中配置字段的前缀（对应 `ASConfig::from_env_with` 的键前缀）
+const CONFIG_PREFIX: &str = "AS";
+
+/// 不属于配置字段的参数
+const OPTION_FLAGS: [&str; 4] = ["--config", "--bid", "--ask", "--inventory"];
+
+/// 计算报价使用的行情时间戳（纳秒）
+const QUOTE_TS_NS: u64 = 1_000_000_000;
+
+/// 报价计算输入
+#[derive(Debug, Clone, PartialEq)]
+struct QuoteOptions {
+    /// JSON 配置文件路径（None 表示由命令行参数构造配置）
+    config_path: Option<PathBuf>,
+    /// 最优买价
+    bid: f64,
+    /// 最优卖价
+    ask: f64,
+    /// 当前库存（正数为多头，负数为空头）
+    inventory: f64,
+}
+
+impl QuoteOptions {
+    /// 从命令行参数解析
+    fn parse(args: &[String]) -> Result<Self> {
+        check_flags(args)?;
+
+        let price = |name: &str| -> Result<f64> {
+            let value =
+                flag_value(args, name).ok_or_else(|| anyhow::anyhow!("缺少参数 {}", name))?;
+            parse_f64(name, &value)
+        };
+        let bid = price("--bid")?;
+        let ask = price("--ask")?;
+        if !(bid > 0.0 && bid < ask) {
+            return Err(anyhow::anyhow!("无效的盘口: 买价 {} / 卖价 {}", bid, ask));
+        }
+
+        let inventory = match flag_value(args, "--inventory") {
+            Some(value) => parse_f64("--inventory", &value)?,
+            None => 0.0,
+        };
+
+        Ok(Self {
+            config_path: flag_value(args, "--config").map(PathBuf::from),
+            bid,
+            ask,
+            inventory,
+        })
+    }
+
+    /// 加载策略配置：指定配置文件时从文件加载，否则由命令行参数覆盖默认值，均会校验
+    fn load_config(&self, args: &[String]) -> Result<ASConfig> {
+        let config = match &self.config_path {
+            Some(path) => ASConfig::from_file(path)?,
+            None => {
+                ASConfig::from_env_with(CONFIG_PREFIX, |key| flag_value(args, &config_flag(key)))?
+            }
+        };
+        Ok(config)
+    }
+}
+
+/// `ASConfig::from_env_with` 读取的全部配置字段对应的命令行参数名
+fn config_flags() -> Vec<String> {
+    let flags = RefCell::new(Vec::new());
+    // 默认配置总能通过校验，这里只记录读取了哪些键
+    let _ = ASConfig::from_env_with(CONFIG_PREFIX, |key| {
+        flags.borrow_mut().push(config_flag(key));
+        None
+    });
+    flags.into_inner()
+}
+
+/// 拒绝未知参数，以及与 `--config` 同时传入的配置字段参数（两者都会被静默忽略）
+fn check_flags(args: &[String]) -> Result<()> {
+    let config_flags = config_flags();
+    let has_config_file = flag_value(args, "--config").is_some();
+    for arg in args.iter().filter(|arg| arg.starts_with("--")) {
+        let name = arg.split_once('=').map_or(arg.as_str(), |(name, _)| name);
+        if OPTION_FLAGS.contains(&name) {
+            continue;
+        }
+        if !config_flags.iter().any(|flag| flag == name) {
+            return Err(anyhow::anyhow!("未知参数 {}", name));
+        }
+        if has_config_file {
+            return Err(anyhow::anyhow!(
+                "指定 --config 时不能再传入配置参数 {}",
+                name
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// 配置键（如 `AS_RISK_AVERSION`）对应的命令行参数名（`--risk-aversion`）
+fn config_flag(key: &str) -> String {
+    let field = key
+        .strip_prefix(CONFIG_PREFIX)
+        .unwrap_or(key)
+        .trim_start_matches('_');
+    format!("--{}", field.to_lowercase().replace('_', "-"))
+}
+
+fn parse_f64(name: &str, value: &str) -> Result<f64> {
+    value
+        .parse()
+        .map_err(|e| anyhow::anyhow!("无效的数值 {} {}: {}", name, value, e))
+}
+
+/// 读取 `--name value` 或 `--name=value` 形式的参数
+fn flag_value(args: &[String], name: &str) -> Option<String> {
+    let prefix = format!("{}=", name);
+    args.iter().enumerate().find_map(|(i, arg)| {
+        if arg == name {
+            args.get(i + 1).cloned()
+        } else {
+            arg.strip_prefix(&prefix).map(str::to_string)
+        }
+    })
+}
+
+/// 按给定盘口和库存计算一次报价
+fn compute_quote(config: ASConfig, options: &QuoteOptions) -> Result<QuoteUpdate> {
+    // 单次计算，节流没有意义（否则同一时刻的第二次更新会被跳过）
+    let config = ASConfig {
+        min_quote_interval_ns: 0,
+        ..config
+    };
+    let mut strategy = AvellanedaStoikov::new(config);
+    let snapshot = OrderBookSnapshot {
+        best_bid: options.bid,
+        best_ask: options.ask,
+        bid_volume: 1.0,
+        ask_volume: 1.0,
+        timestamp_ns: UnixNanos::new(QUOTE_TS_NS),
+    };
+
+    // 首次更新建立中间价和行情时间，再按中间价设置库存（刚建仓，不触发成交后冷却）
+    strategy.on_orderbook_update(&snapshot);
+    strategy.set_inventory(options.inventory, (options.bid + options.ask) * 0.5);
+
+    strategy
+        .on_orderbook_update(&snapshot)
+        .ok_or_else(|| anyhow::anyhow!("当前参数下不报价（可能触发熔断或价格带保护）"))
+}
+
+fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let options = QuoteOptions::parse(&args)?;
+    let config = options.load_config(&args)?;
+    let quote = compute_quote(config, &options)?;
+
+    println!(
+        "买 {:.2} x {} / 卖 {:.2} x {}",
+        quote.bid_price, quote.bid_size, quote.ask_price, quote.ask_size
+    );
+    println!(
+        "保留价格 {:.2} | 模型价差 {:.4} ({:.2} bps) | 距中间价 买 {:.2} bps / 卖 {:.2} bps",
+        quote.reservation_price,
+        quote.spread,
+        quote.spread_bps,
+        quote.bid_distance_bps,
+        quote.ask_distance_bps
+    );
+    println!("{}", serde_json::to_string_pretty(&quote)?);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn argv(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let args = argv(&["--bid", "50000", "--ask=50010", "--inventory", "-0.02"]);
+        let options = QuoteOptions::parse(&args).unwrap();
+        assert_eq!(
+            options,
+            QuoteOptions {
+                config_path: None,
+                bid: 50000.0,
+                ask: 50010.0,
+                inventory: -0.02,
+            }
+        );
+
+        let with_file =
+            QuoteOptions::parse(&argv(&["--config", "as.json", "--bid", "1", "--ask", "2"]))
+                .unwrap();
+        assert_eq!(with_file.config_path, Some(PathBuf::from("as.json")));
+        assert_eq!(with_file.inventory, 0.0);
+
+        // 缺少价格、无效数值或交叉盘口均报错
+        assert!(QuoteOptions::parse(&argv(&["--bid", "50000"])).is_err());
+        assert!(QuoteOptions::parse(&argv(&["--bid", "x", "--ask", "1"])).is_err());
+        assert!(QuoteOptions::parse(&argv(&["--bid", "10", "--ask", "9"])).is_err());
+    }
+
+    #[test]
+    fn test_config_from_cli_flags() {
+        assert_eq!(config_flag("AS_RISK_AVERSION"), "--risk-aversion");
+
+        let args = argv(&[
+            "--bid",
+            "50000",
+            "--ask",
+            "50010",
+            "--risk-aversion",
+            "0.2",
+            "--max-spread-bps=50",
+        ]);
+        let config = QuoteOptions::parse(&args)
+            .unwrap()
+            .load_config(&args)
+            .unwrap();
+        assert_eq!(config.risk_aversion, 0.2);
+        assert_eq!(config.max_spread_bps, 50.0);
+        assert_eq!(config.time_horizon, ASConfig::default().time_horizon);
+
+        let args = argv(&["--bid", "1", "--ask", "2", "--risk-aversion", "-1"]);
+        assert!(QuoteOptions::parse(&args)
+            .unwrap()
+            .load_config(&args)
+            .is_err());
+    }
+
+    #[test]
+    fn test_unknown_flag_rejected() {
+        let flags = config_flags();
+        assert!(flags.contains(&"--risk-aversion".to_string()));
+        assert!(flags.contains(&"--max-long-inventory".to_string()));
+
+        // 拼写错误或不支持的字段
+        let args = argv(&["--bid", "1", "--ask", "2", "--risk-aversoin", "0.2"]);
+        assert!(QuoteOptions::parse(&args).is_err());
+        let args = argv(&["--bid", "1", "--ask", "2", "--no-such-field=1"]);
+        assert!(QuoteOptions::parse(&args).is_err());
+
+        // 配置文件与配置参数同时指定
+        let args = argv(&[
+            "--config",
+            "as.json",
+            "--bid",
+            "1",
+            "--ask",
+            "2",
+            "--risk-aversion",
+            "0.2",
+        ]);
+        assert!(QuoteOptions::parse(&args).is_err());
+    }
+
+    #[test]
+    fn test_sample_quote() {
+        let options = QuoteOptions {
+            config_path: None,
+            bid: 50000.0,
+            ask: 50010.0,
+            inventory: 0.0,
+        };
+        let flat = compute_quote(ASConfig::default(), &options).unwrap();
+        assert!(flat.bid_price > 0.0 && flat.bid_price < flat.ask_price);
+        assert!(flat.bid_size > 0.0 && flat.ask_size > 0.0);
+
+        // 多头库存：报价下移
+        let long_options = QuoteOptions {
+            inventory: 0.02,
+            ..options
+        };
+        let long = compute_quote(ASConfig::default(), &long_options).unwrap();
+        assert!(long.reservation_price < flat.reservation_price);
+        assert!(long.bid_distance_bps > long.ask_distance_bps);
+
+        // 建仓不触发成交后冷却，持仓时长不受影响
+        let config = ASConfig {
+            post_fill_cooldown_ns: 60_000_000_000,
+            skew_time_decay: 0.01,
+            ..ASConfig::default()
+        };
+        let cooled = compute_quote(config, &long_options).unwrap();
+        assert!(cooled.bid_size > 0.0 && cooled.ask_size > 0.0);
+        assert_eq!(cooled.reservation_price, long.reservation_price);
+    }
+}
@@ -0,0 +1,53 @@
+//! 策略错误类型
+//!
+//! 库接口返回 `StrategyError`，便于调用方按错误类型分别处理；
+//! 二进制程序可直接用 `?` 转换为 `anyhow::Error`。
+
+use thiserror::Error;
+
+/// 策略错误
+#[derive(Debug, Error)]
+pub enum StrategyError {
+    /// 配置参数无效
+    #[error("invalid config: {0}")]
+    InvalidConfig(String),
+    /// 交易品种ID无法解析
+    #[error("invalid instrument id: {0:?}")]
+    InstrumentParse(String),
+    /// 订单工厂不可用（策略尚未注册到交易节点）
+    #[error("order factory not available")]
+    OrderFactoryUnavailable,
+    /// 文件读写失败
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    /// 序列化/反序列化失败
+    #[error("serialization error: {0}")]
+    Serde(String),
+}
+
+impl From<serde_json::Error> for StrategyError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Serde(e.to_string())
+    }
+}
+
+impl From<bincode::Error> for StrategyError {
+    fn from(e: bincode::Error) -> Self {
+        Self::Serde(e.to_string())
+    }
+}
+
+/// 策略操作结果
+pub type StrategyResult<T> = Result<T, StrategyError>;
+
+/// 条件不满足时返回 `StrategyError::InvalidConfig`
+macro_rules! ensure_config {
+    ($cond:expr, $($arg:tt)+) => {
+        let satisfied: bool = $cond;
+        if !satisfied {
+            return Err($crate::error::StrategyError::InvalidConfig(format!($($arg)+)));
+        }
+    };
+}
+
+pub(crate) use ensure_config;
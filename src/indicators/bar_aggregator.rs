@@ -0,0 +1,135 @@
+//! K线聚合器 - 将逐笔成交/盘口数据按时间聚合为OHLCV K线
+
+use crate::strategies::avellaneda_stoikov::{Bar, OrderBookSnapshot};
+
+/// 时间驱动的K线聚合器
+///
+/// 时间戳落在 `[start, start + interval_ns)` 的数据归入同一根K线，
+/// 出现下一区间的数据时输出已完成的K线（时间戳为K线收盘时间）
+#[derive(Debug, Clone)]
+pub struct BarAggregator {
+    interval_ns: u64,
+    /// 当前K线起始时间
+    bar_start_ns: Option<u64>,
+    open: f64,
+    high: f64,
+    low: f64,
+    close: f64,
+    volume: f64,
+}
+
+impl BarAggregator {
+    pub fn new(interval_ns: u64) -> Self {
+        assert!(interval_ns > 0, "interval_ns must be positive");
+        Self {
+            interval_ns,
+            bar_start_ns: None,
+            open: 0.0,
+            high: 0.0,
+            low: 0.0,
+            close: 0.0,
+            volume: 0.0,
+        }
+    }
+
+    /// 处理一笔成交，区间滚动时返回已完成的K线
+    #[inline]
+    pub fn update(&mut self, price: f64, volume: f64, timestamp_ns: u64) -> Option<Bar> {
+        let start_ns = timestamp_ns - timestamp_ns % self.interval_ns;
+
+        let completed = match self.bar_start_ns {
+            Some(current) if start_ns > current => self.build_bar(),
+            Some(_) => {
+                self.high = self.high.max(price);
+                self.low = self.low.min(price);
+                self.close = price;
+                self.volume += volume;
+                return None;
+            }
+            None => None,
+        };
+
+        self.bar_start_ns = Some(start_ns);
+        self.open = price;
+        self.high = price;
+        self.low = price;
+        self.close = price;
+        self.volume = volume;
+
+        completed
+    }
+
+    /// 以盘口中间价作为价格更新（成交量为0）
+    #[inline]
+    pub fn update_book(&mut self, snapshot: &OrderBookSnapshot) -> Option<Bar> {
+        let mid = (snapshot.best_bid + snapshot.best_ask) * 0.5;
+        self.update(mid, 0.0, snapshot.timestamp_ns.as_u64())
+    }
+
+    /// 输出当前未完成的K线并清空
+    pub fn flush(&mut self) -> Option<Bar> {
+        let bar = self.build_bar();
+        self.bar_start_ns = None;
+        bar
+    }
+
+    fn build_bar(&self) -> Option<Bar> {
+        self.bar_start_ns.map(|start_ns| Bar {
+            open: self.open,
+            high: self.high,
+            low: self.low,
+            close: self.close,
+            volume: self.volume,
+            timestamp_ns: start_ns + self.interval_ns,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE: u64 = 60_000_000_000;
+
+    #[test]
+    fn test_aggregates_two_intervals() {
+        let mut aggregator = BarAggregator::new(MINUTE);
+        let mut bars = Vec::new();
+
+        let ticks = [
+            (100.0, 1.0, 0),
+            (105.0, 2.0, 10_000_000_000),
+            (98.0, 1.0, 30_000_000_000),
+            (101.0, 1.0, 59_000_000_000),
+            (101.5, 1.0, MINUTE),
+            (103.0, 3.0, MINUTE + 20_000_000_000),
+            (99.5, 1.0, MINUTE + 40_000_000_000),
+            (102.0, 1.0, 2 * MINUTE + 1),
+        ];
+        for (price, volume, ts) in ticks {
+            bars.extend(aggregator.update(price, volume, ts));
+        }
+
+        assert_eq!(bars.len(), 2);
+
+        let first = bars[0];
+        assert_eq!(
+            (first.open, first.high, first.low, first.close),
+            (100.0, 105.0, 98.0, 101.0)
+        );
+        assert_eq!(first.volume, 5.0);
+        assert_eq!(first.timestamp_ns, MINUTE);
+
+        let second = bars[1];
+        assert_eq!(
+            (second.open, second.high, second.low, second.close),
+            (101.5, 103.0, 99.5, 99.5)
+        );
+        assert_eq!(second.volume, 5.0);
+        assert_eq!(second.timestamp_ns, 2 * MINUTE);
+
+        let partial = aggregator.flush().unwrap();
+        assert_eq!(partial.open, 102.0);
+        assert!(aggregator.flush().is_none());
+    }
+}
@@ -1,5 +1,11 @@
 //! 技术指标模块
 
+pub mod bar_aggregator;
+pub mod ofi;
 pub mod volatility;
+pub mod vwap;
 
+pub use bar_aggregator::*;
+pub use ofi::*;
 pub use volatility::*;
+pub use vwap::*;
@@ -0,0 +1,95 @@
+//! 订单流不平衡（OFI, Order Flow Imbalance）
+//!
+//! Cont, Kukanov & Stoikov (2014)：由相邻两次盘口快照的最优价和挂单量变化计算，
+//! 正值表示买方压力（买一增加/卖一减少），是常用的短周期价格预测因子。
+
+use crate::strategies::avellaneda_stoikov::OrderBookSnapshot;
+
+/// 订单流不平衡指标（累计自上次重置以来的 OFI）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OrderFlowImbalance {
+    cumulative: f64,
+}
+
+impl OrderFlowImbalance {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 计算相邻快照之间的 OFI 并累加，返回本次增量（无前一快照时为0）
+    ///
+    /// e = 1{Pb ≥ Pb'}·qb − 1{Pb ≤ Pb'}·qb' − 1{Pa ≤ Pa'}·qa + 1{Pa ≥ Pa'}·qa'
+    /// 其中带 ' 的为前一快照
+    #[inline]
+    pub fn update(&mut self, prev: Option<&OrderBookSnapshot>, curr: &OrderBookSnapshot) -> f64 {
+        let Some(prev) = prev else {
+            return 0.0;
+        };
+
+        let mut ofi = 0.0;
+        if curr.best_bid >= prev.best_bid {
+            ofi += curr.bid_volume;
+        }
+        if curr.best_bid <= prev.best_bid {
+            ofi -= prev.bid_volume;
+        }
+        if curr.best_ask <= prev.best_ask {
+            ofi -= curr.ask_volume;
+        }
+        if curr.best_ask >= prev.best_ask {
+            ofi += prev.ask_volume;
+        }
+
+        self.cumulative += ofi;
+        ofi
+    }
+
+    /// 累计 OFI
+    pub fn cumulative(&self) -> f64 {
+        self.cumulative
+    }
+
+    /// 清零累计值
+    pub fn reset(&mut self) {
+        self.cumulative = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nautilus_core::UnixNanos;
+
+    fn snapshot(bid: f64, bid_volume: f64, ask: f64, ask_volume: f64) -> OrderBookSnapshot {
+        OrderBookSnapshot {
+            best_bid: bid,
+            best_ask: ask,
+            bid_volume,
+            ask_volume,
+            timestamp_ns: UnixNanos::new(0),
+        }
+    }
+
+    #[test]
+    fn test_ofi_sign() {
+        let mut ofi = OrderFlowImbalance::new();
+        let first = snapshot(100.0, 5.0, 101.0, 5.0);
+        assert_eq!(ofi.update(None, &first), 0.0);
+
+        // 价格不变：买一加量、卖一减量 -> 买方压力
+        let buying = snapshot(100.0, 8.0, 101.0, 3.0);
+        assert_eq!(ofi.update(Some(&first), &buying), 5.0);
+
+        // 买一价上移 -> 新买一量全部计入
+        let bid_up = snapshot(100.5, 2.0, 101.0, 3.0);
+        assert_eq!(ofi.update(Some(&buying), &bid_up), 2.0);
+
+        // 卖一价下移 -> 卖方压力
+        let ask_down = snapshot(100.5, 2.0, 100.8, 4.0);
+        assert_eq!(ofi.update(Some(&bid_up), &ask_down), -4.0);
+
+        assert_eq!(ofi.cumulative(), 3.0);
+        ofi.reset();
+        assert_eq!(ofi.cumulative(), 0.0);
+    }
+}
@@ -0,0 +1,107 @@
+//! 滚动成交量加权平均价（VWAP）
+//!
+//! 按时间窗口累计成交的 价格×数量 与数量，窗口外的成交从队首淘汰（O(1) 均摊）。
+//! 变化比中间价慢，可作为参考价格或公允价值锚点。
+
+use std::collections::VecDeque;
+
+/// 时间窗口滚动 VWAP
+#[derive(Debug, Clone)]
+pub struct RollingVwap {
+    window_ns: u64,
+    /// 窗口内的成交 (时间戳纳秒, 价格, 数量)，按时间顺序
+    trades: VecDeque<(u64, f64, f64)>,
+    /// 窗口内 Σ价格×数量
+    notional: f64,
+    /// 窗口内 Σ数量
+    volume: f64,
+}
+
+impl RollingVwap {
+    pub fn new(window_ns: u64) -> Self {
+        Self {
+            window_ns,
+            trades: VecDeque::new(),
+            notional: 0.0,
+            volume: 0.0,
+        }
+    }
+
+    /// 记录一笔成交（非正价格或数量忽略），并淘汰窗口外的成交
+    pub fn update(&mut self, timestamp_ns: u64, price: f64, volume: f64) {
+        if price.is_finite() && volume.is_finite() && price > 0.0 && volume > 0.0 {
+            self.trades.push_back((timestamp_ns, price, volume));
+            self.notional += price * volume;
+            self.volume += volume;
+        }
+        self.advance(timestamp_ns);
+    }
+
+    /// 推进到当前时间：淘汰距今不少于 `window_ns` 的成交
+    pub fn advance(&mut self, now_ns: u64) {
+        while let Some(&(timestamp_ns, price, volume)) = self.trades.front() {
+            if now_ns.saturating_sub(timestamp_ns) < self.window_ns {
+                break;
+            }
+            self.notional -= price * volume;
+            self.volume -= volume;
+            self.trades.pop_front();
+        }
+        // 窗口清空时归零，避免浮点减法累积误差
+        if self.trades.is_empty() {
+            self.notional = 0.0;
+            self.volume = 0.0;
+        }
+    }
+
+    /// 当前 VWAP（窗口内无成交时为 None）
+    pub fn value(&self) -> Option<f64> {
+        (self.volume > 0.0).then(|| self.notional / self.volume)
+    }
+
+    /// 窗口内成交笔数
+    pub fn len(&self) -> usize {
+        self.trades.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.trades.is_empty()
+    }
+
+    pub fn reset(&mut self) {
+        self.trades.clear();
+        self.notional = 0.0;
+        self.volume = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECOND: u64 = 1_000_000_000;
+
+    #[test]
+    fn test_window_eviction() {
+        let mut vwap = RollingVwap::new(10 * SECOND);
+        assert_eq!(vwap.value(), None);
+
+        vwap.update(SECOND, 100.0, 1.0);
+        vwap.update(5 * SECOND, 102.0, 3.0);
+        assert_eq!(vwap.value(), Some((100.0 + 306.0) / 4.0));
+
+        // 第1秒的成交在第11秒滑出窗口
+        vwap.update(11 * SECOND, 104.0, 1.0);
+        assert_eq!(vwap.len(), 2);
+        assert_eq!(vwap.value(), Some((306.0 + 104.0) / 4.0));
+
+        vwap.advance(14 * SECOND);
+        assert_eq!(vwap.value(), Some((306.0 + 104.0) / 4.0));
+        vwap.advance(15 * SECOND);
+        assert_eq!(vwap.value(), Some(104.0));
+
+        vwap.advance(30 * SECOND);
+        assert!(vwap.is_empty());
+        assert_eq!(vwap.value(), None);
+    }
+}
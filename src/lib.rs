@@ -3,11 +3,18 @@
 //! 基于 Nautilus Trader 框架的高性能交易策略实现。
 //! 复用 Nautilus 的回测引擎、实盘引擎和交易所适配器。
 
+pub mod error;
 pub mod strategies;
 pub mod indicators;
+pub mod ring_buffer;
+pub mod synthetic;
+
+#[cfg(feature = "metrics")]
+pub mod metrics;
 
 // 导出主要组件
 pub use strategies::avellaneda_stoikov::{AvellanedaStoikov, ASConfig};
+pub use error::{StrategyError, StrategyResult};
 
 
 /// 库版本
@@ -0,0 +1,86 @@
+//! Prometheus 指标导出（需启用 `metrics` feature）
+//!
+//! 将策略计数器与市场状态通过 `metrics` crate 暴露，
+//! 由调用方安装的 Recorder（如 Prometheus exporter）负责采集。
+
+use crate::strategies::avellaneda_stoikov::AvellanedaStoikov;
+use metrics::{describe_counter, describe_gauge, Recorder};
+
+/// 报价更新次数
+pub const QUOTE_UPDATES: &str = "as_quote_updates_total";
+/// 订单簿更新次数
+pub const ORDERBOOK_UPDATES: &str = "as_orderbook_updates_total";
+/// 库存调整次数
+pub const INVENTORY_ADJUSTMENTS: &str = "as_inventory_adjustments_total";
+/// 当前库存
+pub const INVENTORY: &str = "as_inventory";
+/// 当前波动率
+pub const VOLATILITY: &str = "as_volatility";
+/// 当前最优价差
+pub const SPREAD: &str = "as_spread";
+
+impl AvellanedaStoikov {
+    /// 向指定 Recorder 注册并写入当前指标
+    ///
+    /// 数值取自 `get_stats`，可在每次采集前重复调用以刷新
+    pub fn register_metrics(&self, recorder: &dyn Recorder) {
+        let stats = self.get_stats();
+        let spread = self.optimal_spread();
+
+        metrics::with_local_recorder(recorder, || {
+            describe_counter!(QUOTE_UPDATES, "Number of quote updates computed");
+            describe_counter!(ORDERBOOK_UPDATES, "Number of order book updates processed");
+            describe_counter!(
+                INVENTORY_ADJUSTMENTS,
+                "Number of fills applied to inventory"
+            );
+            describe_gauge!(INVENTORY, "Current inventory in base units");
+            describe_gauge!(VOLATILITY, "Current volatility estimate");
+            describe_gauge!(SPREAD, "Current optimal spread in price units");
+
+            metrics::counter!(QUOTE_UPDATES).absolute(stats.quote_updates);
+            metrics::counter!(ORDERBOOK_UPDATES).absolute(stats.orderbook_updates);
+            metrics::counter!(INVENTORY_ADJUSTMENTS).absolute(stats.inventory_adjustments);
+            metrics::gauge!(INVENTORY).set(stats.current_inventory);
+            metrics::gauge!(VOLATILITY).set(stats.current_volatility);
+            metrics::gauge!(SPREAD).set(spread);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategies::avellaneda_stoikov::{ASConfig, OrderBookSnapshot};
+    use metrics_exporter_prometheus::PrometheusBuilder;
+    use nautilus_core::UnixNanos;
+
+    #[test]
+    fn test_register_metrics_exports_names() {
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+
+        let mut strategy = AvellanedaStoikov::new(ASConfig::default());
+        strategy.on_orderbook_update(&OrderBookSnapshot {
+            best_bid: 50000.0,
+            best_ask: 50010.0,
+            bid_volume: 1.0,
+            ask_volume: 1.0,
+            timestamp_ns: UnixNanos::new(1_000_000_000),
+        });
+        strategy.register_metrics(&recorder);
+
+        let rendered = handle.render();
+        for name in [
+            QUOTE_UPDATES,
+            ORDERBOOK_UPDATES,
+            INVENTORY_ADJUSTMENTS,
+            INVENTORY,
+            VOLATILITY,
+            SPREAD,
+        ] {
+            assert!(rendered.contains(name), "missing metric {name}");
+        }
+        assert!(rendered.contains("as_quote_updates_total 1"));
+    }
+}
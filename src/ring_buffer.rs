@@ -0,0 +1,194 @@
+//! 定长环形缓冲区
+//!
+//! 预分配固定容量，写满后 O(1) 覆盖最旧元素，热路径上无分配、无元素移动。
+
+/// 定长环形缓冲区
+#[derive(Debug, Clone)]
+pub struct RingBuffer<T> {
+    buffer: Box<[T]>,
+    /// 最旧元素的位置
+    head: usize,
+    len: usize,
+}
+
+impl<T: Copy + Default> RingBuffer<T> {
+    /// 创建指定容量的缓冲区（容量为0时不保存任何元素）
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            buffer: vec![T::default(); capacity].into_boxed_slice(),
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// 写入新元素，缓冲区已满时覆盖并返回最旧元素
+    #[inline]
+    pub fn push(&mut self, value: T) -> Option<T> {
+        let capacity = self.capacity();
+        if capacity == 0 {
+            return Some(value);
+        }
+
+        if self.len < capacity {
+            self.buffer[(self.head + self.len) % capacity] = value;
+            self.len += 1;
+            None
+        } else {
+            let evicted = std::mem::replace(&mut self.buffer[self.head], value);
+            self.head = (self.head + 1) % capacity;
+            Some(evicted)
+        }
+    }
+
+    /// 调整容量，保留最新的元素（缩容时丢弃最旧的元素）
+    pub fn resize(&mut self, capacity: usize) {
+        if capacity == self.capacity() {
+            return;
+        }
+
+        let mut resized = Self::new(capacity);
+        let skip = self.len.saturating_sub(capacity);
+        resized.extend(self.iter().skip(skip).copied());
+        *self = resized;
+    }
+}
+
+impl<T> RingBuffer<T> {
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.buffer.len()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.len == self.capacity()
+    }
+
+    /// 清空缓冲区（不释放内存）
+    #[inline]
+    pub fn clear(&mut self) {
+        self.head = 0;
+        self.len = 0;
+    }
+
+    /// 按从旧到新的顺序返回两段连续切片
+    #[inline]
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let end = self.head + self.len;
+        if end <= self.capacity() {
+            (&self.buffer[self.head..end], &[])
+        } else {
+            (
+                &self.buffer[self.head..],
+                &self.buffer[..end - self.capacity()],
+            )
+        }
+    }
+
+    /// 按从旧到新的顺序迭代
+    #[inline]
+    pub fn iter(&self) -> std::iter::Chain<std::slice::Iter<'_, T>, std::slice::Iter<'_, T>> {
+        let (front, back) = self.as_slices();
+        front.iter().chain(back.iter())
+    }
+}
+
+impl<'a, T> IntoIterator for &'a RingBuffer<T> {
+    type Item = &'a T;
+    type IntoIter = std::iter::Chain<std::slice::Iter<'a, T>, std::slice::Iter<'a, T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: Copy + Default> Extend<T> for RingBuffer<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    #[test]
+    fn test_push_and_evict() {
+        let mut buffer = RingBuffer::new(3);
+        assert!(buffer.is_empty());
+
+        assert_eq!(buffer.push(1.0), None);
+        assert_eq!(buffer.push(2.0), None);
+        assert_eq!(buffer.push(3.0), None);
+        assert!(buffer.is_full());
+
+        assert_eq!(buffer.push(4.0), Some(1.0));
+        assert_eq!(buffer.len(), 3);
+        assert_eq!(
+            buffer.iter().copied().collect::<Vec<_>>(),
+            vec![2.0, 3.0, 4.0]
+        );
+
+        buffer.clear();
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.iter().count(), 0);
+    }
+
+    #[test]
+    fn test_matches_vecdeque_window() {
+        let window = 5;
+        let mut buffer = RingBuffer::new(window);
+        let mut deque = VecDeque::with_capacity(window);
+
+        for i in 0..23 {
+            let value = (i as f64).sin();
+            buffer.push(value);
+            deque.push_back(value);
+            if deque.len() > window {
+                deque.pop_front();
+            }
+
+            assert!(buffer.iter().eq(deque.iter()));
+            // 相邻元素配对（收益率计算方式）也保持一致
+            assert!(buffer
+                .iter()
+                .zip(buffer.iter().skip(1))
+                .eq(deque.iter().zip(deque.iter().skip(1))));
+        }
+    }
+
+    #[test]
+    fn test_resize_keeps_latest() {
+        let mut buffer = RingBuffer::new(4);
+        buffer.extend([1, 2, 3, 4, 5, 6]);
+
+        buffer.resize(2);
+        assert_eq!(buffer.capacity(), 2);
+        assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), vec![5, 6]);
+
+        buffer.resize(5);
+        assert_eq!(buffer.capacity(), 5);
+        buffer.push(7);
+        assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), vec![5, 6, 7]);
+    }
+
+    #[test]
+    fn test_zero_capacity() {
+        let mut buffer = RingBuffer::new(0);
+        assert_eq!(buffer.push((1.0, 2.0)), Some((1.0, 2.0)));
+        assert!(buffer.is_empty());
+    }
+}
@@ -0,0 +1,129 @@
+//! 逆向选择统计 - 比较每笔成交与成交后一段时间的中间价，衡量订单流毒性
+
+use crate::ring_buffer::RingBuffer;
+use nautilus_model::enums::OrderSide;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// 逆向选择价差放宽配置
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AdverseSelectionConfig {
+    /// 统计的最近成交笔数
+    pub window: usize,
+    /// 成交后观察中间价的时长（纳秒）
+    pub markout_ns: u64,
+    /// 不利成交占比达到该值（且窗口已满）时放宽价差，取值 (0, 1]
+    pub threshold: f64,
+    /// 放宽期间的价差倍数（≥1）
+    pub spread_multiplier: f64,
+    /// 放宽持续时长（纳秒）
+    pub cooldown_ns: u64,
+}
+
+/// 等待观察期结束的成交
+#[derive(Debug, Clone, Copy)]
+struct PendingFill {
+    /// 买入为 1，卖出为 -1
+    direction: f64,
+    /// 成交时的中间价
+    mid: f64,
+    timestamp_ns: u64,
+}
+
+/// 逆向选择追踪器：成交满 `markout_ns` 后，中间价朝不利方向移动
+/// （买入后下跌、卖出后上涨）记为一次不利成交
+#[derive(Debug, Clone)]
+pub struct AdverseSelectionTracker {
+    markout_ns: u64,
+    /// 尚未到观察期的成交（按时间顺序）
+    pending: VecDeque<PendingFill>,
+    /// 最近成交的结果（true 表示不利）
+    outcomes: RingBuffer<bool>,
+}
+
+impl AdverseSelectionTracker {
+    pub fn new(window: usize, markout_ns: u64) -> Self {
+        Self {
+            markout_ns,
+            pending: VecDeque::with_capacity(window),
+            outcomes: RingBuffer::new(window),
+        }
+    }
+
+    /// 记录一笔成交及成交时的中间价（待观察成交超过窗口时丢弃最旧的）
+    pub fn on_fill(&mut self, side: OrderSide, mid: f64, timestamp_ns: u64) {
+        let direction = match side {
+            OrderSide::Buy => 1.0,
+            OrderSide::Sell => -1.0,
+            OrderSide::NoOrderSide => return,
+        };
+        if mid <= 0.0 || self.outcomes.capacity() == 0 {
+            return;
+        }
+        if self.pending.len() == self.outcomes.capacity() {
+            self.pending.pop_front();
+        }
+        self.pending.push_back(PendingFill {
+            direction,
+            mid,
+            timestamp_ns,
+        });
+    }
+
+    /// 用最新中间价结算已到观察期的成交
+    pub fn on_mid(&mut self, mid: f64, now_ns: u64) {
+        while let Some(fill) = self.pending.front() {
+            if now_ns.saturating_sub(fill.timestamp_ns) < self.markout_ns {
+                break;
+            }
+            self.outcomes.push(fill.direction * (mid - fill.mid) < 0.0);
+            self.pending.pop_front();
+        }
+    }
+
+    /// 逆向选择分数：窗口内不利成交占比（尚无结算成交时为0）
+    pub fn score(&self) -> f64 {
+        if self.outcomes.is_empty() {
+            return 0.0;
+        }
+        let adverse = self.outcomes.iter().filter(|&&adverse| adverse).count();
+        adverse as f64 / self.outcomes.len() as f64
+    }
+
+    /// 结算成交是否已填满窗口
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.outcomes.capacity() > 0 && self.outcomes.is_full()
+    }
+
+    pub fn clear(&mut self) {
+        self.pending.clear();
+        self.outcomes.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markout_scoring() {
+        let mut tracker = AdverseSelectionTracker::new(4, 100);
+
+        // 买入后下跌：不利；观察期未到不结算
+        tracker.on_fill(OrderSide::Buy, 100.0, 0);
+        tracker.on_mid(99.0, 50);
+        assert_eq!(tracker.score(), 0.0);
+        tracker.on_mid(99.0, 100);
+        assert_eq!(tracker.score(), 1.0);
+
+        // 卖出后下跌：有利
+        tracker.on_fill(OrderSide::Sell, 100.0, 200);
+        tracker.on_mid(98.0, 300);
+        assert_eq!(tracker.score(), 0.5);
+        assert!(!tracker.is_full());
+
+        tracker.clear();
+        assert_eq!(tracker.score(), 0.0);
+    }
+}
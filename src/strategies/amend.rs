@@ -0,0 +1,274 @@
+//! 报价更新方式 - 改单（amend）与撤单重挂（cancel-replace）
+//!
+//! 纯决策逻辑：根据当前挂单与目标报价决定每一侧的操作，
+//! 由 Nautilus 包装层执行具体的下单/改单/撤单。
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// 报价更新方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AmendMode {
+    /// 撤销旧订单后提交新订单
+    #[default]
+    CancelReplace,
+    /// 交易所支持时原地修改价格/数量（延迟更低、占用更少下单频率）
+    Amend,
+}
+
+/// 当前挂单（单侧）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RestingQuote {
+    pub price: f64,
+    pub size: f64,
+    /// 提交（或最近一次改单）时间（纳秒）
+    pub submitted_ns: u64,
+}
+
+impl RestingQuote {
+    /// 挂单是否已达到最大存活时间
+    pub fn is_expired(&self, now_ns: u64, max_age_ns: u64) -> bool {
+        now_ns.saturating_sub(self.submitted_ns) >= max_age_ns
+    }
+}
+
+/// 单侧报价操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuoteAction {
+    /// 价格和数量均未变化
+    Keep,
+    /// 无挂单，提交新订单
+    Submit,
+    /// 目标数量为0，撤销挂单
+    Cancel,
+    /// 原地修改挂单
+    Amend,
+    /// 撤销后重新提交
+    CancelReplace,
+}
+
+/// 根据当前挂单和目标报价决定操作
+pub fn plan_quote_action(
+    mode: AmendMode,
+    resting: Option<RestingQuote>,
+    price: f64,
+    size: f64,
+) -> QuoteAction {
+    match resting {
+        None if size > 0.0 => QuoteAction::Submit,
+        None => QuoteAction::Keep,
+        Some(_) if size <= 0.0 => QuoteAction::Cancel,
+        Some(order) if order.price == price && order.size == size => QuoteAction::Keep,
+        Some(_) => match mode {
+            AmendMode::Amend => QuoteAction::Amend,
+            AmendMode::CancelReplace => QuoteAction::CancelReplace,
+        },
+    }
+}
+
+/// 改单/重挂次数统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrderUpdateStats {
+    pub amends: u64,
+    pub replaces: u64,
+}
+
+impl OrderUpdateStats {
+    pub fn record(&mut self, action: QuoteAction) {
+        match action {
+            QuoteAction::Amend => self.amends += 1,
+            QuoteAction::CancelReplace => self.replaces += 1,
+            QuoteAction::Keep | QuoteAction::Submit | QuoteAction::Cancel => {}
+        }
+    }
+}
+
+/// 撤单限速：相邻两次撤单间隔不少于 `min_interval_ns`，超出频率的撤单按先后排队
+///
+/// 交易所通常对撤单单独限频，与报价节流互补
+#[derive(Debug, Clone)]
+pub struct CancelScheduler<T> {
+    min_interval_ns: u64,
+    last_cancel_ns: Option<u64>,
+    pending: VecDeque<T>,
+}
+
+impl<T: PartialEq> CancelScheduler<T> {
+    /// 创建撤单限速器（0表示不限速）
+    pub fn new(min_interval_ns: u64) -> Self {
+        Self {
+            min_interval_ns,
+            last_cancel_ns: None,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// 当前能否立即撤单（无排队撤单且距上次撤单已满间隔）
+    pub fn can_cancel(&self, now_ns: u64) -> bool {
+        self.pending.is_empty() && self.interval_elapsed(now_ns)
+    }
+
+    /// 请求撤单：可以立即撤单时返回该订单并记录撤单时间，否则排队（重复请求忽略）
+    pub fn request(&mut self, order: T, now_ns: u64) -> Option<T> {
+        if self.can_cancel(now_ns) {
+            self.last_cancel_ns = Some(now_ns);
+            return Some(order);
+        }
+        if !self.pending.contains(&order) {
+            self.pending.push_back(order);
+        }
+        None
+    }
+
+    /// 间隔已满时取出最早排队的撤单并记录撤单时间
+    pub fn poll(&mut self, now_ns: u64) -> Option<T> {
+        if !self.interval_elapsed(now_ns) {
+            return None;
+        }
+        let order = self.pending.pop_front()?;
+        self.last_cancel_ns = Some(now_ns);
+        Some(order)
+    }
+
+    /// 只保留满足条件的排队撤单（例如跳过已成交或已撤销的订单）
+    pub fn retain(&mut self, f: impl FnMut(&T) -> bool) {
+        self.pending.retain(f);
+    }
+
+    /// 该订单的撤单是否仍在排队
+    pub fn is_pending(&self, order: &T) -> bool {
+        self.pending.contains(order)
+    }
+
+    /// 排队中的撤单数量
+    pub fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// 相邻两次撤单的最小间隔（纳秒）
+    pub fn min_interval_ns(&self) -> u64 {
+        self.min_interval_ns
+    }
+
+    #[inline]
+    fn interval_elapsed(&self, now_ns: u64) -> bool {
+        self.last_cancel_ns
+            .is_none_or(|last| now_ns.saturating_sub(last) >= self.min_interval_ns)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESTING: RestingQuote = RestingQuote {
+        price: 50000.0,
+        size: 0.001,
+        submitted_ns: 1_000_000_000,
+    };
+
+    #[test]
+    fn test_price_change_amends_in_amend_mode() {
+        let action = plan_quote_action(AmendMode::Amend, Some(RESTING), 50001.0, 0.001);
+        assert_eq!(action, QuoteAction::Amend);
+
+        let action = plan_quote_action(AmendMode::CancelReplace, Some(RESTING), 50001.0, 0.001);
+        assert_eq!(action, QuoteAction::CancelReplace);
+
+        let mut stats = OrderUpdateStats::default();
+        stats.record(QuoteAction::Amend);
+        stats.record(QuoteAction::CancelReplace);
+        stats.record(QuoteAction::Submit);
+        assert_eq!(
+            stats,
+            OrderUpdateStats {
+                amends: 1,
+                replaces: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_submit_keep_cancel() {
+        for mode in [AmendMode::Amend, AmendMode::CancelReplace] {
+            assert_eq!(
+                plan_quote_action(mode, None, 50000.0, 0.001),
+                QuoteAction::Submit
+            );
+            assert_eq!(
+                plan_quote_action(mode, None, 50000.0, 0.0),
+                QuoteAction::Keep
+            );
+            assert_eq!(
+                plan_quote_action(mode, Some(RESTING), 50000.0, 0.001),
+                QuoteAction::Keep
+            );
+            assert_eq!(
+                plan_quote_action(mode, Some(RESTING), 50000.0, 0.0),
+                QuoteAction::Cancel
+            );
+        }
+    }
+
+    #[test]
+    fn test_quote_age() {
+        assert!(!RESTING.is_expired(1_000_000_000, 500_000_000));
+        assert!(!RESTING.is_expired(1_499_999_999, 500_000_000));
+        assert!(RESTING.is_expired(1_500_000_000, 500_000_000));
+        // 时钟回拨时不视为过期
+        assert!(!RESTING.is_expired(0, 500_000_000));
+    }
+
+    #[test]
+    fn test_rapid_requotes_respect_cancel_interval() {
+        const INTERVAL: u64 = 100_000_000;
+        let mut scheduler = CancelScheduler::new(INTERVAL);
+        let mut resting = RESTING;
+        let mut cancels = Vec::new();
+
+        // 每10ms重新报价一次，撤单重挂被限速时保留原挂单
+        for step in 0..50u64 {
+            let now = 1_000_000_000 + step * 10_000_000;
+            let price = 50001.0 + step as f64;
+            let action = plan_quote_action(AmendMode::CancelReplace, Some(resting), price, 0.001);
+            assert_eq!(action, QuoteAction::CancelReplace);
+            if !scheduler.can_cancel(now) {
+                continue;
+            }
+            if scheduler.request(step, now).is_some() {
+                cancels.push(now);
+                resting = RestingQuote {
+                    price,
+                    size: 0.001,
+                    submitted_ns: now,
+                };
+            }
+        }
+
+        assert_eq!(cancels.len(), 5);
+        assert!(cancels.windows(2).all(|w| w[1] - w[0] >= INTERVAL));
+
+        // 不能延后的撤单（目标数量为0）排队，间隔满后逐个放行
+        let now = *cancels.last().unwrap();
+        assert_eq!(scheduler.request(100, now + 1), None);
+        assert_eq!(scheduler.request(101, now + 2), None);
+        assert_eq!(scheduler.request(100, now + 3), None);
+        assert_eq!(scheduler.pending_len(), 2);
+        assert!(scheduler.is_pending(&100));
+        assert_eq!(scheduler.poll(now + INTERVAL - 1), None);
+        assert_eq!(scheduler.poll(now + INTERVAL), Some(100));
+        assert!(!scheduler.is_pending(&100));
+        assert_eq!(scheduler.poll(now + INTERVAL + 1), None);
+        assert_eq!(scheduler.poll(now + 2 * INTERVAL), Some(101));
+        assert!(scheduler.can_cancel(now + 3 * INTERVAL));
+
+        scheduler.request(102, now + 3 * INTERVAL + 1);
+        scheduler.retain(|&order| order != 102);
+        assert_eq!(scheduler.pending_len(), 0);
+
+        // 不限速时总是立即撤单
+        let mut unlimited = CancelScheduler::new(0);
+        assert_eq!(unlimited.request(1, 0), Some(1));
+        assert_eq!(unlimited.request(2, 0), Some(2));
+    }
+}
@@ -6,11 +6,183 @@
 //! - SIMD优化
 //! - 零分配热路径
 
+use crate::error::{ensure_config, StrategyError, StrategyResult};
+use crate::indicators::garman_klass_volatility;
+use crate::ring_buffer::RingBuffer;
+use crate::strategies::adverse_selection::{AdverseSelectionConfig, AdverseSelectionTracker};
+use crate::strategies::clock::Clock;
+use crate::strategies::consolidated_book::ConsolidatedBook;
+use crate::strategies::performance::PerformanceTracker;
+use crate::strategies::pnl::{PnlReport, PnlTracker};
+use crate::strategies::spread_capture::SpreadCaptureTracker;
+use crate::strategies::volatility_recorder::VolatilityRecorder;
 use crate::CacheAligned;
 use nautilus_core::UnixNanos;
 use nautilus_model::enums::OrderSide;
 use serde::{Deserialize, Serialize};
-use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+
+/// 统计推送通道容量（满时丢弃，不阻塞热路径）
+const STATS_CHANNEL_CAPACITY: usize = 64;
+
+/// 绩效追踪保留的K线收益期数
+const PERFORMANCE_CAPACITY: usize = 1000;
+
+/// 波动率估计方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VolatilityEstimator {
+    /// 中间价对数收益率标准差
+    Standard,
+    /// Parkinson（K线高低价）
+    Parkinson,
+    /// Garman-Klass（K线开高低收）
+    GarmanKlass,
+}
+
+/// 订单大小模型
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum SizeModel {
+    /// 固定基础大小（base_order_size / bid_size / ask_size）
+    #[default]
+    Fixed,
+    /// Kelly 比例：size = clamp(fraction * μ/σ², 0, 1) * max_position_size
+    /// 其中 μ = |microprice - reservation_price| / mid
+    Kelly { fraction: f64 },
+}
+
+/// 报价参考价格来源（所选来源缺少数据时回退到最优买卖价中间价）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReferencePrice {
+    /// 最优买卖价中间价
+    #[default]
+    Mid,
+    /// 按挂单量加权的微观价格
+    Microprice,
+    /// 最新成交价（通过 `on_trade` 输入）
+    LastTrade,
+    /// 前 `levels` 档深度加权中间价（仅 `on_orderbook_update_l2` 可用）
+    DepthWeighted { levels: usize },
+}
+
+impl std::str::FromStr for ReferencePrice {
+    type Err = String;
+
+    /// 解析 `mid` / `microprice` / `last_trade` / `depth_weighted:<levels>`
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim().to_lowercase();
+        match value.split_once(':') {
+            Some(("depth_weighted", levels)) => levels
+                .trim()
+                .parse()
+                .map(|levels| ReferencePrice::DepthWeighted { levels })
+                .map_err(|e| format!("invalid depth levels {:?}: {}", levels, e)),
+            Some(_) => Err(format!("unknown reference price source: {}", value)),
+            None => match value.as_str() {
+                "mid" => Ok(ReferencePrice::Mid),
+                "microprice" => Ok(ReferencePrice::Microprice),
+                "last_trade" => Ok(ReferencePrice::LastTrade),
+                _ => Err(format!("unknown reference price source: {}", value)),
+            },
+        }
+    }
+}
+
+/// 盘口只有一个价格单位宽时的处理方式
+///
+/// 此时模型价差往往窄于可实现价差，取整后报价可能落入盘口内成为吃单
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TightBookPolicy {
+    /// 报价不优于买一/卖一（加入最优价排队，保持被动）
+    #[default]
+    Join,
+    /// 跳过本次报价
+    Skip,
+}
+
+/// 报价价格取整到价格单位的方向（Decimal 报价路径使用）
+///
+/// 取整方向在手续费与逆向选择之间权衡：放宽的报价每笔成交捕获更多价差、
+/// 更不容易被知情订单吃到，但成交更少；收窄的报价排在更靠前的价格上、成交更多，
+/// 但每笔利润更薄，更容易被逆向选择。无论哪种方向，取整后的价差都不低于双边 maker 手续费
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RoundingPolicy {
+    /// 买价向下、卖价向上取整，报价不比模型更激进
+    #[default]
+    Conservative,
+    /// 买卖价都取最近的价格单位（恰在中点时远离零）
+    Nearest,
+    /// 买价向上、卖价向下取整，报价比模型更紧
+    Aggressive,
+}
+
+/// 多档报价的数量分配方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum LadderSizing {
+    /// 每档数量相同
+    #[default]
+    Uniform,
+    /// 第 i 档权重为 decay^i（decay < 1 时越远越小）
+    Geometric { decay: f64 },
+    /// 权重与该档在 `horizon_secs` 秒内的成交概率成反比：成交越难的远档挂越多，
+    /// 成交后捕获更多价差
+    InverseFillProbability { horizon_secs: f64 },
+}
+
+/// 按成交概率反比分配时的概率下限，避免远档概率为0时权重无穷大
+const MIN_LADDER_FILL_PROBABILITY: f64 = 1e-12;
+
+impl LadderSizing {
+    /// 各档权重（未归一化）
+    ///
+    /// `InverseFillProbability` 需要各档成交概率（见 `weights_by_fill_probability`），此处按等权处理
+    pub fn weights(self, levels: usize) -> Vec<f64> {
+        match self {
+            LadderSizing::Uniform | LadderSizing::InverseFillProbability { .. } => {
+                vec![1.0; levels]
+            }
+            LadderSizing::Geometric { decay } => {
+                (0..levels).map(|i| decay.powi(i as i32)).collect()
+            }
+        }
+    }
+
+    /// 根据各档成交概率计算权重（未归一化），非 `InverseFillProbability` 时与 `weights` 相同
+    pub fn weights_by_fill_probability(self, fill_probabilities: &[f64]) -> Vec<f64> {
+        match self {
+            LadderSizing::InverseFillProbability { .. } => fill_probabilities
+                .iter()
+                .map(|p| 1.0 / p.max(MIN_LADDER_FILL_PROBABILITY))
+                .collect(),
+            sizing => sizing.weights(fill_probabilities.len()),
+        }
+    }
+}
+
+/// 报价偏斜函数形状（作用于归一化库存 x = q / max_inventory）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SkewShape {
+    /// 线性：f(x) = x
+    #[default]
+    Linear,
+    /// 二次（保号）：f(x) = x * |x|，小库存时偏斜更小，接近上限时加速
+    Quadratic,
+    /// 双曲正切：f(x) = tanh(x)，饱和于 ±1，库存超限时偏斜不会继续放大
+    Tanh,
+}
+
+impl SkewShape {
+    /// 对归一化库存应用变换
+    #[inline]
+    pub fn apply(self, x: f64) -> f64 {
+        match self {
+            SkewShape::Linear => x,
+            SkewShape::Quadratic => x * x.abs(),
+            SkewShape::Tanh => x.tanh(),
+        }
+    }
+}
 
 /// AS策略配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -37,450 +209,5439 @@ pub struct ASConfig {
     /// 基础订单大小
     pub base_order_size: f64,
 
+    /// 买单基础大小（未设置时使用 base_order_size）
+    #[serde(default)]
+    pub bid_size: Option<f64>,
+
+    /// 卖单基础大小（未设置时使用 base_order_size）
+    #[serde(default)]
+    pub ask_size: Option<f64>,
+
+    /// 订单大小模型
+    #[serde(default)]
+    pub size_model: SizeModel,
+
     /// 最大持仓
     pub max_position_size: f64,
 
     /// 最大库存偏离
     pub max_inventory: f64,
 
+    /// 多头库存上限（None 时取 `max_inventory`），例如资金费率为负时允许更多多头
+    #[serde(default)]
+    pub max_long_inventory: Option<f64>,
+
+    /// 空头库存上限（绝对值，None 时取 `max_inventory`），例如借币成本高时限制空头
+    #[serde(default)]
+    pub max_short_inventory: Option<f64>,
+
     /// 波动率窗口大小
     pub volatility_window: usize,
 
+    /// 数据不足时使用的回退波动率（默认0.01，即1%）
+    #[serde(default = "default_initial_volatility")]
+    pub initial_volatility: f64,
+
+    /// 波动率采样间隔（秒），None 表示不换算
+    ///
+    /// 单位约定：`time_horizon` 以秒计，报价使用的 σ 须为每秒波动率。
+    /// 波动率按K线（或其他固定间隔）估计时，报价前换算为
+    /// σ_per_sec = σ_per_bar / sqrt(bar_interval_secs)
+    #[serde(default)]
+    pub bar_interval_secs: Option<f64>,
+
     /// 是否使用Parkinson波动率
     pub use_parkinson: bool,
 
+    /// 波动率估计方法，设置后优先于 `use_parkinson`
+    #[serde(default)]
+    pub volatility_estimator: Option<VolatilityEstimator>,
+
+    /// 标准波动率的收益率指数衰减因子 (0, 1)
+    /// 最新收益率权重为1，往前每期乘以该因子；None 表示等权
+    #[serde(default)]
+    pub return_decay: Option<f64>,
+
+    /// 成交波动率在报价波动率中的权重 [0, 1]
+    ///
+    /// σ = w·σ_trade + (1-w)·σ_bar。逐笔方差先乘以每根K线的平均成交笔数换算为K线方差，
+    /// 再与K线波动率混合（尚无完整K线时按逐笔波动率使用）；只有一个来源有数据时
+    /// 完全使用该来源。0 表示只用K线波动率（默认）
+    #[serde(default)]
+    pub vol_blend_weight: f64,
+
+    /// 成交波动率 EWMA 的衰减因子 (0, 1)，越大越平滑
+    #[serde(default = "default_trade_volatility_decay")]
+    pub trade_volatility_decay: f64,
+
+    /// 记录每次K线/成交后报价使用的波动率序列（诊断用，只保留最近的样本，关闭时不分配内存）
+    #[serde(default)]
+    pub record_volatility: bool,
+
+    /// 是否按库存比例缩短有效时间窗口：T_eff = T * (1 - |q|/max_inventory)
+    /// 满仓时表现得如同临近收盘，更急于平仓
+    #[serde(default)]
+    pub inventory_scaled_horizon: bool,
+
+    /// 临近时段结束时只报减仓一侧直到持平
+    #[serde(default)]
+    pub end_of_session_flatten: bool,
+
+    /// 剩余时间低于 T 的该比例时进入平仓模式
+    #[serde(default = "default_flatten_fraction")]
+    pub flatten_fraction: f64,
+
+    /// 库存中性区间 (下限, 上限)：库存在区间内时不偏斜报价，
+    /// 区间外按超出部分偏斜；默认 (0, 0) 即按全部库存偏斜
+    #[serde(default)]
+    pub inventory_band: (f64, f64),
+
+    /// 设置后按名义价值偏斜：q_eff = q * mid / max_notional * max_inventory（反向合约为 q / mid）
+    ///
+    /// 同样的基础资产库存在高价位时偏斜更强；mid = max_notional / max_inventory 时与按数量偏斜一致。
+    /// None 表示按基础资产数量偏斜
+    #[serde(default)]
+    pub max_notional: Option<f64>,
+
+    /// 反向合约（币本位）：库存为合约张数，名义价值 = 张数 / 价格（基础货币），
+    /// 盈亏以基础货币计；`max_notional` 同样以基础货币计
+    #[serde(default)]
+    pub inverse: bool,
+
+    /// 报价偏斜函数形状（默认线性）
+    #[serde(default)]
+    pub skew_shape: SkewShape,
+
+    /// 库存偏斜随持仓时间增强的速率（每秒）：偏斜乘以 1 + decay * 持仓秒数（0表示禁用）
+    #[serde(default)]
+    pub skew_time_decay: f64,
+
     /// 库存惩罚因子
     pub inventory_penalty_factor: f64,
 
+    /// 库存惩罚是否按价格计量
+    /// false: penalty = q * factor * σ（σ为无量纲收益率，惩罚与价格水平无关）
+    /// true:  penalty = q * factor * σ * mid（价格比例偏斜，跨价格水平可比）
+    #[serde(default)]
+    pub penalty_in_price_terms: bool,
+
     /// 最大价差（基点）
     pub max_spread_bps: f64,
 
     /// 最小价差（基点）
     pub min_spread_bps: f64,
-}
 
-impl Default for ASConfig {
-    fn default() -> Self {
-        Self {
-            instrument_id: "BTCUSDT.BINANCE".to_string(),
-            risk_aversion: 0.1,
-            order_arrival_rate: 100.0,
-            price_sensitivity: 1.5,
-            time_horizon: 300.0,
-            base_order_size: 0.001,
-            max_position_size: 0.1,
-            max_inventory: 0.05,
-            volatility_window: 20,
-            use_parkinson: true,
-            inventory_penalty_factor: 2.0,
-            max_spread_bps: 200.0,
-            min_spread_bps: 2.0,
-        }
-    }
-}
+    /// 中间价 EMA 平滑系数（0, 1]，1.0 表示不平滑
+    /// 平滑后的中间价同时用于波动率和报价计算
+    #[serde(default = "default_mid_smoothing_alpha")]
+    pub mid_smoothing_alpha: f64,
 
-/// 订单簿快照（最小化版本）
-#[derive(Debug, Clone, Copy)]
-pub struct OrderBookSnapshot {
-    pub best_bid: f64,
-    pub best_ask: f64,
-    pub bid_volume: f64,
-    pub ask_volume: f64,
-    pub timestamp_ns: UnixNanos,
-}
+    /// 以平滑中间价作为公允价值计算保留价格：r = 公允价值 - q*γ*σ²*(T-t)
+    ///
+    /// 启用后 `mid_smoothing_alpha` 的 EMA 只作为公允价值（判断价格趋势），
+    /// 中间价跟随当前盘口，价差下限、距离、波动率等仍以当前中间价计算，
+    /// 避免噪声中间价带动保留价格抖动
+    #[serde(default)]
+    pub skew_around_fair_value: bool,
 
-/// K线数据
-#[derive(Debug, Clone, Copy)]
-pub struct Bar {
-    pub open: f64,
-    pub high: f64,
-    pub low: f64,
-    pub close: f64,
-    pub volume: f64,
-    pub timestamp_ns: u64,
-}
+    /// 报价参考价格来源（默认最优买卖价中间价）
+    #[serde(default)]
+    pub reference_price: ReferencePrice,
 
-/// 报价结果
-#[derive(Debug, Clone, Copy)]
-pub struct QuoteUpdate {
-    pub bid_price: f64,
-    pub ask_price: f64,
-    pub bid_size: f64,
-    pub ask_size: f64,
-    pub spread: f64,
-    pub reservation_price: f64,
-}
+    /// 已弃用：L2 深度加权中间价使用的档位数（0表示不使用），
+    /// 非0时等同 `reference_price = DepthWeighted { levels }`，不能与其他参考价格来源同时设置
+    #[serde(default)]
+    pub l2_depth_levels: usize,
 
-/// AS策略主体 - 缓存行对齐优化
-#[repr(align(128))]
-#[derive(Debug)]
-pub struct AvellanedaStoikov {
-    /// 配置参数
-    config: ASConfig,
+    /// 是否以 debug 级别记录每次报价（结构化字段）
+    #[serde(default)]
+    pub log_quotes: bool,
 
-    /// 市场状态（缓存行对齐）
-    mid_price: CacheAligned<f64>,
-    volatility: CacheAligned<f64>,
-    inventory: CacheAligned<f64>,
+    /// 统计推送间隔（每N次报价推送一次StrategyStats，0表示禁用）
+    #[serde(default = "default_stats_interval")]
+    pub stats_interval: u64,
 
-    /// 价格历史（用于波动率计算）
-    price_history: VecDeque<f64>,
-    high_low_history: VecDeque<(f64, f64)>,
+    /// 交易所最小数量步长（0表示不取整）
+    #[serde(default)]
+    pub lot_size: f64,
 
-    /// 性能计数器（缓存行对齐，避免false sharing）
-    quote_updates: CacheAligned<u64>,
-    orderbook_updates: CacheAligned<u64>,
-    inventory_adjustments: CacheAligned<u64>,
+    /// 盘口价差不超过一个价格单位（见 `tick_size`）时的处理方式
+    #[serde(default)]
+    pub tight_book_policy: TightBookPolicy,
 
-    /// 最后更新时间
-    last_update_ns: UnixNanos,
-}
+    /// 是否允许报价改善盘口（买价高于最优买价、卖价低于最优卖价）
+    ///
+    /// 关闭后报价最多挂在最优价上。默认开启，保持模型价格原样（含平仓窗口内的主动让价）
+    #[serde(default = "default_allow_price_improvement")]
+    pub allow_price_improvement: bool,
 
-impl AvellanedaStoikov {
-    /// 创建新策略实例
-    pub fn new(config: ASConfig) -> Self {
-        let capacity = config.volatility_window;
+    /// 按排队位置决定加入还是改善盘口：模型价格改善盘口时默认加入最优价，只有最优价
+    /// 挂单量超过 `max_queue_volume` 时才改善一个价格单位。`allow_price_improvement`
+    /// 关闭时不生效（报价始终最多挂在最优价上）
+    #[serde(default)]
+    pub queue_aware: bool,
 
-        Self {
-            config,
-            mid_price: CacheAligned::new(0.0),
-            volatility: CacheAligned::new(0.01), // 初始波动率1%
-            inventory: CacheAligned::new(0.0),
-            price_history: VecDeque::with_capacity(capacity),
-            high_low_history: VecDeque::with_capacity(capacity),
-            quote_updates: CacheAligned::new(0),
-            orderbook_updates: CacheAligned::new(0),
-            inventory_adjustments: CacheAligned::new(0),
-            last_update_ns: UnixNanos::new(0),
-        }
-    }
+    /// 最优价排队量阈值（基础资产数量），仅 `queue_aware` 时生效
+    #[serde(default)]
+    pub max_queue_volume: f64,
 
-    /// 处理订单簿更新 - 超低延迟热路径
-    ///
-    /// 性能要求: < 20μs
-    #[inline(always)]
-    pub fn on_orderbook_update(&mut self, snapshot: &OrderBookSnapshot) -> Option<QuoteUpdate> {
-        self.orderbook_updates.data += 1;
-        self.last_update_ns = snapshot.timestamp_ns;
+    /// 成交后暂停重新报价成交一侧的时长（纳秒），另一侧照常报价；0表示不暂停
+    #[serde(default)]
+    pub post_fill_cooldown_ns: u64,
 
-        // 计算中间价
-        let new_mid = (snapshot.best_bid + snapshot.best_ask) * 0.5;
-        self.mid_price.data = new_mid;
+    /// 逆向选择防御：最近成交持续不利（成交后中间价朝不利方向移动）时放宽价差；None表示禁用
+    #[serde(default)]
+    pub adverse_selection: Option<AdverseSelectionConfig>,
 
-        // 更新价格历史
-        self.update_price_history(new_mid);
+    /// 两次报价之间的最小间隔（纳秒），间隔内的订单簿更新只更新状态不报价；0表示不节流
+    #[serde(default)]
+    pub min_quote_interval_ns: u64,
 
-        // 计算并返回新报价
-        Some(self.calculate_quotes(snapshot.timestamp_ns.as_u64()))
-    }
+    /// 报价取整到价格单位的方向
+    #[serde(default)]
+    pub rounding_policy: RoundingPolicy,
 
-    /// 处理K线更新
-    #[inline]
-    pub fn on_bar(&mut self, bar: &Bar) {
-        // 更新高低价历史（用于Parkinson波动率）
-        self.high_low_history.push_back((bar.high, bar.low));
-        if self.high_low_history.len() > self.config.volatility_window {
-            self.high_low_history.pop_front();
-        }
+    /// 多档报价档数（`quote_ladder` 使用，1 表示只报最优一档）
+    ///
+    /// 仅用于离线分析：Nautilus 包装层每侧只挂最优一档
+    #[serde(default = "default_ladder_levels")]
+    pub ladder_levels: usize,
 
-        // 重新计算波动率
-        if self.config.use_parkinson {
-            self.volatility.data = self.calculate_parkinson_volatility();
-        } else {
-            self.volatility.data = self.calculate_standard_volatility();
-        }
-    }
+    /// 相邻两档的价格间隔（基点，相对中间价）
+    #[serde(default = "default_ladder_step_bps")]
+    pub ladder_step_bps: f64,
 
-    /// 处理订单成交
-    #[inline]
-    pub fn on_fill(&mut self, side: OrderSide, quantity: f64) {
-        self.inventory_adjustments.data += 1;
+    /// 多档报价的数量分配方式
+    #[serde(default)]
+    pub ladder_sizing: LadderSizing,
 
-        match side {
-            OrderSide::Buy => self.inventory.data += quantity,
-            OrderSide::Sell => self.inventory.data -= quantity,
-            OrderSide::NoOrderSide => todo!(),
-        }
+    /// 最小报价数量，低于该值的一侧不报价（0表示不限制）
+    #[serde(default)]
+    pub min_order_size: f64,
 
-        // 检查库存限制
-        if self.inventory.data.abs() > self.config.max_inventory {
-            tracing::warn!(
-                inventory = self.inventory.data,
-                max = self.config.max_inventory,
-                "Inventory exceeds limit"
-            );
-        }
-    }
+    /// 价格精度（小数位数，Decimal 报价路径使用）
+    #[serde(default = "default_price_precision")]
+    pub price_precision: u32,
 
-    /// 计算AS模型报价 - 核心算法
+    /// 最小价格单位（None 表示 10^-price_precision）
     ///
-    /// 性能要求: < 10μs
-    #[inline]
-    fn calculate_quotes(&mut self, _timestamp_ns: u64) -> QuoteUpdate {
-        self.quote_updates.data += 1;
+    /// 价格单位不是10的整数次幂时（如 0.5、0.05）需要显式设置，取品种的 price_increment
+    #[serde(default)]
+    pub tick_size: Option<f64>,
 
-        let mid = self.mid_price.data;
-        let sigma = self.volatility.data;
-        let gamma = self.config.risk_aversion;
-        let q = self.inventory.data;
+    /// 数量精度（小数位数，Decimal 报价路径使用）
+    #[serde(default = "default_size_precision")]
+    pub size_precision: u32,
 
-        // 计算剩余时间（秒）
-        let time_remaining = self.config.time_horizon;
+    /// 对冲软阈值：|库存| 超过该值时发出对冲信号（0表示禁用）
+    #[serde(default)]
+    pub hedge_threshold: f64,
 
-        // 1. 计算保留价格 (Reservation Price)
-        // r = s - q*γ*σ²*(T-t)
-        let reservation_price = mid - q * gamma * sigma * sigma * time_remaining;
+    /// 对冲目标库存：对冲后 |库存| 回到该水平
+    #[serde(default)]
+    pub hedge_target_inventory: f64,
 
-        // 2. 计算最优价差 (Optimal Spread)
-        // δ = γ*σ²*(T-t) + (2/γ)*ln(1 + γ/κ)
-        let kappa = self.config.price_sensitivity;
-        let spread_base = gamma * sigma * sigma * time_remaining;
-        let spread_adjustment = (2.0 / gamma) * (1.0 + gamma / kappa).ln();
-        let mut optimal_spread = spread_base + spread_adjustment;
+    /// 报价相对保留价格的最小利润边际（基点）
+    /// 买价不高于 r*(1-edge)，卖价不低于 r*(1+edge)
+    #[serde(default)]
+    pub min_edge_bps: f64,
 
-        // 3. 应用价差限制
-        let min_spread = mid * self.config.min_spread_bps / 10000.0;
-        let max_spread = mid * self.config.max_spread_bps / 10000.0;
-        optimal_spread = optimal_spread.clamp(min_spread, max_spread);
+    /// 单日最大亏损（已实现+未实现，计价货币），触发后停止报价直到新交易日（0表示禁用）
+    #[serde(default)]
+    pub max_daily_loss: f64,
 
-        // 4. 计算买卖报价
-        let half_spread = optimal_spread * 0.5;
-        let mut bid_price = reservation_price - half_spread;
-        let mut ask_price = reservation_price + half_spread;
+    /// 报价价格带（基点）：买价或卖价距当前中间价超过该值时拒绝本次报价（0表示禁用）
+    ///
+    /// 最后一道保护，拦截波动率或参数异常导致的离谱报价
+    #[serde(default)]
+    pub max_quote_deviation_bps: f64,
 
-        // 5. 库存惩罚调整
-        let inventory_penalty = q * self.config.inventory_penalty_factor * sigma;
-        bid_price -= inventory_penalty;
-        ask_price -= inventory_penalty;
+    /// 挂单成交手续费（基点，每边；负值表示返佣）
+    #[serde(default)]
+    pub maker_fee_bps: f64,
 
-        // 6. 订单大小（可以根据库存调整）
-        let size_adjustment = 1.0 - (q.abs() / self.config.max_inventory).min(1.0);
-        let order_size = self.config.base_order_size * size_adjustment;
+    /// 预期库存持有时间（秒），用于盈亏平衡波动率
+    #[serde(default = "default_expected_holding_secs")]
+    pub expected_holding_secs: f64,
 
-        QuoteUpdate {
-            bid_price,
-            ask_price,
-            bid_size: order_size,
-            ask_size: order_size,
-            spread: optimal_spread,
-            reservation_price,
-        }
+    /// 报价波动率超过盈亏平衡波动率时触发熔断
+    ///
+    /// 盈亏平衡波动率按秒计算，须设置 `bar_interval_secs` 把报价波动率换算为每秒
+    #[serde(default)]
+    pub halt_above_break_even_volatility: bool,
+}
+
+impl ASConfig {
+    /// 多头库存上限
+    #[inline]
+    pub fn long_inventory_limit(&self) -> f64 {
+        self.max_long_inventory.unwrap_or(self.max_inventory)
     }
 
-    /// 更新价格历史
+    /// 空头库存上限（绝对值）
     #[inline]
-    fn update_price_history(&mut self, price: f64) {
-        self.price_history.push_back(price);
-        if self.price_history.len() > self.config.volatility_window {
-            self.price_history.pop_front();
+    pub fn short_inventory_limit(&self) -> f64 {
+        self.max_short_inventory.unwrap_or(self.max_inventory)
+    }
+
+    /// 当前库存方向对应的上限
+    #[inline]
+    pub fn inventory_limit(&self, inventory: f64) -> f64 {
+        if inventory >= 0.0 {
+            self.long_inventory_limit()
+        } else {
+            self.short_inventory_limit()
         }
     }
 
-    /// 计算Parkinson波动率 - SIMD优化版本
-    ///
-    /// Parkinson波动率使用高低价，比标准波动率更稳定
-    /// σ² = (1/4ln2) * (1/n) * Σ(ln(H/L))²
+    /// 实际使用的波动率估计方法
     #[inline]
-    fn calculate_parkinson_volatility(&self) -> f64 {
-        if self.high_low_history.len() < 2 {
-            return 0.01;
+    pub fn volatility_estimator(&self) -> VolatilityEstimator {
+        match self.volatility_estimator {
+            Some(estimator) => estimator,
+            None if self.use_parkinson => VolatilityEstimator::Parkinson,
+            None => VolatilityEstimator::Standard,
         }
+    }
 
-        let mut sum_sq = 0.0;
-        let ln2 = std::f64::consts::LN_2;
+    /// 实际使用的最小价格单位
+    #[inline]
+    pub fn tick_size(&self) -> f64 {
+        self.tick_size
+            .unwrap_or_else(|| 10f64.powi(-(self.price_precision as i32)))
+    }
 
-        for &(high, low) in &self.high_low_history {
-            if high > 0.0 && low > 0.0 {
-                let ratio = (high / low).ln();
-                sum_sq += ratio * ratio;
-            }
+    /// 实际使用的报价参考价格来源（兼容已弃用的 `l2_depth_levels`）
+    #[inline]
+    pub fn reference_price_source(&self) -> ReferencePrice {
+        match (self.reference_price, self.l2_depth_levels) {
+            (ReferencePrice::Mid, levels) if levels > 0 => ReferencePrice::DepthWeighted { levels },
+            (source, _) => source,
         }
+    }
 
-        let n = self.high_low_history.len() as f64;
-        (sum_sq / (n * 4.0 * ln2)).sqrt()
+    /// 从环境变量加载配置（`{PREFIX}_RISK_AVERSION`、`{PREFIX}_MAX_SPREAD_BPS` 等），
+    /// 未设置的字段使用默认值，加载后校验
+    ///
+    /// 每个字段都可设置：数值、布尔值和 `reference_price` 按字面值解析，其余枚举和复合字段
+    /// 使用与 JSON 配置相同的格式（如 `AS_SKEW_SHAPE=Tanh`、`AS_INVENTORY_BAND=[-0.5,0.5]`、
+    /// `AS_SIZE_MODEL={"Kelly":{"fraction":0.5}}`）
+    pub fn from_env(prefix: &str) -> StrategyResult<Self> {
+        Self::from_env_with(prefix, |key| std::env::var(key).ok())
     }
 
-    /// 计算标准波动率（基于收益率）
-    #[inline]
-    fn calculate_standard_volatility(&self) -> f64 {
-        if self.price_history.len() < 2 {
-            return 0.01;
+    /// 使用自定义查找函数加载（便于测试）
+    pub fn from_env_with<F>(prefix: &str, env: F) -> StrategyResult<Self>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let mut config = Self::default();
+        let lookup = |field: &str| {
+            let key = format!("{}_{}", prefix, field.to_uppercase());
+            env(&key).map(|value| (key, value))
+        };
+
+        if let Some((_, value)) = lookup("instrument_id") {
+            config.instrument_id = value;
         }
 
-        // 计算对数收益率
-        let returns: Vec<f64> = self
-            .price_history
-            .iter()
-            .zip(self.price_history.iter().skip(1))
-            .map(|(p1, p2)| (p2 / p1).ln())
-            .collect();
+        macro_rules! parse_fields {
+            ($($field:ident),* $(,)?) => {
+                $(
+                    if let Some((key, value)) = lookup(stringify!($field)) {
+                        config.$field = value.trim().parse().map_err(|e| {
+                            StrategyError::InvalidConfig(format!(
+                                "invalid value for {}: {:?} ({})",
+                                key, value, e
+                            ))
+                        })?;
+                    }
+                )*
+            };
+        }
 
-        if returns.is_empty() {
-            return 0.01;
+        macro_rules! parse_optional_fields {
+            ($($field:ident),* $(,)?) => {
+                $(
+                    if let Some((key, value)) = lookup(stringify!($field)) {
+                        config.$field = Some(value.trim().parse().map_err(|e| {
+                            StrategyError::InvalidConfig(format!(
+                                "invalid value for {}: {:?} ({})",
+                                key, value, e
+                            ))
+                        })?);
+                    }
+                )*
+            };
         }
 
-        // 计算均值
-        let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+        // 枚举与复合字段按 serde 格式解析，裸字符串视为 JSON 字符串（`Tanh` 等同 `"Tanh"`）
+        macro_rules! parse_json_fields {
+            ($($field:ident),* $(,)?) => {
+                $(
+                    if let Some((key, value)) = lookup(stringify!($field)) {
+                        let trimmed = value.trim();
+                        config.$field = serde_json::from_str(trimmed)
+                            .or_else(|_| serde_json::from_value(trimmed.into()))
+                            .map_err(|e| {
+                                StrategyError::InvalidConfig(format!(
+                                    "invalid value for {}: {:?} ({})",
+                                    key, value, e
+                                ))
+                            })?;
+                    }
+                )*
+            };
+        }
 
-        // 计算方差
-        let variance =
-            returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / returns.len() as f64;
+        parse_fields!(
+            risk_aversion,
+            order_arrival_rate,
+            price_sensitivity,
+            time_horizon,
+            base_order_size,
+            max_position_size,
+            max_inventory,
+            volatility_window,
+            initial_volatility,
+            use_parkinson,
+            inventory_scaled_horizon,
+            end_of_session_flatten,
+            flatten_fraction,
+            inventory_penalty_factor,
+            penalty_in_price_terms,
+            max_spread_bps,
+            min_spread_bps,
+            mid_smoothing_alpha,
+            skew_around_fair_value,
+            reference_price,
+            l2_depth_levels,
+            log_quotes,
+            stats_interval,
+            lot_size,
+            min_order_size,
+            price_precision,
+            size_precision,
+            hedge_threshold,
+            hedge_target_inventory,
+            min_edge_bps,
+            max_daily_loss,
+            max_quote_deviation_bps,
+            vol_blend_weight,
+            trade_volatility_decay,
+            record_volatility,
+            inverse,
+            skew_time_decay,
+            allow_price_improvement,
+            queue_aware,
+            max_queue_volume,
+            post_fill_cooldown_ns,
+            min_quote_interval_ns,
+            ladder_levels,
+            ladder_step_bps,
+            maker_fee_bps,
+            expected_holding_secs,
+            halt_above_break_even_volatility,
+        );
+        parse_optional_fields!(
+            bid_size,
+            ask_size,
+            return_decay,
+            max_long_inventory,
+            max_short_inventory,
+            max_notional,
+            tick_size,
+            bar_interval_secs,
+        );
+        parse_json_fields!(
+            size_model,
+            volatility_estimator,
+            inventory_band,
+            skew_shape,
+            tight_book_policy,
+            adverse_selection,
+            rounding_policy,
+            ladder_sizing,
+        );
 
-        variance.sqrt()
+        config.validate()?;
+        Ok(config)
     }
 
-    /// 获取当前统计数据
-    pub fn get_stats(&self) -> StrategyStats {
-        StrategyStats {
-            quote_updates: self.quote_updates.data,
-            orderbook_updates: self.orderbook_updates.data,
-            inventory_adjustments: self.inventory_adjustments.data,
-            current_inventory: self.inventory.data,
-            current_volatility: self.volatility.data,
-            mid_price: self.mid_price.data,
-        }
+    /// 从 JSON 文件加载配置（格式与 `ASConfig` 的序列化格式一致），加载后校验
+    pub fn from_file(path: impl AsRef<Path>) -> StrategyResult<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        let config: Self = serde_json::from_str(&contents)?;
+        config.validate()?;
+        Ok(config)
     }
 
-    /// 重置策略状态
-    pub fn reset(&mut self) {
-        self.mid_price.data = 0.0;
-        self.volatility.data = 0.01;
-        self.inventory.data = 0.0;
-        self.price_history.clear();
-        self.high_low_history.clear();
-        self.quote_updates.data = 0;
-        self.orderbook_updates.data = 0;
-        self.inventory_adjustments.data = 0;
-        self.last_update_ns = UnixNanos::new(0);
+    /// 校验配置参数，不满足时返回 `StrategyError::InvalidConfig`
+    pub fn validate(&self) -> StrategyResult<()> {
+        ensure_config!(self.risk_aversion > 0.0, "risk_aversion must be positive");
+        ensure_config!(
+            self.price_sensitivity > 0.0,
+            "price_sensitivity must be positive"
+        );
+        ensure_config!(self.time_horizon > 0.0, "time_horizon must be positive");
+        ensure_config!(
+            self.base_order_size > 0.0,
+            "base_order_size must be positive"
+        );
+        ensure_config!(self.max_inventory > 0.0, "max_inventory must be positive");
+        ensure_config!(
+            self.long_inventory_limit() > 0.0 && self.short_inventory_limit() > 0.0,
+            "max_long_inventory and max_short_inventory must be positive"
+        );
+        ensure_config!(
+            self.volatility_window >= 2,
+            "volatility_window must be at least 2"
+        );
+        ensure_config!(
+            self.initial_volatility > 0.0,
+            "initial_volatility must be positive"
+        );
+        ensure_config!(
+            self.min_spread_bps >= 0.0 && self.max_spread_bps >= self.min_spread_bps,
+            "spread bounds must satisfy 0 <= min_spread_bps <= max_spread_bps"
+        );
+        ensure_config!(
+            self.mid_smoothing_alpha > 0.0 && self.mid_smoothing_alpha <= 1.0,
+            "mid_smoothing_alpha must be in (0, 1]"
+        );
+        if let Some(tick) = self.tick_size {
+            let scaled = tick * 10f64.powi(self.price_precision as i32);
+            ensure_config!(
+                tick.is_finite() && tick > 0.0 && (scaled - scaled.round()).abs() < 1e-6,
+                "tick_size must be positive and representable with price_precision decimals"
+            );
+        }
+        ensure_config!(
+            self.inventory_band.0 <= self.inventory_band.1,
+            "inventory_band lower bound must not exceed upper bound"
+        );
+        ensure_config!(
+            self.max_queue_volume >= 0.0,
+            "max_queue_volume must be non-negative"
+        );
+        if let Some(adverse) = self.adverse_selection {
+            ensure_config!(
+                adverse.window >= 1,
+                "adverse_selection window must be at least 1"
+            );
+            ensure_config!(
+                adverse.threshold > 0.0 && adverse.threshold <= 1.0,
+                "adverse_selection threshold must be in (0, 1]"
+            );
+            ensure_config!(
+                adverse.spread_multiplier >= 1.0,
+                "adverse_selection spread_multiplier must be at least 1"
+            );
+        }
+        if let Some(max_notional) = self.max_notional {
+            ensure_config!(max_notional > 0.0, "max_notional must be positive");
+        }
+        if let Some(decay) = self.return_decay {
+            ensure_config!(decay > 0.0 && decay < 1.0, "return_decay must be in (0, 1)");
+        }
+        ensure_config!(
+            (0.0..=1.0).contains(&self.vol_blend_weight),
+            "vol_blend_weight must be in [0, 1]"
+        );
+        ensure_config!(
+            self.trade_volatility_decay > 0.0 && self.trade_volatility_decay < 1.0,
+            "trade_volatility_decay must be in (0, 1)"
+        );
+        ensure_config!(
+            self.max_quote_deviation_bps >= 0.0,
+            "max_quote_deviation_bps must be non-negative"
+        );
+        ensure_config!(
+            self.skew_time_decay >= 0.0,
+            "skew_time_decay must be non-negative"
+        );
+        ensure_config!(
+            self.expected_holding_secs > 0.0,
+            "expected_holding_secs must be positive"
+        );
+        if let Some(interval) = self.bar_interval_secs {
+            ensure_config!(
+                interval.is_finite() && interval > 0.0,
+                "bar_interval_secs must be positive"
+            );
+        }
+        ensure_config!(
+            (0.0..=1.0).contains(&self.flatten_fraction),
+            "flatten_fraction must be in [0, 1]"
+        );
+        ensure_config!(self.lot_size >= 0.0, "lot_size must be non-negative");
+        ensure_config!(
+            self.min_order_size >= 0.0,
+            "min_order_size must be non-negative"
+        );
+        ensure_config!(
+            self.hedge_threshold >= 0.0,
+            "hedge_threshold must be non-negative"
+        );
+        ensure_config!(
+            !self.halt_above_break_even_volatility || self.bar_interval_secs.is_some(),
+            "halt_above_break_even_volatility requires bar_interval_secs so that \
+             volatility is compared per second"
+        );
+        ensure_config!(self.ladder_levels >= 1, "ladder_levels must be at least 1");
+        ensure_config!(
+            self.ladder_step_bps >= 0.0,
+            "ladder_step_bps must be non-negative"
+        );
+        match self.ladder_sizing {
+            LadderSizing::Geometric { decay } => {
+                ensure_config!(decay > 0.0, "ladder geometric decay must be positive");
+            }
+            LadderSizing::InverseFillProbability { horizon_secs } => {
+                ensure_config!(
+                    horizon_secs > 0.0,
+                    "ladder fill probability horizon must be positive"
+                );
+            }
+            LadderSizing::Uniform => {}
+        }
+        ensure_config!(
+            self.l2_depth_levels == 0 || self.reference_price == ReferencePrice::Mid,
+            "l2_depth_levels is deprecated and conflicts with reference_price {:?}; \
+             use reference_price = DepthWeighted {{ levels }} instead",
+            self.reference_price
+        );
+        Ok(())
     }
 }
 
-// /// 订单方向
-// #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-// pub enum OrderSide {
-//     Buy,
-//     Sell,
-// }
+fn default_stats_interval() -> u64 {
+    100
+}
 
-/// 策略统计数据
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct StrategyStats {
-    pub quote_updates: u64,
-    pub orderbook_updates: u64,
-    pub inventory_adjustments: u64,
-    pub current_inventory: f64,
-    pub current_volatility: f64,
-    pub mid_price: f64,
+fn default_allow_price_improvement() -> bool {
+    true
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+fn default_expected_holding_secs() -> f64 {
+    60.0
+}
 
-    fn create_test_config() -> ASConfig {
-        ASConfig {
-            instrument_id: "BTCUSDT".to_string(),
+fn default_ladder_levels() -> usize {
+    1
+}
+
+fn default_ladder_step_bps() -> f64 {
+    5.0
+}
+
+fn default_initial_volatility() -> f64 {
+    0.01
+}
+
+fn default_mid_smoothing_alpha() -> f64 {
+    1.0
+}
+
+fn default_trade_volatility_decay() -> f64 {
+    0.94
+}
+
+fn default_flatten_fraction() -> f64 {
+    0.1
+}
+
+fn default_price_precision() -> u32 {
+    2
+}
+
+fn default_size_precision() -> u32 {
+    4
+}
+
+impl Default for ASConfig {
+    fn default() -> Self {
+        Self {
+            instrument_id: "BTCUSDT.BINANCE".to_string(),
             risk_aversion: 0.1,
             order_arrival_rate: 100.0,
             price_sensitivity: 1.5,
             time_horizon: 300.0,
             base_order_size: 0.001,
+            bid_size: None,
+            ask_size: None,
+            size_model: SizeModel::Fixed,
             max_position_size: 0.1,
             max_inventory: 0.05,
+            max_long_inventory: None,
+            max_short_inventory: None,
             volatility_window: 20,
+            initial_volatility: default_initial_volatility(),
+            bar_interval_secs: None,
             use_parkinson: true,
+            volatility_estimator: None,
+            return_decay: None,
+            vol_blend_weight: 0.0,
+            trade_volatility_decay: default_trade_volatility_decay(),
+            record_volatility: false,
+            inventory_scaled_horizon: false,
+            end_of_session_flatten: false,
+            flatten_fraction: default_flatten_fraction(),
+            inventory_band: (0.0, 0.0),
+            max_notional: None,
+            inverse: false,
+            skew_shape: SkewShape::Linear,
+            skew_time_decay: 0.0,
             inventory_penalty_factor: 2.0,
+            penalty_in_price_terms: false,
             max_spread_bps: 200.0,
             min_spread_bps: 2.0,
+            mid_smoothing_alpha: default_mid_smoothing_alpha(),
+            skew_around_fair_value: false,
+            reference_price: ReferencePrice::Mid,
+            l2_depth_levels: 0,
+            log_quotes: false,
+            stats_interval: default_stats_interval(),
+            lot_size: 0.0,
+            tight_book_policy: TightBookPolicy::Join,
+            allow_price_improvement: default_allow_price_improvement(),
+            queue_aware: false,
+            max_queue_volume: 0.0,
+            post_fill_cooldown_ns: 0,
+            adverse_selection: None,
+            min_quote_interval_ns: 0,
+            rounding_policy: RoundingPolicy::Conservative,
+            ladder_levels: default_ladder_levels(),
+            ladder_step_bps: default_ladder_step_bps(),
+            ladder_sizing: LadderSizing::Uniform,
+            min_order_size: 0.0,
+            price_precision: default_price_precision(),
+            tick_size: None,
+            size_precision: default_size_precision(),
+            hedge_threshold: 0.0,
+            hedge_target_inventory: 0.0,
+            min_edge_bps: 0.0,
+            max_daily_loss: 0.0,
+            max_quote_deviation_bps: 0.0,
+            maker_fee_bps: 0.0,
+            expected_holding_secs: default_expected_holding_secs(),
+            halt_above_break_even_volatility: false,
         }
     }
+}
 
-    fn create_test_snapshot(bid: f64, ask: f64) -> OrderBookSnapshot {
+/// 部分配置更新（运行时热更新，None 字段保持不变）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PartialASConfig {
+    pub risk_aversion: Option<f64>,
+    pub order_arrival_rate: Option<f64>,
+    pub price_sensitivity: Option<f64>,
+    pub time_horizon: Option<f64>,
+    pub base_order_size: Option<f64>,
+    pub max_position_size: Option<f64>,
+    pub max_inventory: Option<f64>,
+    pub volatility_window: Option<usize>,
+    pub inventory_penalty_factor: Option<f64>,
+    pub max_spread_bps: Option<f64>,
+    pub min_spread_bps: Option<f64>,
+    pub mid_smoothing_alpha: Option<f64>,
+    pub min_edge_bps: Option<f64>,
+    pub hedge_threshold: Option<f64>,
+    pub hedge_target_inventory: Option<f64>,
+    pub max_daily_loss: Option<f64>,
+    pub log_quotes: Option<bool>,
+}
+
+impl PartialASConfig {
+    /// 将非 None 字段合并到给定配置，返回新配置
+    pub fn merge(&self, config: &ASConfig) -> ASConfig {
+        let mut merged = config.clone();
+
+        macro_rules! merge_fields {
+            ($($field:ident),* $(,)?) => {
+                $(
+                    if let Some(value) = self.$field {
+                        merged.$field = value;
+                    }
+                )*
+            };
+        }
+
+        merge_fields!(
+            risk_aversion,
+            order_arrival_rate,
+            price_sensitivity,
+            time_horizon,
+            base_order_size,
+            max_position_size,
+            max_inventory,
+            volatility_window,
+            inventory_penalty_factor,
+            max_spread_bps,
+            min_spread_bps,
+            mid_smoothing_alpha,
+            min_edge_bps,
+            hedge_threshold,
+            hedge_target_inventory,
+            max_daily_loss,
+            log_quotes,
+        );
+        merged
+    }
+}
+
+/// 订单簿快照（最小化版本）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct OrderBookSnapshot {
+    pub best_bid: f64,
+    pub best_ask: f64,
+    pub bid_volume: f64,
+    pub ask_volume: f64,
+    pub timestamp_ns: UnixNanos,
+}
+
+/// 多档订单簿快照
+#[derive(Debug, Clone)]
+pub struct OrderBookSnapshotL2 {
+    /// 买盘 (价格, 数量)，按价格从高到低
+    pub bids: Vec<(f64, f64)>,
+    /// 卖盘 (价格, 数量)，按价格从低到高
+    pub asks: Vec<(f64, f64)>,
+    pub timestamp_ns: UnixNanos,
+}
+
+impl OrderBookSnapshotL2 {
+    /// 提取最优买卖价快照（缺失的一侧价格为0）
+    pub fn top_of_book(&self) -> OrderBookSnapshot {
+        let (best_bid, bid_volume) = self.bids.first().copied().unwrap_or((0.0, 0.0));
+        let (best_ask, ask_volume) = self.asks.first().copied().unwrap_or((0.0, 0.0));
         OrderBookSnapshot {
-            best_bid: bid,
-            best_ask: ask,
-            bid_volume: 1.0,
-            ask_volume: 1.0,
-            timestamp_ns: UnixNanos::new(1000000000),
+            best_bid,
+            best_ask,
+            bid_volume,
+            ask_volume,
+            timestamp_ns: self.timestamp_ns,
         }
     }
 
-    #[test]
-    fn test_strategy_creation() {
-        let config = create_test_config();
-        let strategy = AvellanedaStoikov::new(config);
+    /// 深度加权中间价：买卖两侧前 `levels` 档各自的成交量加权均价的中点
+    ///
+    /// 任一侧无有效数量时返回 None
+    pub fn depth_weighted_mid(&self, levels: usize) -> Option<f64> {
+        let vwap = |side: &[(f64, f64)]| {
+            let (notional, volume) = side
+                .iter()
+                .take(levels)
+                .fold((0.0, 0.0), |(n, v), &(price, qty)| {
+                    (n + price * qty, v + qty)
+                });
+            (volume > 0.0).then(|| notional / volume)
+        };
 
-        let stats = strategy.get_stats();
-        assert_eq!(stats.quote_updates, 0);
-        assert_eq!(stats.current_inventory, 0.0);
+        Some((vwap(&self.bids)? + vwap(&self.asks)?) * 0.5)
     }
+}
 
-    #[test]
-    fn test_orderbook_update() {
-        let config = create_test_config();
-        let mut strategy = AvellanedaStoikov::new(config);
+/// 价差换算为基点（参考价格非正时返回0）
+#[inline]
+pub fn spread_to_bps(spread: f64, reference_price: f64) -> f64 {
+    if reference_price > 0.0 {
+        spread / reference_price * 10000.0
+    } else {
+        0.0
+    }
+}
 
-        let snapshot = create_test_snapshot(50000.0, 50010.0);
-        let quote = strategy.on_orderbook_update(&snapshot);
+/// AS 保留价格
+///
+/// r = s - q*γ*σ²*(T-t)
+#[inline]
+pub fn reservation_price(
+    mid: f64,
+    inventory: f64,
+    gamma: f64,
+    sigma: f64,
+    t_remaining: f64,
+) -> f64 {
+    mid - inventory * gamma * sigma * sigma * t_remaining
+}
 
-        assert!(quote.is_some());
-        let quote = quote.unwrap();
+/// γ/κ 低于该值时价差调整项使用 γ→0 的泰勒展开
+const SMALL_GAMMA_RATIO: f64 = 1e-6;
 
-        // 验证报价在合理范围内
-        assert!(quote.bid_price > 0.0);
-        assert!(quote.ask_price > quote.bid_price);
-        assert!(quote.spread > 0.0);
+/// AS 最优价差（未应用价差限制）
+///
+/// δ = γ*σ²*(T-t) + (2/γ)*ln(1 + γ/κ)
+///
+/// γ→0 时调整项趋于 2/κ（风险中性报价）：|γ/κ| 很小时按
+/// (2/κ)*(1 - x/2 + x²/3)，x = γ/κ 计算，避免 γ = 0 时除零及极小 γ 的数值不稳定
+#[inline]
+pub fn optimal_spread(gamma: f64, sigma: f64, t_remaining: f64, kappa: f64) -> f64 {
+    let spread_base = gamma * sigma * sigma * t_remaining;
+    let ratio = gamma / kappa;
+    let spread_adjustment = if ratio.abs() < SMALL_GAMMA_RATIO {
+        (2.0 / kappa) * (1.0 - ratio / 2.0 + ratio * ratio / 3.0)
+    } else {
+        (2.0 / gamma) * ratio.ln_1p()
+    };
+    spread_base + spread_adjustment
+}
 
-        let stats = strategy.get_stats();
-        assert_eq!(stats.orderbook_updates, 1);
-        assert_eq!(stats.quote_updates, 1);
+/// 价差敏感性曲面：行为 γ，列为 σ，其余参数（κ、T）取自 `base`
+///
+/// 返回未应用价差限制的模型价差，便于绘图和参数直觉分析
+pub fn spread_surface(base: &ASConfig, gammas: &[f64], sigmas: &[f64]) -> Vec<Vec<f64>> {
+    gammas
+        .iter()
+        .map(|&gamma| {
+            sigmas
+                .iter()
+                .map(|&sigma| {
+                    optimal_spread(gamma, sigma, base.time_horizon, base.price_sensitivity)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// 从历史成交拟合订单到达强度 λ(δ) = A·exp(-κδ)
+///
+/// `fills` 为 (δ, λ) 观测：报价距中间价的距离与该距离上观测到的成交强度（每秒成交次数）。
+/// 对 ln λ = ln A - κδ 做最小二乘回归，返回 (A, κ)；λ 非正或非有限的观测被忽略，
+/// 有效观测不足两个或 δ 全部相同时返回 (0.0, 0.0)
+pub fn calibrate_intensity(fills: &[(f64, f64)]) -> (f64, f64) {
+    let points: Vec<(f64, f64)> = fills
+        .iter()
+        .filter(|(delta, intensity)| delta.is_finite() && intensity.is_finite() && *intensity > 0.0)
+        .map(|&(delta, intensity)| (delta, intensity.ln()))
+        .collect();
+    if points.len() < 2 {
+        return (0.0, 0.0);
     }
 
-    #[test]
-    fn test_fill_updates_inventory() {
-        let config = create_test_config();
-        let mut strategy = AvellanedaStoikov::new(config);
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let (cov, var) = points.iter().fold((0.0, 0.0), |(cov, var), (x, y)| {
+        let dx = x - mean_x;
+        (cov + dx * (y - mean_y), var + dx * dx)
+    });
+    if var <= f64::EPSILON {
+        return (0.0, 0.0);
+    }
 
-        strategy.on_fill(OrderSide::Buy, 0.001);
-        assert_eq!(strategy.inventory.data, 0.001);
+    let slope = cov / var;
+    let intercept = mean_y - slope * mean_x;
+    (intercept.exp(), -slope)
+}
 
-        strategy.on_fill(OrderSide::Sell, 0.0005);
-        assert_eq!(strategy.inventory.data, 0.0005);
+/// 每天的纳秒数
+const NANOS_PER_DAY: u64 = 86_400_000_000_000;
+
+/// 解析 `HH:MM` 形式的 UTC 时刻，返回当天零点起的秒数
+pub fn parse_utc_time_of_day(value: &str) -> StrategyResult<u32> {
+    let invalid =
+        || StrategyError::InvalidConfig(format!("invalid time of day {value:?}, expected HH:MM"));
+    let (hours, minutes) = value.trim().split_once(':').ok_or_else(invalid)?;
+    let hours: u32 = hours.parse().map_err(|_| invalid())?;
+    let minutes: u32 = minutes.parse().map_err(|_| invalid())?;
+    ensure_config!(
+        hours < 24 && minutes < 60,
+        "time of day out of range: {value:?}"
+    );
+    Ok(hours * 3600 + minutes * 60)
+}
+
+/// 纳秒时间戳所在的 UTC 日（自 Unix 纪元起的天数），用于判断跨日
+#[inline]
+pub fn utc_day(now_ns: u64) -> u64 {
+    now_ns / NANOS_PER_DAY
+}
+
+/// 当前 UTC 时刻（纳秒时间戳）是否已到达当天的指定时刻
+#[inline]
+pub fn time_of_day_reached(now_ns: u64, secs_of_day: u32) -> bool {
+    (now_ns % NANOS_PER_DAY) / 1_000_000_000 >= secs_of_day as u64
+}
+
+/// 检查订单簿是否可用于报价
+///
+/// 要求买卖价均为正且未交叉（ask >= bid）；空边（价格为0）或交叉盘口返回false
+/// 价格或挂单量为 NaN/Inf、挂单量为负时同样返回false（行情异常，避免污染历史）
+#[inline]
+pub fn is_valid_book(snapshot: &OrderBookSnapshot) -> bool {
+    snapshot.best_bid.is_finite()
+        && snapshot.best_ask.is_finite()
+        && snapshot.bid_volume.is_finite()
+        && snapshot.ask_volume.is_finite()
+        && snapshot.bid_volume >= 0.0
+        && snapshot.ask_volume >= 0.0
+        && snapshot.best_bid > 0.0
+        && snapshot.best_ask > 0.0
+        && snapshot.best_ask >= snapshot.best_bid
+}
+
+/// 微观价格：按对手方挂单量加权的中间价
+///
+/// microprice = (bid * ask_volume + ask * bid_volume) / (bid_volume + ask_volume)
+#[inline]
+pub fn microprice(snapshot: &OrderBookSnapshot) -> f64 {
+    let total_volume = snapshot.bid_volume + snapshot.ask_volume;
+    if total_volume <= 0.0 {
+        return (snapshot.best_bid + snapshot.best_ask) * 0.5;
     }
+    (snapshot.best_bid * snapshot.ask_volume + snapshot.best_ask * snapshot.bid_volume)
+        / total_volume
+}
 
-    #[test]
-    fn test_volatility_calculation() {
-        let config = create_test_config();
-        let mut strategy = AvellanedaStoikov::new(config);
+/// 将数量向下取整到步长网格，不足一个步长时返回0
+#[inline]
+pub fn round_down_to_lot(size: f64, lot_size: f64) -> f64 {
+    if lot_size <= 0.0 {
+        return size;
+    }
+    // 容差避免 0.003 / 0.001 = 2.9999... 之类的浮点误差
+    (size / lot_size + 1e-9).floor() * lot_size
+}
 
-        // 添加一些价格数据
-        for i in 0..30 {
-            let price = 50000.0 + (i as f64) * 10.0;
-            strategy.update_price_history(price);
+/// 按权重将总数量分配到各档并取整到步长（最大余数法）
+///
+/// 先按比例向下取整到整数个步长，剩余步长依次分给小数部分最大的档位，
+/// 使各档之和等于总数量向下取整后的步长数（与目标相差不超过一个步长）
+pub fn distribute_lots(total: f64, weights: &[f64], lot_size: f64) -> Vec<f64> {
+    let weight_sum: f64 = weights.iter().sum();
+    if weights.is_empty() || total <= 0.0 || weight_sum <= 0.0 {
+        return vec![0.0; weights.len()];
+    }
+    if lot_size <= 0.0 {
+        return weights.iter().map(|w| total * w / weight_sum).collect();
+    }
+
+    let total_lots = (total / lot_size + 1e-9).floor();
+    let shares: Vec<f64> = weights
+        .iter()
+        .map(|w| total_lots * w / weight_sum)
+        .collect();
+    let mut lots: Vec<f64> = shares.iter().map(|share| (share + 1e-9).floor()).collect();
+
+    let mut order: Vec<usize> = (0..shares.len()).collect();
+    order.sort_by(|&a, &b| {
+        let remainder = |i: usize| shares[i] - lots[i];
+        remainder(b).total_cmp(&remainder(a))
+    });
+    let leftover = (total_lots - lots.iter().sum::<f64>()).round().max(0.0) as usize;
+    for &i in order.iter().take(leftover) {
+        lots[i] += 1.0;
+    }
+
+    lots.iter().map(|n| n * lot_size).collect()
+}
+
+/// K线数据
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Bar {
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+    pub timestamp_ns: u64,
+}
+
+/// 价差被限制的方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ClampDirection {
+    /// 模型价差小于下限，被抬高到 min_spread_bps
+    Min,
+    /// 模型价差大于上限，被压低到 max_spread_bps
+    Max,
+}
+
+/// 报价结果
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct QuoteUpdate {
+    pub bid_price: f64,
+    pub ask_price: f64,
+    pub bid_size: f64,
+    pub ask_size: f64,
+    /// 模型最优价差（已按最小/最大价差限制）
+    ///
+    /// 不随盘口、保护价格等调整变化，实际挂单价差为 `ask_price - bid_price`
+    pub spread: f64,
+    /// 模型最优价差（基点，相对保留价格）
+    pub spread_bps: f64,
+    /// 买价距中间价（基点）：(mid - bid) / mid * 1e4，库存偏斜时两侧不同
+    #[serde(default)]
+    pub bid_distance_bps: f64,
+    /// 卖价距中间价（基点）：(ask - mid) / mid * 1e4
+    #[serde(default)]
+    pub ask_distance_bps: f64,
+    pub reservation_price: f64,
+    /// 模型价差是否被最小/最大价差限制
+    pub spread_clamped: Option<ClampDirection>,
+}
+
+impl QuoteUpdate {
+    /// 按中间价重算两侧距离（中间价无效时为0）
+    #[inline]
+    pub fn update_distances(&mut self, mid: f64) {
+        if mid > 0.0 {
+            self.bid_distance_bps = (mid - self.bid_price) / mid * 10000.0;
+            self.ask_distance_bps = (self.ask_price - mid) / mid * 10000.0;
+        } else {
+            self.bid_distance_bps = 0.0;
+            self.ask_distance_bps = 0.0;
         }
+    }
+}
 
-        let vol = strategy.calculate_standard_volatility();
-        assert!(vol > 0.0);
-        assert!(vol < 1.0); // 波动率应该在合理范围
+/// 不报价的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuppressReason {
+    /// 空边或交叉盘口
+    InvalidBook,
+    /// 熔断（单日亏损或波动率过高）
+    KillSwitch,
+    /// 一个价格单位宽的盘口且 `tight_book_policy = Skip`
+    TightBook,
+    /// 报价超出 `max_quote_deviation_bps` 价格带
+    PriceBand,
+}
+
+/// 订单簿更新的处理结果
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BookOutcome {
+    /// 新报价
+    Quote(QuoteUpdate),
+    /// 节流：距上次报价不足最小间隔，已有挂单应保持不变
+    Throttled,
+    /// 当前不应报价，已有挂单应撤销
+    Suppressed(SuppressReason),
+}
+
+impl BookOutcome {
+    /// 新报价（节流或不报价时为 None）
+    #[inline]
+    pub fn quote(self) -> Option<QuoteUpdate> {
+        match self {
+            Self::Quote(quote) => Some(quote),
+            Self::Throttled | Self::Suppressed(_) => None,
+        }
     }
+}
 
-    #[test]
-    fn test_inventory_penalty() {
-        let config = create_test_config();
-        let mut strategy = AvellanedaStoikov::new(config);
+/// 报价监听器：订单簿更新每产生一次最终报价（取整、盘口限制、价格带检查之后）调用一次，
+/// 例如推送到自有 OMS；节流或拒绝报价时不调用
+pub trait QuoteListener: std::fmt::Debug + Send {
+    fn on_quote(&mut self, quote: &QuoteUpdate);
+}
 
-        let snapshot = create_test_snapshot(50000.0, 50010.0);
+/// 闭包形式的报价监听器
+struct FnQuoteListener<F>(F);
 
-        // 无库存时的报价
-        let quote1 = strategy.on_orderbook_update(&snapshot).unwrap();
+impl<F> std::fmt::Debug for FnQuoteListener<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("FnQuoteListener")
+    }
+}
 
-        // 增加库存
-        strategy.on_fill(OrderSide::Buy, 0.01);
+impl<F: FnMut(&QuoteUpdate) + Send> QuoteListener for FnQuoteListener<F> {
+    fn on_quote(&mut self, quote: &QuoteUpdate) {
+        (self.0)(quote)
+    }
+}
 
-        // 有库存时的报价
-        let quote2 = strategy.on_orderbook_update(&snapshot).unwrap();
+/// 把报价限制在盘口外侧（买价不高于最优买价，卖价不低于最优卖价）
+#[inline]
+fn clamp_to_touch(quote: &mut QuoteUpdate, snapshot: &OrderBookSnapshot) {
+    quote.bid_price = quote.bid_price.min(snapshot.best_bid);
+    quote.ask_price = quote.ask_price.max(snapshot.best_ask);
+}
 
-        // 库存增加后，买价应该降低（惩罚）
-        assert!(quote2.bid_price < quote1.bid_price);
+/// 把报价限制在保护价格外侧（买价不高于、卖价不低于保护价格）
+#[inline]
+fn clamp_to_guard(quote: &mut QuoteUpdate, guard: f64) {
+    quote.bid_price = quote.bid_price.min(guard);
+    quote.ask_price = quote.ask_price.max(guard);
+}
+
+/// 按合约类型创建盈亏追踪器
+fn pnl_tracker(config: &ASConfig) -> PnlTracker {
+    if config.inverse {
+        PnlTracker::inverse()
+    } else {
+        PnlTracker::new()
+    }
+}
+
+/// 按配置创建逆向选择追踪器（未启用时容量为0，不保存成交）
+fn adverse_selection_tracker(config: &ASConfig) -> AdverseSelectionTracker {
+    match config.adverse_selection {
+        Some(adverse) => AdverseSelectionTracker::new(adverse.window, adverse.markout_ns),
+        None => AdverseSelectionTracker::new(0, 0),
+    }
+}
+
+/// 多档报价中的一档
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LadderLevel {
+    pub bid_price: f64,
+    pub ask_price: f64,
+    pub bid_size: f64,
+    pub ask_size: f64,
+}
+
+/// 对冲信号（建议以市价单执行）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HedgeSignal {
+    pub side: OrderSide,
+    pub quantity: f64,
+}
+
+/// 策略状态格式标识（写在版本号之前，用于区分没有版本号的第一版格式）
+///
+/// 按小端 f64 解释为 NaN，而第一版以有限的中间价开头，两种格式不会混淆
+const STATE_MAGIC: [u8; 8] = *b"ASST\x00\x00\xf8\x7f";
+
+/// 当前策略状态格式版本
+const STATE_VERSION: u32 = 2;
+
+/// 第一版策略状态：没有格式标识和版本号，K线历史只有高低价
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StrategyStateV1 {
+    mid_price: f64,
+    volatility: f64,
+    inventory: f64,
+    price_history: Vec<f64>,
+    high_low_history: Vec<(f64, f64)>,
+    quote_updates: u64,
+    orderbook_updates: u64,
+    inventory_adjustments: u64,
+    last_update_ns: u64,
+}
+
+/// 可持久化的策略状态（用于崩溃恢复）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StrategyState {
+    mid_price: f64,
+    volatility: f64,
+    inventory: f64,
+    price_history: Vec<f64>,
+    bar_history: Vec<(f64, f64, f64, f64)>,
+    quote_updates: u64,
+    orderbook_updates: u64,
+    inventory_adjustments: u64,
+    last_update_ns: u64,
+    /// 持仓、平均成本与已实现盈亏
+    pnl: PnlTracker,
+    session_pnl_start: f64,
+    killed: bool,
+    fair_value: f64,
+    inventory_entry_ns: Option<f64>,
+    session_start_ns: Option<u64>,
+}
+
+impl StrategyState {
+    /// 编码为 格式标识 + 版本号 + 状态
+    fn encode(&self) -> StrategyResult<Vec<u8>> {
+        Ok(bincode::serialize(&(STATE_MAGIC, STATE_VERSION, self))?)
+    }
+
+    /// 解码当前版本或第一版格式（第一版按 `config` 迁移）
+    fn decode(bytes: &[u8], config: &ASConfig) -> StrategyResult<Self> {
+        let header: Option<([u8; 8], u32)> = bincode::deserialize(bytes).ok();
+        let Some((STATE_MAGIC, version)) = header else {
+            let state: StrategyStateV1 = bincode::deserialize(bytes)?;
+            if !state.mid_price.is_finite() {
+                return Err(StrategyError::Serde(
+                    "invalid strategy state: non-finite mid price".to_string(),
+                ));
+            }
+            return Ok(Self::from_v1(state, config));
+        };
+
+        match version {
+            STATE_VERSION => {
+                let (_, _, state): ([u8; 8], u32, Self) = bincode::deserialize(bytes)?;
+                Ok(state)
+            }
+            _ => Err(StrategyError::Serde(format!(
+                "unsupported strategy state version {}",
+                version
+            ))),
+        }
+    }
+
+    /// 第一版迁移：缺少的开盘/收盘价取高低价中点（Garman-Klass 收盘项为0），
+    /// 盈亏追踪以中间价为成本重建持仓，建仓时间取最后更新时间
+    fn from_v1(state: StrategyStateV1, config: &ASConfig) -> Self {
+        let mut pnl = pnl_tracker(config);
+        pnl.set_position(state.inventory, state.mid_price);
+
+        Self {
+            mid_price: state.mid_price,
+            volatility: state.volatility,
+            inventory: state.inventory,
+            price_history: state.price_history,
+            bar_history: state
+                .high_low_history
+                .into_iter()
+                .map(|(high, low)| {
+                    let mid = (high + low) * 0.5;
+                    (mid, high, low, mid)
+                })
+                .collect(),
+            quote_updates: state.quote_updates,
+            orderbook_updates: state.orderbook_updates,
+            inventory_adjustments: state.inventory_adjustments,
+            last_update_ns: state.last_update_ns,
+            pnl,
+            session_pnl_start: 0.0,
+            killed: false,
+            fair_value: state.mid_price,
+            inventory_entry_ns: (state.inventory != 0.0).then_some(state.last_update_ns as f64),
+            session_start_ns: None,
+        }
+    }
+}
+
+/// AS策略主体 - 缓存行对齐优化
+#[repr(align(128))]
+#[derive(Debug)]
+pub struct AvellanedaStoikov {
+    /// 配置参数
+    config: ASConfig,
+
+    /// 市场状态（缓存行对齐）
+    mid_price: CacheAligned<f64>,
+    /// 平滑中间价（公允价值），仅在 `skew_around_fair_value` 启用时与中间价不同
+    fair_value: f64,
+    microprice: f64,
+    volatility: CacheAligned<f64>,
+    inventory: CacheAligned<f64>,
+
+    /// 价格历史（用于波动率计算）
+    price_history: RingBuffer<f64>,
+    /// K线历史 (open, high, low, close)
+    bar_history: RingBuffer<(f64, f64, f64, f64)>,
+
+    /// 性能计数器（缓存行对齐，避免false sharing）
+    quote_updates: CacheAligned<u64>,
+    orderbook_updates: CacheAligned<u64>,
+    inventory_adjustments: CacheAligned<u64>,
+    spread_clamp_events: u64,
+    self_cross_events: u64,
+
+    /// 最后更新时间
+    last_update_ns: UnixNanos,
+
+    /// 统计订阅者
+    stats_subscribers: Mutex<Vec<SyncSender<StrategyStats>>>,
+
+    /// 价差捕获统计
+    spread_capture: SpreadCaptureTracker,
+
+    /// 盈亏追踪
+    pnl: PnlTracker,
+    /// 当日起始总盈亏（用于计算当日盈亏）
+    session_pnl_start: f64,
+    /// 熔断标志（超过单日最大亏损，锁定到 `reset_session`）
+    killed: bool,
+    /// 波动率暂停标志（报价波动率超过盈亏平衡波动率，回落后自动解除）
+    volatility_halted: bool,
+
+    /// 逐K线绩效追踪
+    performance: PerformanceTracker,
+    /// 上一根K线收盘时的盯市权益
+    last_bar_equity: Option<f64>,
+
+    /// 交易时段起点（纳秒），未显式设置时取首个有效订单簿时间
+    session_start_ns: Option<u64>,
+    /// 是否已请求强制平仓（例如到达收盘时刻），持平前只报减仓一侧
+    flatten_requested: bool,
+    /// 当前持仓的加权平均建仓时间（纳秒），空仓时为 None
+    inventory_entry_ns: Option<f64>,
+    /// 买/卖侧最近一次成交时间（纳秒），用于成交后冷却
+    last_bid_fill_ns: Option<u64>,
+    last_ask_fill_ns: Option<u64>,
+    /// 逆向选择统计与价差放宽截止时间（纳秒）
+    adverse_selection: AdverseSelectionTracker,
+    adverse_widen_until_ns: Option<u64>,
+    /// 最新市场成交价
+    last_trade_price: Option<f64>,
+    /// 逐笔成交对数收益率平方的 EWMA（首笔收益率之前为 None）
+    trade_variance: Option<f64>,
+    /// 当前K线内的成交收益率笔数
+    trades_in_bar: u64,
+    /// 每根K线成交笔数的 EWMA（尚无完整K线时为 None），用于把逐笔方差换算为K线方差
+    trades_per_bar: Option<f64>,
+    /// 最近一次输出报价的时间（纳秒），用于节流
+    last_quote_ns: Option<u64>,
+    /// 注入的时钟，None 时以订单簿快照时间戳为当前时间
+    clock: Option<Arc<dyn Clock>>,
+    /// 波动率序列记录（仅 `record_volatility` 时启用）
+    volatility_recorder: Option<VolatilityRecorder>,
+    /// 受保护参考价格：买价不高于、卖价不低于该价格（由外部逐笔更新）
+    reference_price_guard: Option<f64>,
+    /// 报价监听器，None 时热路径上只有一次分支判断
+    quote_listener: Option<Box<dyn QuoteListener>>,
+}
+
+impl AvellanedaStoikov {
+    /// 创建新策略实例（不校验配置）
+    ///
+    /// 非法配置（如 `risk_aversion = 0`）不会在这里报错，而是到首次报价时才产生
+    /// NaN 或 panic；新代码请使用 [`AvellanedaStoikov::try_new`]
+    pub fn new(config: ASConfig) -> Self {
+        let capacity = config.volatility_window;
+        let initial_volatility = config.initial_volatility;
+        let adverse_selection = adverse_selection_tracker(&config);
+        let volatility_recorder = config.record_volatility.then(VolatilityRecorder::new);
+        let pnl = pnl_tracker(&config);
+
+        Self {
+            config,
+            mid_price: CacheAligned::new(0.0),
+            fair_value: 0.0,
+            microprice: 0.0,
+            volatility: CacheAligned::new(initial_volatility),
+            inventory: CacheAligned::new(0.0),
+            price_history: RingBuffer::new(capacity),
+            bar_history: RingBuffer::new(capacity),
+            quote_updates: CacheAligned::new(0),
+            orderbook_updates: CacheAligned::new(0),
+            inventory_adjustments: CacheAligned::new(0),
+            spread_clamp_events: 0,
+            self_cross_events: 0,
+            last_update_ns: UnixNanos::new(0),
+            stats_subscribers: Mutex::new(Vec::new()),
+            spread_capture: SpreadCaptureTracker::new(),
+            pnl,
+            session_pnl_start: 0.0,
+            killed: false,
+            volatility_halted: false,
+            performance: PerformanceTracker::new(PERFORMANCE_CAPACITY),
+            last_bar_equity: None,
+            session_start_ns: None,
+            flatten_requested: false,
+            inventory_entry_ns: None,
+            last_bid_fill_ns: None,
+            last_ask_fill_ns: None,
+            adverse_selection,
+            adverse_widen_until_ns: None,
+            last_trade_price: None,
+            trade_variance: None,
+            trades_in_bar: 0,
+            trades_per_bar: None,
+            last_quote_ns: None,
+            clock: None,
+            volatility_recorder,
+            reference_price_guard: None,
+            quote_listener: None,
+        }
+    }
+
+    /// 校验配置后创建策略实例
+    pub fn try_new(config: ASConfig) -> StrategyResult<Self> {
+        config.validate()?;
+        Ok(Self::new(config))
+    }
+
+    /// 注入时钟（测试或回放中确定性地控制当前时间）
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// 设置报价监听器（替换已有监听器）
+    pub fn set_quote_listener(&mut self, listener: Box<dyn QuoteListener>) {
+        self.quote_listener = Some(listener);
+    }
+
+    /// 以闭包作为报价监听器
+    pub fn set_on_quote<F>(&mut self, callback: F)
+    where
+        F: FnMut(&QuoteUpdate) + Send + 'static,
+    {
+        self.set_quote_listener(Box::new(FnQuoteListener(callback)));
+    }
+
+    /// 移除报价监听器
+    pub fn clear_quote_listener(&mut self) {
+        self.quote_listener = None;
+    }
+
+    /// 当前时间：优先使用注入的时钟，否则使用行情时间戳
+    #[inline]
+    fn now_ns(&self, event_ns: u64) -> u64 {
+        self.clock.as_ref().map_or(event_ns, |clock| clock.now_ns())
+    }
+
+    /// 处理订单簿更新 - 超低延迟热路径
+    ///
+    /// 性能要求: < 20μs
+    #[inline(always)]
+    pub fn on_orderbook_update(&mut self, snapshot: &OrderBookSnapshot) -> Option<QuoteUpdate> {
+        self.on_orderbook_update_outcome(snapshot).quote()
+    }
+
+    /// 处理订单簿更新并返回处理结果，区分节流（保留挂单）与不报价（撤销挂单）
+    #[inline(always)]
+    pub fn on_orderbook_update_outcome(&mut self, snapshot: &OrderBookSnapshot) -> BookOutcome {
+        let reference_price = self.select_reference_price(snapshot, None);
+        self.process_book(snapshot, reference_price)
+    }
+
+    /// 处理多档订单簿更新（`ReferencePrice::DepthWeighted` 需要多档数据）
+    #[inline]
+    pub fn on_orderbook_update_l2(
+        &mut self,
+        snapshot: &OrderBookSnapshotL2,
+    ) -> Option<QuoteUpdate> {
+        let top = snapshot.top_of_book();
+        let reference_price = self.select_reference_price(&top, Some(snapshot));
+        self.process_book(&top, reference_price).quote()
+    }
+
+    /// 处理本交易所订单簿更新，以多交易所合并盘口中间价作为参考价格
+    ///
+    /// 合并盘口为空时按 `reference_price` 使用本交易所盘口
+    #[inline]
+    pub fn on_orderbook_update_consolidated(
+        &mut self,
+        home: &OrderBookSnapshot,
+        consolidated: &ConsolidatedBook,
+    ) -> Option<QuoteUpdate> {
+        let reference_price = consolidated
+            .mid()
+            .unwrap_or_else(|| self.select_reference_price(home, None));
+        self.process_book(home, reference_price).quote()
+    }
+
+    /// 设置受保护参考价格（例如全市场最优价），None 表示取消保护
+    ///
+    /// 设置后报价不会穿过该价格：买价不高于、卖价不低于它
+    #[inline]
+    pub fn set_reference_price_guard(&mut self, guard: Option<f64>) {
+        self.reference_price_guard = guard.filter(|price| price.is_finite() && *price > 0.0);
+    }
+
+    /// 当前受保护参考价格
+    pub fn reference_price_guard(&self) -> Option<f64> {
+        self.reference_price_guard
+    }
+
+    /// 记录最新成交价（`ReferencePrice::LastTrade` 使用），非正或非有限值忽略
+    #[inline]
+    pub fn on_trade(&mut self, price: f64) {
+        if price.is_finite() && price > 0.0 {
+            if let Some(previous) = self.last_trade_price {
+                self.update_trade_variance((price / previous).ln());
+            }
+            self.last_trade_price = Some(price);
+            self.record_volatility(self.last_update_ns.as_u64());
+        }
+    }
+
+    #[inline]
+    fn update_trade_variance(&mut self, log_return: f64) {
+        let squared = log_return * log_return;
+        let decay = self.config.trade_volatility_decay;
+        self.trade_variance = Some(match self.trade_variance {
+            Some(variance) => decay * variance + (1.0 - decay) * squared,
+            None => squared,
+        });
+        self.trades_in_bar += 1;
+    }
+
+    /// K线结束时更新每根K线成交笔数的 EWMA（首笔成交之前的K线不计入）
+    #[inline]
+    fn update_trades_per_bar(&mut self) {
+        if self.trade_variance.is_none() {
+            return;
+        }
+        let count = std::mem::take(&mut self.trades_in_bar) as f64;
+        let decay = self.config.trade_volatility_decay;
+        self.trades_per_bar = Some(match self.trades_per_bar {
+            Some(average) => decay * average + (1.0 - decay) * count,
+            None => count,
+        });
+    }
+
+    /// 成交波动率（逐笔对数收益率的 EWMA 标准差），尚无收益率时为 None
+    pub fn trade_volatility(&self) -> Option<f64> {
+        self.trade_variance.map(f64::sqrt)
+    }
+
+    /// 换算到K线周期的成交波动率：σ_tick·√(每根K线成交笔数)，尚无完整K线时等于逐笔波动率
+    pub fn trade_volatility_per_bar(&self) -> Option<f64> {
+        let variance = self.trade_variance?;
+        Some((variance * self.trades_per_bar.unwrap_or(1.0)).sqrt())
+    }
+
+    /// 按 `vol_blend_weight` 混合K线周期的成交波动率与K线波动率；只有一个来源有数据时
+    /// 完全使用该来源
+    #[inline]
+    fn blended_volatility(&self) -> f64 {
+        let weight = self.config.vol_blend_weight;
+        if weight <= 0.0 {
+            return self.volatility.data;
+        }
+        match (self.trade_volatility_per_bar(), self.bar_history.is_empty()) {
+            (Some(trade), false) => weight * trade + (1.0 - weight) * self.volatility.data,
+            (Some(trade), true) => trade,
+            (None, _) => self.volatility.data,
+        }
+    }
+
+    /// 记录当前报价使用的波动率（混合成交波动率后，未启用记录时无操作）
+    #[inline]
+    fn record_volatility(&mut self, timestamp_ns: u64) {
+        if self.volatility_recorder.is_none() {
+            return;
+        }
+        let volatility = self.quote_volatility();
+        if let Some(recorder) = &mut self.volatility_recorder {
+            recorder.record(timestamp_ns, volatility);
+        }
+    }
+
+    /// 已记录的波动率序列（`record_volatility` 关闭时为 None）
+    pub fn volatility_recorder(&self) -> Option<&VolatilityRecorder> {
+        self.volatility_recorder.as_ref()
+    }
+
+    /// 按 `reference_price_source` 选取参考价格，所选来源缺少数据时回退到中间价
+    #[inline]
+    fn select_reference_price(
+        &self,
+        top: &OrderBookSnapshot,
+        depth: Option<&OrderBookSnapshotL2>,
+    ) -> f64 {
+        let mid = (top.best_bid + top.best_ask) * 0.5;
+        match self.config.reference_price_source() {
+            ReferencePrice::Mid => mid,
+            ReferencePrice::Microprice => microprice(top),
+            ReferencePrice::LastTrade => self.last_trade_price.unwrap_or(mid),
+            ReferencePrice::DepthWeighted { levels } => depth
+                .and_then(|book| book.depth_weighted_mid(levels))
+                .unwrap_or(mid),
+        }
+    }
+
+    /// 订单簿更新公共路径：校验、更新参考价格与历史、计算报价
+    #[inline(always)]
+    fn process_book(&mut self, snapshot: &OrderBookSnapshot, reference_price: f64) -> BookOutcome {
+        // 空边或交叉盘口无法给出可信中间价，跳过本次更新
+        if !is_valid_book(snapshot) {
+            tracing::warn!(
+                best_bid = snapshot.best_bid,
+                best_ask = snapshot.best_ask,
+                "Invalid order book, skipping quote"
+            );
+            return BookOutcome::Suppressed(SuppressReason::InvalidBook);
+        }
+
+        self.orderbook_updates.data += 1;
+        let now_ns = self.now_ns(snapshot.timestamp_ns.as_u64());
+        self.last_update_ns = UnixNanos::new(now_ns);
+        self.roll_session(now_ns);
+
+        // 更新参考价格（首个有效值不做平滑）
+        let alpha = self.config.mid_smoothing_alpha;
+        let fair_value_skew = self.config.skew_around_fair_value;
+        let prev_mid = if fair_value_skew {
+            self.fair_value
+        } else {
+            self.mid_price.data
+        };
+        let smoothed = if prev_mid > 0.0 && alpha < 1.0 {
+            alpha * reference_price + (1.0 - alpha) * prev_mid
+        } else {
+            reference_price
+        };
+        // 公允价值偏斜：EMA 只作为公允价值，报价中间价跟随当前盘口
+        let new_mid = if fair_value_skew {
+            reference_price
+        } else {
+            smoothed
+        };
+        self.fair_value = smoothed;
+        self.mid_price.data = new_mid;
+        self.microprice = microprice(snapshot);
+
+        // 更新价格历史
+        self.update_price_history(new_mid);
+        self.update_adverse_selection(new_mid, now_ns);
+
+        // 熔断后停止报价
+        if self.check_kill_switch() {
+            return BookOutcome::Suppressed(SuppressReason::KillSwitch);
+        }
+
+        // 节流：距上次报价不足最小间隔时不重新报价（已有挂单保持不变）
+        let interval = self.config.min_quote_interval_ns;
+        if interval > 0
+            && self
+                .last_quote_ns
+                .is_some_and(|last| now_ns.saturating_sub(last) < interval)
+        {
+            return BookOutcome::Throttled;
+        }
+
+        // 计算并返回新报价
+        let mut quote = self.calculate_quotes(now_ns);
+        let model_quote = quote;
+
+        // 一个价格单位宽的盘口：避免报价落入盘口内成为吃单
+        // （按价格单位计算容差，f64 价差在常见价位上的误差远大于 f64::EPSILON）
+        let tick = self.tick_size();
+        if snapshot.best_ask - snapshot.best_bid <= tick + tick * 1e-6 {
+            match self.config.tight_book_policy {
+                TightBookPolicy::Join => clamp_to_touch(&mut quote, snapshot),
+                TightBookPolicy::Skip => {
+                    tracing::debug!(
+                        best_bid = snapshot.best_bid,
+                        best_ask = snapshot.best_ask,
+                        "One-tick book, skipping quote"
+                    );
+                    return BookOutcome::Suppressed(SuppressReason::TightBook);
+                }
+            }
+        }
+
+        // 不改善盘口：报价最多挂在最优价上，避免过度让价被逆向选择
+        // 排队感知：先加入最优价，排队量大时再改善一个价格单位
+        let queue_aware = self.config.queue_aware && self.config.allow_price_improvement;
+        if !self.config.allow_price_improvement || queue_aware {
+            clamp_to_touch(&mut quote, snapshot);
+        }
+        if queue_aware {
+            self.improve_long_queues(&mut quote, &model_quote, snapshot);
+        }
+
+        // 改善盘口可能把报价推过保护价格，最后再限制一次
+        if let Some(guard) = self.reference_price_guard {
+            clamp_to_guard(&mut quote, guard);
+        }
+        quote.update_distances(self.mid_price.data);
+
+        // 价格带保护：任一侧偏离中间价过远视为模型异常，不报价
+        let band = self.config.max_quote_deviation_bps;
+        if band > 0.0
+            && (quote.bid_distance_bps.abs() > band || quote.ask_distance_bps.abs() > band)
+        {
+            tracing::warn!(
+                bid = quote.bid_price,
+                ask = quote.ask_price,
+                mid = self.mid_price.data,
+                max_deviation_bps = band,
+                "Quote outside price band, suppressing"
+            );
+            return BookOutcome::Suppressed(SuppressReason::PriceBand);
+        }
+
+        self.last_quote_ns = Some(now_ns);
+        if let Some(listener) = self.quote_listener.as_mut() {
+            listener.on_quote(&quote);
+        }
+        BookOutcome::Quote(quote)
+    }
+
+    /// 排队估计：挂在最优价的一侧若前方挂单量超过 `max_queue_volume`，
+    /// 且模型价格不差于改善一个价格单位后的价格、改善后不触及对手价，则改善一个价格单位
+    ///
+    /// 改善后的挂单价差低于 `min_spread_bps` 或双边 maker 手续费时不改善
+    fn improve_long_queues(
+        &self,
+        quote: &mut QuoteUpdate,
+        model: &QuoteUpdate,
+        snapshot: &OrderBookSnapshot,
+    ) {
+        let tick = self.tick_size();
+        let tolerance = tick * 1e-6;
+        let max_queue = self.config.max_queue_volume;
+        let floor_bps = self
+            .config
+            .min_spread_bps
+            .max(2.0 * self.config.maker_fee_bps);
+        let above_floor =
+            |bid: f64, ask: f64| ask - bid >= (bid + ask) * 0.5 * floor_bps / 10000.0 - tolerance;
+
+        let improved_bid = snapshot.best_bid + tick;
+        if (quote.bid_price - snapshot.best_bid).abs() <= tolerance
+            && snapshot.bid_volume > max_queue
+            && model.bid_price >= improved_bid - tolerance
+            && improved_bid < snapshot.best_ask - tolerance
+            && above_floor(improved_bid, quote.ask_price)
+        {
+            quote.bid_price = improved_bid;
+        }
+
+        let improved_ask = snapshot.best_ask - tick;
+        if (quote.ask_price - snapshot.best_ask).abs() <= tolerance
+            && snapshot.ask_volume > max_queue
+            && model.ask_price <= improved_ask + tolerance
+            && improved_ask > quote.bid_price + tolerance
+            && above_floor(quote.bid_price, improved_ask)
+        {
+            quote.ask_price = improved_ask;
+        }
+    }
+
+    /// 最小价格单位（见 [`ASConfig::tick_size`]）
+    #[inline]
+    pub fn tick_size(&self) -> f64 {
+        self.config.tick_size()
+    }
+
+    /// 处理K线更新
+    #[inline]
+    pub fn on_bar(&mut self, bar: &Bar) {
+        // 更新K线历史（用于Parkinson / Garman-Klass波动率）
+        self.bar_history
+            .push((bar.open, bar.high, bar.low, bar.close));
+
+        // 重新计算波动率
+        self.volatility.data = match self.config.volatility_estimator() {
+            VolatilityEstimator::Parkinson => self.calculate_parkinson_volatility(),
+            VolatilityEstimator::GarmanKlass => self.calculate_garman_klass_volatility(),
+            VolatilityEstimator::Standard => self.calculate_standard_volatility(),
+        };
+        self.update_trades_per_bar();
+        self.record_volatility(bar.timestamp_ns);
+
+        // 以收盘价盯市，记录本K线权益变化
+        let equity = self.pnl.total_pnl(bar.close);
+        if let Some(prev) = self.last_bar_equity {
+            self.performance.record(equity - prev);
+        }
+        self.last_bar_equity = Some(equity);
+    }
+
+    /// 处理订单成交（按当前中间价成交，以最近一次行情时间作为成交时间）
+    #[inline]
+    pub fn on_fill(&mut self, side: OrderSide, quantity: f64) {
+        self.on_fill_with_price(side, quantity, self.mid_price.data);
+    }
+
+    /// 处理指定价格的订单成交（以最近一次行情时间作为成交时间）
+    #[inline]
+    pub fn on_fill_with_price(&mut self, side: OrderSide, quantity: f64, price: f64) {
+        self.on_fill_at(side, quantity, price, self.last_update_ns.as_u64());
+    }
+
+    /// 处理订单成交，`timestamp_ns` 为成交时间（成交回报的 ts_event），
+    /// 用于成交后冷却、建仓时间和逆向选择统计
+    pub fn on_fill_at(&mut self, side: OrderSide, quantity: f64, price: f64, timestamp_ns: u64) {
+        if !self.apply_fill(side, quantity, price, timestamp_ns) {
+            return;
+        }
+        match side {
+            OrderSide::Buy => self.last_bid_fill_ns = Some(timestamp_ns),
+            OrderSide::Sell => self.last_ask_fill_ns = Some(timestamp_ns),
+            OrderSide::NoOrderSide => {}
+        }
+        self.adverse_selection
+            .on_fill(side, self.mid_price.data, timestamp_ns);
+        self.spread_capture.on_fill(side, quantity, price);
+    }
+
+    /// 处理对冲单成交：只更新库存和盈亏
+    ///
+    /// 对冲单不是报价成交，不触发成交后冷却，也不计入价差捕获和逆向选择统计
+    pub fn on_hedge_fill(&mut self, side: OrderSide, quantity: f64, price: f64, timestamp_ns: u64) {
+        self.apply_fill(side, quantity, price, timestamp_ns);
+    }
+
+    /// 更新库存、建仓时间和盈亏并检查熔断，方向缺失时忽略并返回 false
+    fn apply_fill(&mut self, side: OrderSide, quantity: f64, price: f64, now: u64) -> bool {
+        let previous = self.inventory.data;
+        match side {
+            OrderSide::Buy => self.inventory.data += quantity,
+            OrderSide::Sell => self.inventory.data -= quantity,
+            // 方向缺失的成交无法更新库存
+            OrderSide::NoOrderSide => {
+                tracing::warn!(quantity, price, "Fill without order side, ignoring");
+                return false;
+            }
+        }
+        self.inventory_adjustments.data += 1;
+        self.update_inventory_entry(previous, now);
+        self.pnl
+            .on_fill_at_mid(side, quantity, price, self.mid_price.data);
+        self.check_kill_switch();
+
+        // 检查库存限制
+        let limit = self.config.inventory_limit(self.inventory.data);
+        if self.inventory.data.abs() > limit {
+            tracing::warn!(
+                inventory = self.inventory.data,
+                max = limit,
+                "Inventory exceeds limit"
+            );
+        }
+        true
+    }
+
+    /// 直接设置库存（如启动时同步已有持仓），按给定均价记入盈亏追踪
+    ///
+    /// 不视为成交：不触发成交后冷却，也不计入价差捕获和逆向选择统计。
+    /// 建仓时间取最近一次行情时间，应在首次订单簿更新之后调用
+    pub fn set_inventory(&mut self, inventory: f64, avg_price: f64) {
+        self.inventory.data = inventory;
+        self.inventory_entry_ns = None;
+        self.update_inventory_entry(0.0, self.last_update_ns.as_u64());
+        self.pnl.set_position(inventory, avg_price);
+    }
+
+    /// 计算AS模型报价 - 核心算法
+    ///
+    /// 性能要求: < 10μs
+    ///
+    /// 不经过 `process_book` 的各项门控（熔断、预热、盘口检查等），只供内部调用
+    #[inline]
+    fn calculate_quotes(&mut self, timestamp_ns: u64) -> QuoteUpdate {
+        self.quote_updates.data += 1;
+
+        let interval = self.config.stats_interval;
+        if interval > 0 && self.quote_updates.data.is_multiple_of(interval) {
+            self.publish_stats();
+        }
+
+        let sigma = self.quote_volatility();
+        let q = self.inventory.data;
+
+        // 1. 保留价格与最优价差（纯函数，不修改状态）
+        let reservation_price = self.reservation_price();
+        let (optimal_spread, spread_clamped) = self.clamp_spread(self.raw_optimal_spread());
+        if spread_clamped.is_some() {
+            self.spread_clamp_events += 1;
+        }
+        // 逆向选择防御期间放宽价差（在上下限之后生效）
+        let optimal_spread = optimal_spread * self.adverse_spread_multiplier(timestamp_ns);
+
+        // 2. 计算买卖报价
+        let half_spread = optimal_spread * 0.5;
+        let mut bid_price = reservation_price - half_spread;
+        let mut ask_price = reservation_price + half_spread;
+
+        // 3. 库存惩罚调整
+        let mut inventory_penalty =
+            self.skew_inventory() * self.config.inventory_penalty_factor * sigma;
+        if self.config.penalty_in_price_terms {
+            inventory_penalty *= self.mid_price.data;
+        }
+        bid_price -= inventory_penalty;
+        ask_price -= inventory_penalty;
+
+        // 确保报价不会越过保留价格（大幅偏斜时可能反转）
+        let min_edge = reservation_price * self.config.min_edge_bps / 10000.0;
+        bid_price = bid_price.min(reservation_price - min_edge);
+        ask_price = ask_price.max(reservation_price + min_edge);
+
+        // 最终兜底：所有调整之后买卖价差不得低于最小价差，不足时围绕中点对称放宽
+        let min_spread = self.mid_price.data * self.config.min_spread_bps / 10000.0;
+        if ask_price - bid_price < min_spread {
+            let center = (bid_price + ask_price) * 0.5;
+            bid_price = center - min_spread * 0.5;
+            ask_price = center + min_spread * 0.5;
+        }
+
+        // 病态参数下仍可能自交叉：围绕保留价格以最小允许价差（至少一个价格精度单位）重新居中
+        if bid_price >= ask_price {
+            let resolved_spread = min_spread.max(self.tick_size());
+            tracing::warn!(
+                bid_price,
+                ask_price,
+                reservation_price,
+                "Self-crossed quote, re-centering around reservation price"
+            );
+            bid_price = reservation_price - resolved_spread * 0.5;
+            ask_price = reservation_price + resolved_spread * 0.5;
+            self.self_cross_events += 1;
+        }
+
+        // 不穿过受保护参考价格
+        if let Some(guard) = self.reference_price_guard {
+            bid_price = bid_price.min(guard);
+            ask_price = ask_price.max(guard);
+        }
+
+        // 4. 订单大小：增仓一侧按剩余库存空间缩小，达到上限后不再报价；减仓一侧保持原数量
+        let long_headroom = (1.0 - q.max(0.0) / self.config.long_inventory_limit()).clamp(0.0, 1.0);
+        let short_headroom =
+            (1.0 - (-q).max(0.0) / self.config.short_inventory_limit()).clamp(0.0, 1.0);
+        let (base_bid_size, base_ask_size) = match self.config.size_model {
+            SizeModel::Fixed => (
+                self.config.bid_size.unwrap_or(self.config.base_order_size),
+                self.config.ask_size.unwrap_or(self.config.base_order_size),
+            ),
+            SizeModel::Kelly { fraction } => {
+                let size = self.kelly_size(fraction, reservation_price);
+                (size, size)
+            }
+        };
+        let mut bid_size = round_down_to_lot(base_bid_size * long_headroom, self.config.lot_size);
+        let mut ask_size = round_down_to_lot(base_ask_size * short_headroom, self.config.lot_size);
+
+        // 5. 时段末或强制平仓：只在中间价挂减仓一侧（全部库存），持平后停止报价
+        if self.flatten_requested || self.in_flatten_window(timestamp_ns) {
+            let mid = self.mid_price.data;
+            let flatten_size = round_down_to_lot(q.abs(), self.config.lot_size);
+            bid_size = 0.0;
+            ask_size = 0.0;
+            if q > 0.0 {
+                ask_price = ask_price.min(mid);
+                ask_size = flatten_size;
+            } else if q < 0.0 {
+                bid_price = bid_price.max(mid);
+                bid_size = flatten_size;
+            }
+        }
+
+        // 成交后冷却：刚成交的一侧暂不重新报价，避免连续被有毒订单流吃单
+        if self.in_fill_cooldown(self.last_bid_fill_ns, timestamp_ns) {
+            bid_size = 0.0;
+        }
+        if self.in_fill_cooldown(self.last_ask_fill_ns, timestamp_ns) {
+            ask_size = 0.0;
+        }
+
+        // 低于最小数量的一侧不报价，避免挂出碎单
+        let min_order_size = self.config.min_order_size;
+        if bid_size < min_order_size {
+            bid_size = 0.0;
+        }
+        if ask_size < min_order_size {
+            ask_size = 0.0;
+        }
+
+        if self.config.log_quotes {
+            tracing::debug!(
+                reservation_price,
+                spread = optimal_spread,
+                inventory = q,
+                bid_price,
+                bid_size,
+                ask_price,
+                ask_size,
+                "Quote update"
+            );
+        }
+
+        let mut quote = QuoteUpdate {
+            bid_price,
+            ask_price,
+            bid_size,
+            ask_size,
+            spread: optimal_spread,
+            spread_bps: spread_to_bps(optimal_spread, reservation_price),
+            bid_distance_bps: 0.0,
+            ask_distance_bps: 0.0,
+            reservation_price,
+            spread_clamped,
+        };
+        quote.update_distances(self.mid_price.data);
+        quote
+    }
+
+    /// 逆向选择分数：最近成交中不利成交的占比（未启用或尚无结算成交时为0）
+    pub fn adverse_selection_score(&self) -> f64 {
+        self.adverse_selection.score()
+    }
+
+    /// 结算到期成交；窗口已满且分数达到阈值时开启（或延长）价差放宽
+    fn update_adverse_selection(&mut self, mid: f64, now_ns: u64) {
+        let Some(adverse) = self.config.adverse_selection else {
+            return;
+        };
+        self.adverse_selection.on_mid(mid, now_ns);
+
+        let score = self.adverse_selection.score();
+        if self.adverse_selection.is_full() && score >= adverse.threshold {
+            if !self.in_adverse_widening(now_ns) {
+                tracing::info!(
+                    score,
+                    multiplier = adverse.spread_multiplier,
+                    "Adverse selection detected, widening spread"
+                );
+            }
+            self.adverse_widen_until_ns = Some(now_ns.saturating_add(adverse.cooldown_ns));
+        }
+    }
+
+    #[inline]
+    fn in_adverse_widening(&self, now_ns: u64) -> bool {
+        self.adverse_widen_until_ns
+            .is_some_and(|until| now_ns < until)
+    }
+
+    /// 当前价差倍数：逆向选择放宽期间为 `spread_multiplier`，否则为1
+    #[inline]
+    fn adverse_spread_multiplier(&self, now_ns: u64) -> f64 {
+        match self.config.adverse_selection {
+            Some(adverse) if self.in_adverse_widening(now_ns) => adverse.spread_multiplier,
+            _ => 1.0,
+        }
+    }
+
+    /// 距该侧最近一次成交是否仍在 `post_fill_cooldown_ns` 内
+    #[inline]
+    fn in_fill_cooldown(&self, last_fill_ns: Option<u64>, now_ns: u64) -> bool {
+        let cooldown = self.config.post_fill_cooldown_ns;
+        cooldown > 0
+            && last_fill_ns.is_some_and(|fill_ns| now_ns.saturating_sub(fill_ns) < cooldown)
+    }
+
+    /// Kelly 订单大小：边际 μ 取微观价格相对保留价格的偏离
+    #[inline]
+    fn kelly_size(&self, fraction: f64, reservation_price: f64) -> f64 {
+        let mid = self.mid_price.data;
+        let sigma = self.quote_volatility();
+        let variance = sigma * sigma;
+        if mid <= 0.0 || variance <= 0.0 {
+            return 0.0;
+        }
+
+        let edge = (self.microprice - reservation_price).abs() / mid;
+        (fraction * edge / variance).clamp(0.0, 1.0) * self.config.max_position_size
+    }
+
+    /// 计算保留价格 (Reservation Price)
+    ///
+    /// r = s - q*γ*σ²*(T-t)，s 为中间价（启用 `skew_around_fair_value` 时为公允价值）
+    #[inline]
+    pub fn reservation_price(&self) -> f64 {
+        reservation_price(
+            self.fair_value(),
+            self.skew_inventory(),
+            self.config.risk_aversion,
+            self.quote_volatility(),
+            self.effective_time_remaining(),
+        )
+    }
+
+    /// 公允价值：启用 `skew_around_fair_value` 时为平滑中间价，否则即中间价
+    #[inline]
+    pub fn fair_value(&self) -> f64 {
+        if self.config.skew_around_fair_value && self.fair_value > 0.0 {
+            self.fair_value
+        } else {
+            self.mid_price.data
+        }
+    }
+
+    /// 库存的名义价值：线性合约为 数量 × 中间价，反向合约为 张数 / 中间价
+    #[inline]
+    pub fn notional(&self, inventory: f64) -> f64 {
+        let mid = self.mid_price.data;
+        if !self.config.inverse {
+            inventory * mid
+        } else if mid > 0.0 {
+            inventory / mid
+        } else {
+            0.0
+        }
+    }
+
+    /// 用于报价偏斜的库存：超出 `inventory_band` 的部分（区间内为0），
+    /// 按 `max_inventory` 归一化后经 `skew_shape` 变换再还原，
+    /// 启用 `skew_time_decay` 时按持仓时间放大；
+    /// 设置 `max_notional` 时先把库存换算为名义价值口径
+    #[inline]
+    pub fn skew_inventory(&self) -> f64 {
+        let q = match self.config.max_notional {
+            Some(max_notional) => {
+                self.notional(self.inventory.data) / max_notional * self.config.max_inventory
+            }
+            None => self.inventory.data,
+        };
+        let (lower, upper) = self.config.inventory_band;
+        let excess = if q > upper {
+            q - upper
+        } else if q < lower {
+            q - lower
+        } else {
+            0.0
+        };
+
+        let max_inventory = self.config.max_inventory;
+        let shaped = if self.config.skew_shape == SkewShape::Linear || max_inventory <= 0.0 {
+            excess
+        } else {
+            self.config.skew_shape.apply(excess / max_inventory) * max_inventory
+        };
+
+        // 持仓越久偏斜越强，促使陈旧库存尽快出清
+        let decay = self.config.skew_time_decay;
+        if decay > 0.0 {
+            shaped * (1.0 + decay * self.inventory_age_secs())
+        } else {
+            shaped
+        }
+    }
+
+    /// 报价使用的每秒波动率（按 `bar_interval_secs` 换算）
+    #[inline]
+    pub fn quote_volatility(&self) -> f64 {
+        let volatility = self.blended_volatility();
+        match self.config.bar_interval_secs {
+            Some(interval) => volatility / interval.sqrt(),
+            None => volatility,
+        }
+    }
+
+    /// 有效剩余时间（秒）
+    ///
+    /// 启用 `inventory_scaled_horizon` 时随库存占用比例线性缩短
+    #[inline]
+    pub fn effective_time_remaining(&self) -> f64 {
+        let horizon = self.config.time_horizon;
+        let limit = self.config.inventory_limit(self.inventory.data);
+        if !self.config.inventory_scaled_horizon || limit <= 0.0 {
+            return horizon;
+        }
+
+        let inventory_ratio = (self.inventory.data.abs() / limit).min(1.0);
+        horizon * (1.0 - inventory_ratio)
+    }
+
+    /// 由最终报价展开多档报价（不修改状态，不计入报价统计）
+    ///
+    /// 第0档即传入的报价（通常为 `on_orderbook_update` 的结果），之后每档向外偏移
+    /// `ladder_step_bps`；每侧总数量为最优档数量 × 档数（不超过 `max_position_size`），
+    /// 按 `ladder_sizing` 分配并用最大余数法取整到步长，避免逐档取整导致总数量偏离目标
+    pub fn quote_ladder(&self, quote: &QuoteUpdate) -> Vec<LadderLevel> {
+        let levels = self.config.ladder_levels.max(1);
+        let mid = self.mid_price.data;
+        let step = mid * self.config.ladder_step_bps / 10000.0;
+
+        // 各档距中间价的距离决定成交概率（仅 InverseFillProbability 使用）
+        let side_weights = |first_distance: f64| {
+            let sizing = self.config.ladder_sizing;
+            match sizing {
+                LadderSizing::InverseFillProbability { horizon_secs } => {
+                    let probabilities: Vec<f64> = (0..levels)
+                        .map(|i| {
+                            self.fill_probability(first_distance + step * i as f64, horizon_secs)
+                        })
+                        .collect();
+                    sizing.weights_by_fill_probability(&probabilities)
+                }
+                _ => sizing.weights(levels),
+            }
+        };
+        let bid_weights = side_weights(mid - quote.bid_price);
+        let ask_weights = side_weights(quote.ask_price - mid);
+
+        let lot_size = self.config.lot_size;
+        let side_total = |size: f64| (size * levels as f64).min(self.config.max_position_size);
+        let bid_sizes = distribute_lots(side_total(quote.bid_size), &bid_weights, lot_size);
+        let ask_sizes = distribute_lots(side_total(quote.ask_size), &ask_weights, lot_size);
+
+        (0..levels)
+            .map(|i| LadderLevel {
+                bid_price: quote.bid_price - step * i as f64,
+                ask_price: quote.ask_price + step * i as f64,
+                bid_size: bid_sizes[i],
+                ask_size: ask_sizes[i],
+            })
+            .collect()
+    }
+
+    /// 计算最优价差 (Optimal Spread)，已应用最小/最大价差限制
+    ///
+    /// δ = γ*σ²*(T-t) + (2/γ)*ln(1 + γ/κ)
+    #[inline]
+    pub fn optimal_spread(&self) -> f64 {
+        self.clamp_spread(self.raw_optimal_spread()).0
+    }
+
+    /// 计算模型最优价差（未应用价差限制）
+    #[inline]
+    pub fn raw_optimal_spread(&self) -> f64 {
+        optimal_spread(
+            self.config.risk_aversion,
+            self.quote_volatility(),
+            self.effective_time_remaining(),
+            self.config.price_sensitivity,
+        )
+    }
+
+    /// 应用最小/最大价差限制，并返回被限制的方向
+    #[inline]
+    fn clamp_spread(&self, spread: f64) -> (f64, Option<ClampDirection>) {
+        let mid = self.mid_price.data;
+        let min_spread = mid * self.config.min_spread_bps / 10000.0;
+        let max_spread = mid * self.config.max_spread_bps / 10000.0;
+
+        if spread < min_spread {
+            (min_spread, Some(ClampDirection::Min))
+        } else if spread > max_spread {
+            (max_spread, Some(ClampDirection::Max))
+        } else {
+            (spread, None)
+        }
+    }
+
+    /// 更新价格历史
+    #[inline]
+    fn update_price_history(&mut self, price: f64) {
+        self.price_history.push(price);
+    }
+
+    /// 计算Parkinson波动率 - SIMD优化版本
+    ///
+    /// Parkinson波动率使用高低价，比标准波动率更稳定
+    /// σ² = (1/4ln2) * (1/n) * Σ(ln(H/L))²
+    #[inline]
+    pub fn calculate_parkinson_volatility(&self) -> f64 {
+        if self.bar_history.len() < 2 {
+            return self.config.initial_volatility;
+        }
+
+        let mut sum_sq = 0.0;
+        let ln2 = std::f64::consts::LN_2;
+
+        for &(_, high, low, _) in &self.bar_history {
+            if high > 0.0 && low > 0.0 {
+                let ratio = (high / low).ln();
+                sum_sq += ratio * ratio;
+            }
+        }
+
+        let n = self.bar_history.len() as f64;
+        (sum_sq / (n * 4.0 * ln2)).sqrt()
+    }
+
+    /// 计算Garman-Klass波动率（复用 indicators 中的实现）
+    ///
+    /// 含非正价格的K线被跳过；结果非有限（方差估计为负）时回退到初始波动率
+    #[inline]
+    fn calculate_garman_klass_volatility(&self) -> f64 {
+        let bars: Vec<(f64, f64, f64, f64)> = self
+            .bar_history
+            .iter()
+            .copied()
+            .filter(|&(open, high, low, close)| {
+                open > 0.0 && high > 0.0 && low > 0.0 && close > 0.0
+            })
+            .collect();
+
+        if bars.len() < 2 {
+            return self.config.initial_volatility;
+        }
+
+        let sigma = garman_klass_volatility(&bars);
+        if sigma.is_finite() {
+            sigma
+        } else {
+            self.config.initial_volatility
+        }
+    }
+
+    /// 计算标准波动率（基于收益率）
+    #[inline]
+    fn calculate_standard_volatility(&self) -> f64 {
+        if self.price_history.len() < 2 {
+            return self.config.initial_volatility;
+        }
+
+        // 计算对数收益率（跳过非正价格，避免 NaN/Inf）
+        let returns: Vec<f64> = self
+            .price_history
+            .iter()
+            .zip(self.price_history.iter().skip(1))
+            .filter(|(p1, p2)| **p1 > 0.0 && **p2 > 0.0)
+            .map(|(p1, p2)| (p2 / p1).ln())
+            .filter(|r| r.is_finite())
+            .collect();
+
+        if returns.len() < 2 {
+            return self.config.initial_volatility;
+        }
+
+        if let Some(decay) = self.config.return_decay {
+            return Self::weighted_std(&returns, decay);
+        }
+
+        // 计算均值
+        let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+
+        // 计算方差
+        let variance =
+            returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / returns.len() as f64;
+
+        variance.sqrt()
+    }
+
+    /// 指数加权标准差，最新收益率权重最大
+    #[inline]
+    fn weighted_std(returns: &[f64], decay: f64) -> f64 {
+        let mut weight = 1.0;
+        let mut sum_w = 0.0;
+        let mut sum_wr = 0.0;
+        let mut sum_wr2 = 0.0;
+
+        for &r in returns.iter().rev() {
+            sum_w += weight;
+            sum_wr += weight * r;
+            sum_wr2 += weight * r * r;
+            weight *= decay;
+        }
+
+        let mean = sum_wr / sum_w;
+        (sum_wr2 / sum_w - mean * mean).max(0.0).sqrt()
+    }
+
+    /// 获取当前统计数据
+    pub fn get_stats(&self) -> StrategyStats {
+        StrategyStats {
+            quote_updates: self.quote_updates.data,
+            orderbook_updates: self.orderbook_updates.data,
+            inventory_adjustments: self.inventory_adjustments.data,
+            current_inventory: self.inventory.data,
+            current_volatility: self.volatility.data,
+            mid_price: self.mid_price.data,
+            spread_clamp_events: self.spread_clamp_events,
+            self_cross_events: self.self_cross_events,
+            spread_pnl: self.pnl.spread_pnl(),
+            inventory_pnl: self.pnl.inventory_pnl(self.mid_price.data),
+        }
+    }
+
+    /// 检查是否需要对冲
+    ///
+    /// |库存| 超过 `hedge_threshold` 时返回将库存拉回 `hedge_target_inventory` 的对冲单
+    pub fn check_hedge(&self) -> Option<HedgeSignal> {
+        let threshold = self.config.hedge_threshold;
+        let q = self.inventory.data;
+        if threshold <= 0.0 || q.abs() <= threshold {
+            return None;
+        }
+
+        let target = self.config.hedge_target_inventory.abs().min(threshold);
+        let quantity = round_down_to_lot(q.abs() - target, self.config.lot_size);
+        if quantity <= 0.0 {
+            return None;
+        }
+
+        let side = if q > 0.0 {
+            OrderSide::Sell
+        } else {
+            OrderSide::Buy
+        };
+        Some(HedgeSignal { side, quantity })
+    }
+
+    /// 当日盈亏（已实现 + 以当前中间价计算的未实现）
+    pub fn daily_pnl(&self) -> f64 {
+        self.pnl.total_pnl(self.mid_price.data) - self.session_pnl_start
+    }
+
+    /// 盈亏追踪（持仓、平均成本、已实现/未实现盈亏）
+    pub fn pnl(&self) -> &PnlTracker {
+        &self.pnl
+    }
+
+    /// 盈亏报告：以当前中间价计算未实现盈亏，时段为当前交易时段起点至最后更新时间
+    pub fn pnl_report(&self) -> PnlReport {
+        self.pnl.report(
+            self.mid_price.data,
+            self.session_start_ns,
+            self.last_update_ns.as_u64(),
+        )
+    }
+
+    /// 逐K线绩效追踪（rolling_sharpe / rolling_sortino）
+    pub fn performance(&self) -> &PerformanceTracker {
+        &self.performance
+    }
+
+    /// 距中间价 `distance_from_mid` 的报价在 `horizon_secs` 秒内成交的概率
+    ///
+    /// 成交强度 λ(δ) = A·exp(-κδ)（A = `order_arrival_rate`，κ = `price_sensitivity`），
+    /// 按泊松到达 P(fill) = 1 - exp(-λ(δ)·T)，结果限制在 [0, 1]
+    pub fn fill_probability(&self, distance_from_mid: f64, horizon_secs: f64) -> f64 {
+        let intensity = self.config.order_arrival_rate
+            * (-self.config.price_sensitivity * distance_from_mid.max(0.0)).exp();
+        let probability = 1.0 - (-intensity * horizon_secs.max(0.0)).exp();
+        if probability.is_finite() {
+            probability.clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// 当前报价与偏斜下库存减半所需的时间（秒）
+    ///
+    /// 库存 q 使报价整体下移 s（保留价格偏移加库存惩罚），卖单距中间价 δ-s、
+    /// 买单 δ+s（δ 为半个最优价差）。按成交强度 λ(δ) = A·exp(-κδ)，
+    /// 每秒净减仓 (λ(δ-s) - λ(δ+s))·基础数量，视为比例衰减率 k = 净减仓/|q|，
+    /// 半衰期 ln2/k。偏斜越强 k 越大、半衰期越短；空仓或无偏斜时返回无穷大
+    pub fn inventory_half_life(&self) -> f64 {
+        let q = self.inventory.data;
+        if q == 0.0 {
+            return f64::INFINITY;
+        }
+
+        let mut penalty =
+            self.skew_inventory() * self.config.inventory_penalty_factor * self.quote_volatility();
+        if self.config.penalty_in_price_terms {
+            penalty *= self.mid_price.data;
+        }
+        // 朝减仓方向的报价偏移（多头时为正）
+        let shift = (self.mid_price.data - self.reservation_price() + penalty) * q.signum();
+
+        let half_spread = self.optimal_spread() * 0.5;
+        let intensity = |distance: f64| {
+            self.config.order_arrival_rate
+                * (-self.config.price_sensitivity * distance.max(0.0)).exp()
+        };
+        let outflow = (intensity(half_spread - shift) - intensity(half_spread + shift))
+            * self.config.base_order_size;
+
+        let rate = outflow / q.abs();
+        if rate > 0.0 && rate.is_finite() {
+            std::f64::consts::LN_2 / rate
+        } else {
+            f64::INFINITY
+        }
+    }
+
+    /// 盈亏平衡波动率（每秒收益率，与 `quote_volatility` 同单位）
+    ///
+    /// 一次往返相对中间价的净收益 e = δ/mid - 2·fee，
+    /// 持有 τ 秒库存的预期绝对不利波动为 σ·√(2τ/π)，二者相等时
+    /// σ* = e / √(2τ/π)。报价波动率高于 σ* 时持仓亏损快于价差收益；
+    /// 净收益非正时返回0
+    pub fn break_even_volatility(&self) -> f64 {
+        let mid = self.mid_price.data;
+        if mid <= 0.0 {
+            return 0.0;
+        }
+
+        let edge = self.optimal_spread() / mid - 2.0 * self.config.maker_fee_bps / 10000.0;
+        if edge <= 0.0 {
+            return 0.0;
+        }
+
+        let holding = self.config.expected_holding_secs;
+        edge / (2.0 * holding / std::f64::consts::PI).sqrt()
+    }
+
+    /// 是否已触发单日亏损熔断（锁定到 `reset_session`）
+    pub fn is_killed(&self) -> bool {
+        self.killed
+    }
+
+    /// 是否因波动率超过盈亏平衡波动率暂停报价（波动率回落后自动恢复）
+    pub fn is_volatility_halted(&self) -> bool {
+        self.volatility_halted
+    }
+
+    /// 是否停止报价（单日亏损熔断或波动率暂停）
+    pub fn is_halted(&self) -> bool {
+        self.killed || self.volatility_halted
+    }
+
+    /// 开始新交易日：以当前盈亏为基准、解除熔断并清除强制平仓请求
+    pub fn reset_session(&mut self) {
+        self.session_pnl_start = self.pnl.total_pnl(self.mid_price.data);
+        self.pnl.start_session();
+        self.killed = false;
+        self.flatten_requested = false;
+    }
+
+    /// 设置交易时段起点（纳秒）
+    pub fn start_session(&mut self, start_ns: u64) {
+        self.session_start_ns = Some(start_ns);
+    }
+
+    /// 首次行情时开始交易时段；时段超过 `time_horizon` 后按整数个时段向后滚动，
+    /// 时段末平仓窗口结束后恢复双边报价
+    fn roll_session(&mut self, now_ns: u64) {
+        let start = *self.session_start_ns.get_or_insert(now_ns);
+        let horizon_ns = (self.config.time_horizon * 1e9) as u64;
+        if horizon_ns > 0 && now_ns.saturating_sub(start) >= horizon_ns {
+            let periods = (now_ns - start) / horizon_ns;
+            self.session_start_ns = Some(start + periods * horizon_ns);
+        }
+    }
+
+    /// 交易时段已经过的时间（秒），时段未开始时为0
+    pub fn session_elapsed_secs(&self, now_ns: u64) -> f64 {
+        self.session_start_ns
+            .map_or(0.0, |start| now_ns.saturating_sub(start) as f64 / 1e9)
+    }
+
+    /// 交易时段剩余时间（秒），不小于0
+    pub fn time_remaining_secs(&self, now_ns: u64) -> f64 {
+        (self.config.time_horizon - self.session_elapsed_secs(now_ns)).max(0.0)
+    }
+
+    /// 更新加权平均建仓时间（`now_ns` 为成交时间）
+    ///
+    /// 加仓按数量加权，减仓不变，反手后以当前时间重新计时，持平后清除
+    fn update_inventory_entry(&mut self, previous: f64, now_ns: u64) {
+        let current = self.inventory.data;
+        let now = now_ns as f64;
+
+        self.inventory_entry_ns = if current.abs() <= f64::EPSILON {
+            None
+        } else if previous.abs() <= f64::EPSILON || previous.signum() != current.signum() {
+            Some(now)
+        } else if current.abs() > previous.abs() {
+            let entry = self.inventory_entry_ns.unwrap_or(now);
+            Some((previous.abs() * entry + (current.abs() - previous.abs()) * now) / current.abs())
+        } else {
+            self.inventory_entry_ns
+        };
+    }
+
+    /// 当前持仓的平均持有时间（秒），空仓时为0
+    pub fn inventory_age_secs(&self) -> f64 {
+        self.inventory_entry_ns.map_or(0.0, |entry| {
+            ((self.last_update_ns.as_u64() as f64 - entry) / 1e9).max(0.0)
+        })
+    }
+
+    /// 请求强制平仓：此后只报减仓一侧，持平后停止报价（`reset` 或 `reset_session` 时清除）
+    pub fn request_flatten(&mut self) {
+        if !self.flatten_requested {
+            tracing::warn!(
+                inventory = self.inventory.data,
+                "Flatten requested, quoting reducing side only"
+            );
+        }
+        self.flatten_requested = true;
+    }
+
+    /// 是否已请求强制平仓
+    pub fn is_flatten_requested(&self) -> bool {
+        self.flatten_requested
+    }
+
+    /// 是否处于时段末平仓窗口
+    #[inline]
+    fn in_flatten_window(&self, now_ns: u64) -> bool {
+        self.config.end_of_session_flatten
+            && self.session_start_ns.is_some()
+            && self.time_remaining_secs(now_ns)
+                < self.config.time_horizon * self.config.flatten_fraction
+    }
+
+    /// 检查单日亏损熔断与波动率暂停，返回是否停止报价
+    #[inline]
+    fn check_kill_switch(&mut self) -> bool {
+        let max_loss = self.config.max_daily_loss;
+        if !self.killed && max_loss > 0.0 && self.daily_pnl() < -max_loss {
+            tracing::error!(
+                daily_pnl = self.daily_pnl(),
+                max_daily_loss = max_loss,
+                "Daily loss limit breached, quoting halted"
+            );
+            self.killed = true;
+        }
+
+        // 波动率暂停每次重新判断，不影响单日亏损熔断
+        let halted = self.config.halt_above_break_even_volatility
+            && self.mid_price.data > 0.0
+            && self.quote_volatility() > self.break_even_volatility();
+        if halted != self.volatility_halted {
+            let sigma = self.quote_volatility();
+            let break_even = self.break_even_volatility();
+            if halted {
+                tracing::error!(
+                    volatility = sigma,
+                    break_even_volatility = break_even,
+                    "Volatility above break-even, quoting halted"
+                );
+            } else {
+                tracing::info!(
+                    volatility = sigma,
+                    break_even_volatility = break_even,
+                    "Volatility back below break-even, quoting resumed"
+                );
+            }
+            self.volatility_halted = halted;
+        }
+        self.is_halted()
+    }
+
+    /// 价差捕获直方图：(桶下界bps, 往返数量)
+    pub fn spread_capture_histogram(&self) -> Vec<(f64, f64)> {
+        self.spread_capture.histogram()
+    }
+
+    /// 订阅统计数据流
+    ///
+    /// 每 `stats_interval` 次报价推送一次快照；消费过慢时丢弃快照
+    pub fn subscribe_stats(&self) -> Receiver<StrategyStats> {
+        let (tx, rx) = mpsc::sync_channel(STATS_CHANNEL_CAPACITY);
+        if let Ok(mut subscribers) = self.stats_subscribers.lock() {
+            subscribers.push(tx);
+        }
+        rx
+    }
+
+    /// 向所有订阅者推送统计快照（非阻塞）
+    fn publish_stats(&self) {
+        let Ok(mut subscribers) = self.stats_subscribers.lock() else {
+            return;
+        };
+        if subscribers.is_empty() {
+            return;
+        }
+
+        let stats = self.get_stats();
+        subscribers.retain(|tx| match tx.try_send(stats.clone()) {
+            Ok(()) | Err(TrySendError::Full(_)) => true,
+            Err(TrySendError::Disconnected(_)) => false,
+        });
+    }
+
+    /// 序列化策略状态（库存、波动率、价格历史、计数器、盈亏、熔断与交易时段状态）
+    pub fn serialize_state(&self) -> StrategyResult<Vec<u8>> {
+        let state = StrategyState {
+            mid_price: self.mid_price.data,
+            volatility: self.volatility.data,
+            inventory: self.inventory.data,
+            price_history: self.price_history.iter().copied().collect(),
+            bar_history: self.bar_history.iter().copied().collect(),
+            quote_updates: self.quote_updates.data,
+            orderbook_updates: self.orderbook_updates.data,
+            inventory_adjustments: self.inventory_adjustments.data,
+            last_update_ns: self.last_update_ns.as_u64(),
+            pnl: self.pnl,
+            session_pnl_start: self.session_pnl_start,
+            killed: self.killed,
+            fair_value: self.fair_value,
+            inventory_entry_ns: self.inventory_entry_ns,
+            session_start_ns: self.session_start_ns,
+        };
+
+        state.encode()
+    }
+
+    /// 从 `serialize_state` 的输出恢复策略状态（兼容没有版本号的第一版格式）
+    pub fn restore_state(&mut self, bytes: &[u8]) -> StrategyResult<()> {
+        let state = StrategyState::decode(bytes, &self.config)?;
+
+        self.mid_price.data = state.mid_price;
+        self.volatility.data = state.volatility;
+        self.inventory.data = state.inventory;
+        self.price_history.clear();
+        self.price_history.extend(state.price_history);
+        self.bar_history.clear();
+        self.bar_history.extend(state.bar_history);
+        self.quote_updates.data = state.quote_updates;
+        self.orderbook_updates.data = state.orderbook_updates;
+        self.inventory_adjustments.data = state.inventory_adjustments;
+        self.last_update_ns = UnixNanos::new(state.last_update_ns);
+        self.pnl = state.pnl;
+        self.session_pnl_start = state.session_pnl_start;
+        self.killed = state.killed;
+        self.fair_value = state.fair_value;
+        self.inventory_entry_ns = state.inventory_entry_ns;
+        self.session_start_ns = state.session_start_ns;
+
+        Ok(())
+    }
+
+    /// 热更新配置：先校验，再按新的波动率窗口调整历史缓冲区（保留最新数据）
+    ///
+    /// 校验失败时保持原配置不变
+    pub fn reconfigure(&mut self, config: ASConfig) -> StrategyResult<()> {
+        config.validate()?;
+        // 盈亏追踪按合约类型创建，持仓成本和已实现盈亏的计价单位不同，不能中途切换
+        ensure_config!(
+            config.inverse == self.config.inverse,
+            "inverse cannot be changed by reconfigure"
+        );
+
+        self.price_history.resize(config.volatility_window);
+        self.bar_history.resize(config.volatility_window);
+        if config.adverse_selection != self.config.adverse_selection {
+            self.adverse_selection = adverse_selection_tracker(&config);
+            self.adverse_widen_until_ns = None;
+        }
+        if !config.record_volatility {
+            self.volatility_recorder = None;
+        } else if self.volatility_recorder.is_none() {
+            self.volatility_recorder = Some(VolatilityRecorder::new());
+        }
+        self.config = config;
+        Ok(())
+    }
+
+    /// 应用部分配置更新（合并后校验，失败时保持原配置）
+    pub fn apply_config_update(&mut self, partial: &PartialASConfig) -> StrategyResult<()> {
+        self.reconfigure(partial.merge(&self.config))
+    }
+
+    /// 当前配置
+    pub fn config(&self) -> &ASConfig {
+        &self.config
+    }
+
+    /// 重置策略状态
+    pub fn reset(&mut self) {
+        self.mid_price.data = 0.0;
+        self.fair_value = 0.0;
+        self.microprice = 0.0;
+        self.volatility.data = self.config.initial_volatility;
+        self.inventory.data = 0.0;
+        self.price_history.clear();
+        self.bar_history.clear();
+        self.quote_updates.data = 0;
+        self.orderbook_updates.data = 0;
+        self.inventory_adjustments.data = 0;
+        self.spread_clamp_events = 0;
+        self.self_cross_events = 0;
+        self.last_update_ns = UnixNanos::new(0);
+        self.spread_capture.clear();
+        self.pnl = pnl_tracker(&self.config);
+        self.session_pnl_start = 0.0;
+        self.killed = false;
+        self.volatility_halted = false;
+        self.performance.clear();
+        self.last_bar_equity = None;
+        self.session_start_ns = None;
+        self.flatten_requested = false;
+        self.inventory_entry_ns = None;
+        self.last_bid_fill_ns = None;
+        self.adverse_selection.clear();
+        if let Some(recorder) = &mut self.volatility_recorder {
+            recorder.clear();
+        }
+        self.adverse_widen_until_ns = None;
+        self.last_ask_fill_ns = None;
+        self.last_trade_price = None;
+        self.trade_variance = None;
+        self.trades_in_bar = 0;
+        self.trades_per_bar = None;
+        self.last_quote_ns = None;
+    }
+}
+
+// /// 订单方向
+// #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+// pub enum OrderSide {
+//     Buy,
+//     Sell,
+// }
+
+/// 策略统计数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrategyStats {
+    pub quote_updates: u64,
+    pub orderbook_updates: u64,
+    pub inventory_adjustments: u64,
+    pub current_inventory: f64,
+    pub current_volatility: f64,
+    pub mid_price: f64,
+    /// 价差触及上下限的次数
+    #[serde(default)]
+    pub spread_clamp_events: u64,
+    /// 报价自交叉（买价 >= 卖价）并被重新居中的次数
+    #[serde(default)]
+    pub self_cross_events: u64,
+    /// 价差盈亏（成交相对当时中间价捕获的边际）
+    #[serde(default)]
+    pub spread_pnl: f64,
+    /// 库存盈亏（持仓随中间价变动的盯市盈亏）
+    #[serde(default)]
+    pub inventory_pnl: f64,
+}
+
+impl StrategyStats {
+    /// 序列化为JSON字符串
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategies::clock::ManualClock;
+
+    fn create_test_config() -> ASConfig {
+        ASConfig {
+            instrument_id: "BTCUSDT".to_string(),
+            risk_aversion: 0.1,
+            order_arrival_rate: 100.0,
+            price_sensitivity: 1.5,
+            time_horizon: 300.0,
+            base_order_size: 0.001,
+            max_position_size: 0.1,
+            max_inventory: 0.05,
+            volatility_window: 20,
+            use_parkinson: true,
+            inventory_penalty_factor: 2.0,
+            max_spread_bps: 200.0,
+            min_spread_bps: 2.0,
+            ..ASConfig::default()
+        }
+    }
+
+    fn create_test_snapshot(bid: f64, ask: f64) -> OrderBookSnapshot {
+        OrderBookSnapshot {
+            best_bid: bid,
+            best_ask: ask,
+            bid_volume: 1.0,
+            ask_volume: 1.0,
+            timestamp_ns: UnixNanos::new(1000000000),
+        }
+    }
+
+    #[test]
+    fn test_strategy_creation() {
+        let config = create_test_config();
+        let strategy = AvellanedaStoikov::new(config);
+
+        let stats = strategy.get_stats();
+        assert_eq!(stats.quote_updates, 0);
+        assert_eq!(stats.current_inventory, 0.0);
+    }
+
+    #[test]
+    fn test_orderbook_update() {
+        let config = create_test_config();
+        let mut strategy = AvellanedaStoikov::new(config);
+
+        let snapshot = create_test_snapshot(50000.0, 50010.0);
+        let quote = strategy.on_orderbook_update(&snapshot);
+
+        assert!(quote.is_some());
+        let quote = quote.unwrap();
+
+        // 验证报价在合理范围内
+        assert!(quote.bid_price > 0.0);
+        assert!(quote.ask_price > quote.bid_price);
+        assert!(quote.spread > 0.0);
+
+        let stats = strategy.get_stats();
+        assert_eq!(stats.orderbook_updates, 1);
+        assert_eq!(stats.quote_updates, 1);
+    }
+
+    #[test]
+    fn test_fill_updates_inventory() {
+        let config = create_test_config();
+        let mut strategy = AvellanedaStoikov::new(config);
+
+        strategy.on_fill(OrderSide::Buy, 0.001);
+        assert_eq!(strategy.inventory.data, 0.001);
+
+        strategy.on_fill(OrderSide::Sell, 0.0005);
+        assert_eq!(strategy.inventory.data, 0.0005);
+    }
+
+    #[test]
+    fn test_volatility_calculation() {
+        let config = create_test_config();
+        let mut strategy = AvellanedaStoikov::new(config);
+
+        // 添加一些价格数据
+        for i in 0..30 {
+            let price = 50000.0 + (i as f64) * 10.0;
+            strategy.update_price_history(price);
+        }
+
+        let vol = strategy.calculate_standard_volatility();
+        assert!(vol > 0.0);
+        assert!(vol < 1.0); // 波动率应该在合理范围
+    }
+
+    #[test]
+    fn test_inventory_penalty() {
+        let config = create_test_config();
+        let mut strategy = AvellanedaStoikov::new(config);
+
+        let snapshot = create_test_snapshot(50000.0, 50010.0);
+
+        // 无库存时的报价
+        let quote1 = strategy.on_orderbook_update(&snapshot).unwrap();
+
+        // 增加库存
+        strategy.on_fill(OrderSide::Buy, 0.01);
+
+        // 有库存时的报价
+        let quote2 = strategy.on_orderbook_update(&snapshot).unwrap();
+
+        // 库存增加后，买价应该降低（惩罚）
+        assert!(quote2.bid_price < quote1.bid_price);
+    }
+
+    #[test]
+    fn test_pure_pricing_helpers_match_quote() {
+        let config = create_test_config();
+        let mut strategy = AvellanedaStoikov::new(config);
+
+        strategy.on_fill_with_price(OrderSide::Buy, 0.01, 50000.0);
+        let snapshot = create_test_snapshot(50000.0, 50010.0);
+        let quote = strategy.on_orderbook_update(&snapshot).unwrap();
+
+        assert_eq!(strategy.reservation_price(), quote.reservation_price);
+        assert_eq!(strategy.optimal_spread(), quote.spread);
+
+        // 纯函数不应修改报价计数器
+        let before = strategy.get_stats().quote_updates;
+        let _ = strategy.reservation_price();
+        let _ = strategy.optimal_spread();
+        assert_eq!(strategy.get_stats().quote_updates, before);
+    }
+
+    #[test]
+    fn test_pricing_formulas() {
+        // r = 100 - 2 * 0.1 * 0.2² * 10
+        assert!((reservation_price(100.0, 2.0, 0.1, 0.2, 10.0) - 99.92).abs() < 1e-12);
+        assert!((reservation_price(100.0, -2.0, 0.1, 0.2, 10.0) - 100.08).abs() < 1e-12);
+        assert_eq!(reservation_price(100.0, 0.0, 0.1, 0.2, 10.0), 100.0);
+
+        // δ = 0.1 * 0.2² * 10 + (2/0.1) * ln(1 + 0.1/1.5)
+        assert!((optimal_spread(0.1, 0.2, 10.0, 1.5) - 1.3307704227514234).abs() < 1e-12);
+        // δ = 0.5 * 0.01² * 300 + (2/0.5) * ln(1 + 0.5/2)
+        assert!((optimal_spread(0.5, 0.01, 300.0, 2.0) - 0.9075742052568391).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_stats_subscription() {
+        let mut config = create_test_config();
+        config.stats_interval = 3;
+        let mut strategy = AvellanedaStoikov::new(config);
+        let rx = strategy.subscribe_stats();
+
+        let snapshot = create_test_snapshot(50000.0, 50010.0);
+        for _ in 0..2 {
+            strategy.on_orderbook_update(&snapshot);
+        }
+        assert!(rx.try_recv().is_err());
+
+        strategy.on_orderbook_update(&snapshot);
+        let stats = rx
+            .try_recv()
+            .expect("stats should be pushed after interval");
+        assert_eq!(stats.quote_updates, 3);
+        assert!(stats.to_json().unwrap().contains("\"quote_updates\":3"));
+
+        // 订阅者断开后推送不应失败
+        drop(rx);
+        for _ in 0..3 {
+            strategy.on_orderbook_update(&snapshot);
+        }
+        assert_eq!(strategy.get_stats().quote_updates, 6);
+    }
+
+    #[test]
+    fn test_spread_capture_histogram() {
+        let config = create_test_config();
+        let mut strategy = AvellanedaStoikov::new(config);
+
+        strategy.on_fill_with_price(OrderSide::Buy, 0.001, 100.0);
+        strategy.on_fill_with_price(OrderSide::Sell, 0.001, 101.0);
+
+        assert_eq!(strategy.spread_capture_histogram(), vec![(100.0, 0.001)]);
+    }
+
+    #[test]
+    fn test_invalid_book_returns_none() {
+        let config = create_test_config();
+        let mut strategy = AvellanedaStoikov::new(config);
+
+        // 空买边
+        assert!(strategy
+            .on_orderbook_update(&create_test_snapshot(0.0, 50010.0))
+            .is_none());
+        // 空卖边
+        assert!(strategy
+            .on_orderbook_update(&create_test_snapshot(50000.0, 0.0))
+            .is_none());
+        // 交叉盘口
+        assert!(strategy
+            .on_orderbook_update(&create_test_snapshot(50010.0, 50000.0))
+            .is_none());
+
+        assert!(strategy.price_history.is_empty());
+        assert_eq!(strategy.get_stats().mid_price, 0.0);
+
+        // 锁定盘口（ask == bid）仍然可以报价
+        assert!(is_valid_book(&create_test_snapshot(50000.0, 50000.0)));
+    }
+
+    #[test]
+    fn test_size_rounded_to_lot() {
+        let mut config = create_test_config();
+        config.lot_size = 0.0005;
+        let mut strategy = AvellanedaStoikov::new(config);
+        let snapshot = create_test_snapshot(50000.0, 50010.0);
+
+        let quote = strategy.on_orderbook_update(&snapshot).unwrap();
+        assert_eq!(quote.bid_size, 0.001);
+
+        // 库存0.04 -> 买单计算数量0.0002，不足一个步长；减仓的卖单不缩小
+        strategy.on_fill_with_price(OrderSide::Buy, 0.04, 50000.0);
+        let quote = strategy.on_orderbook_update(&snapshot).unwrap();
+        assert_eq!(quote.bid_size, 0.0);
+        assert_eq!(quote.ask_size, 0.001);
+    }
+
+    #[test]
+    fn test_state_round_trip() {
+        let config = create_test_config();
+        let mut original = AvellanedaStoikov::new(config.clone());
+
+        for i in 0..5 {
+            let bid = 50000.0 + i as f64 * 5.0;
+            original.on_orderbook_update(&create_test_snapshot(bid, bid + 10.0));
+            original.on_bar(&Bar {
+                open: bid,
+                high: bid + 20.0,
+                low: bid - 20.0,
+                close: bid + 5.0,
+                volume: 1.0,
+                timestamp_ns: 0,
+            });
+        }
+        original.on_fill_with_price(OrderSide::Buy, 0.01, 50010.0);
+
+        let bytes = original.serialize_state().unwrap();
+        let mut restored = AvellanedaStoikov::new(config);
+        restored.restore_state(&bytes).unwrap();
+        // 公允价值、建仓时间和交易时段起点一并恢复
+        assert_eq!(restored.fair_value, original.fair_value);
+        assert!(restored.inventory_entry_ns.is_some());
+        assert_eq!(restored.inventory_entry_ns, original.inventory_entry_ns);
+        assert!(restored.session_start_ns.is_some());
+        assert_eq!(restored.session_start_ns, original.session_start_ns);
+
+        let snapshot = create_test_snapshot(50030.0, 50040.0);
+        let q1 = original.on_orderbook_update(&snapshot).unwrap();
+        let q2 = restored.on_orderbook_update(&snapshot).unwrap();
+        assert_eq!(q1.bid_price, q2.bid_price);
+        assert_eq!(q1.ask_price, q2.ask_price);
+        assert_eq!(q1.bid_size, q2.bid_size);
+        assert_eq!(
+            original.get_stats().quote_updates,
+            restored.get_stats().quote_updates
+        );
+
+        assert!(restored.restore_state(&[1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn test_restore_v1_state() {
+        let v1 = StrategyStateV1 {
+            mid_price: 50005.0,
+            volatility: 0.002,
+            inventory: 0.01,
+            price_history: vec![50000.0, 50005.0],
+            high_low_history: vec![(50020.0, 49980.0), (50030.0, 49990.0)],
+            quote_updates: 7,
+            orderbook_updates: 9,
+            inventory_adjustments: 1,
+            last_update_ns: 1_000_000_000,
+        };
+        let mut strategy = AvellanedaStoikov::new(create_test_config());
+        strategy
+            .restore_state(&bincode::serialize(&v1).unwrap())
+            .unwrap();
+
+        let stats = strategy.get_stats();
+        assert_eq!(stats.current_inventory, 0.01);
+        assert_eq!(stats.quote_updates, 7);
+        assert_eq!(stats.orderbook_updates, 9);
+        // 高低价迁移为开高低收，开盘/收盘取中点
+        let bars: Vec<_> = strategy.bar_history.iter().copied().collect();
+        assert_eq!(
+            bars,
+            vec![
+                (50000.0, 50020.0, 49980.0, 50000.0),
+                (50010.0, 50030.0, 49990.0, 50010.0)
+            ]
+        );
+        assert_eq!(strategy.pnl().position(), 0.01);
+        assert_eq!(strategy.pnl().avg_cost(), 50005.0);
+        assert!(!strategy.is_killed());
+
+        // 当前格式带版本号，未知版本报错
+        let bytes = strategy.serialize_state().unwrap();
+        assert!(bytes.starts_with(&STATE_MAGIC));
+        assert!(f64::from_le_bytes(STATE_MAGIC).is_nan());
+        let mut future = bytes.clone();
+        future[8..12].copy_from_slice(&(STATE_VERSION + 1).to_le_bytes());
+        assert!(matches!(
+            strategy.restore_state(&future),
+            Err(StrategyError::Serde(_))
+        ));
+        strategy.restore_state(&bytes).unwrap();
+    }
+
+    #[test]
+    fn test_state_round_trip_keeps_pnl_and_kill_switch() {
+        let mut config = create_test_config();
+        config.max_daily_loss = 5.0;
+        let mut original = AvellanedaStoikov::new(config.clone());
+        original.on_orderbook_update(&create_test_snapshot(50000.0, 50010.0));
+
+        // 买入 0.02 后亏损卖出一半：已实现亏损 10 触发熔断，剩余持仓 0.01
+        original.on_fill_with_price(OrderSide::Buy, 0.02, 50000.0);
+        original.on_fill_with_price(OrderSide::Sell, 0.01, 49000.0);
+        assert!(original.is_killed());
+
+        let mut restored = AvellanedaStoikov::new(config);
+        restored
+            .restore_state(&original.serialize_state().unwrap())
+            .unwrap();
+        assert_eq!(restored.pnl(), original.pnl());
+        assert_eq!(restored.pnl().position(), 0.01);
+        assert_eq!(restored.pnl().avg_cost(), 50000.0);
+        assert_eq!(restored.pnl().realized_pnl(), -10.0);
+        assert_eq!(restored.daily_pnl(), original.daily_pnl());
+        assert!(restored.is_killed());
+
+        // 熔断仍然锁定，直到新交易日
+        assert!(restored
+            .on_orderbook_update(&create_test_snapshot(50000.0, 50010.0))
+            .is_none());
+        restored.reset_session();
+        assert!(restored
+            .on_orderbook_update(&create_test_snapshot(50000.0, 50010.0))
+            .is_some());
+    }
+
+    #[test]
+    fn test_hedge_signal_on_threshold() {
+        let mut config = create_test_config();
+        config.hedge_threshold = 0.03;
+        config.hedge_target_inventory = 0.01;
+        let mut strategy = AvellanedaStoikov::new(config);
+
+        strategy.on_fill_with_price(OrderSide::Buy, 0.02, 50000.0);
+        assert!(strategy.check_hedge().is_none());
+
+        strategy.on_fill_with_price(OrderSide::Buy, 0.02, 50000.0);
+        let signal = strategy.check_hedge().unwrap();
+        assert_eq!(signal.side, OrderSide::Sell);
+        assert!((signal.quantity - 0.03).abs() < 1e-12);
+
+        // 空头超过阈值时买入对冲
+        strategy.on_fill_with_price(OrderSide::Sell, 0.08, 50000.0);
+        let signal = strategy.check_hedge().unwrap();
+        assert_eq!(signal.side, OrderSide::Buy);
+        assert!((signal.quantity - 0.03).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_hedge_fill_skips_cooldown_and_spread_capture() {
+        let mut config = create_test_config();
+        config.post_fill_cooldown_ns = 1_000_000_000;
+        let mut strategy = AvellanedaStoikov::new(config);
+        let snapshot = create_test_snapshot(50000.0, 50010.0);
+        assert!(strategy.on_orderbook_update(&snapshot).is_some());
+
+        strategy.on_fill_with_price(OrderSide::Sell, 0.02, 50010.0);
+        strategy.on_hedge_fill(
+            OrderSide::Buy,
+            0.02,
+            50005.0,
+            snapshot.timestamp_ns.as_u64(),
+        );
+
+        assert_eq!(strategy.get_stats().current_inventory, 0.0);
+        assert!((strategy.pnl().realized_pnl() - 0.1).abs() < 1e-9);
+        // 对冲成交不与报价成交配对
+        assert!(strategy.spread_capture_histogram().is_empty());
+
+        // 买侧没有进入冷却，卖侧仍在冷却中
+        let quote = strategy.calculate_quotes(snapshot.timestamp_ns.as_u64());
+        assert!(quote.bid_size > 0.0);
+        assert_eq!(quote.ask_size, 0.0);
+    }
+
+    #[test]
+    fn test_min_edge_keeps_bid_below_reservation() {
+        let mut config = create_test_config();
+        config.min_edge_bps = 1.0;
+        // 极端惩罚因子使得卖价被压到保留价格以下
+        config.inventory_penalty_factor = 1_000_000.0;
+        let mut strategy = AvellanedaStoikov::new(config);
+
+        strategy.on_fill_with_price(OrderSide::Sell, 0.05, 50000.0);
+        let quote = strategy
+            .on_orderbook_update(&create_test_snapshot(50000.0, 50010.0))
+            .unwrap();
+        let edge = quote.reservation_price * 1.0 / 10000.0;
+        assert!(quote.bid_price <= quote.reservation_price - edge + 1e-9);
+        assert!(quote.ask_price >= quote.reservation_price + edge - 1e-9);
+
+        strategy.on_fill_with_price(OrderSide::Buy, 0.1, 50000.0);
+        let quote = strategy
+            .on_orderbook_update(&create_test_snapshot(50000.0, 50010.0))
+            .unwrap();
+        assert!(quote.bid_price < quote.reservation_price);
+        assert!(quote.ask_price > quote.reservation_price);
+    }
+
+    #[test]
+    fn test_kill_switch_on_daily_loss() {
+        let mut config = create_test_config();
+        config.max_daily_loss = 5.0;
+        let mut strategy = AvellanedaStoikov::new(config);
+        let snapshot = create_test_snapshot(50000.0, 50010.0);
+
+        assert!(strategy.on_orderbook_update(&snapshot).is_some());
+
+        // 买入后低价卖出，亏损 0.01 * 1000 = 10
+        strategy.on_fill_with_price(OrderSide::Buy, 0.01, 50000.0);
+        assert!(!strategy.is_killed());
+        strategy.on_fill_with_price(OrderSide::Sell, 0.01, 49000.0);
+        assert!(strategy.is_killed());
+        assert!(strategy.daily_pnl() < -5.0);
+
+        assert!(strategy.on_orderbook_update(&snapshot).is_none());
+
+        // 新交易日解除熔断
+        strategy.reset_session();
+        assert!(!strategy.is_killed());
+        assert!(strategy.on_orderbook_update(&snapshot).is_some());
+    }
+
+    #[test]
+    fn test_kill_switch_on_unrealized_loss() {
+        let mut config = create_test_config();
+        config.max_daily_loss = 5.0;
+        let mut strategy = AvellanedaStoikov::new(config);
+
+        strategy.on_fill_with_price(OrderSide::Buy, 0.01, 50000.0);
+        assert!(strategy
+            .on_orderbook_update(&create_test_snapshot(49990.0, 50000.0))
+            .is_some());
+
+        // 中间价下跌导致未实现亏损超限
+        assert!(strategy
+            .on_orderbook_update(&create_test_snapshot(48990.0, 49000.0))
+            .is_none());
+        assert!(strategy.is_killed());
+    }
+
+    #[test]
+    fn test_bid_size_override() {
+        let mut config = create_test_config();
+        config.bid_size = Some(0.003);
+        let mut strategy = AvellanedaStoikov::new(config);
+
+        let quote = strategy
+            .on_orderbook_update(&create_test_snapshot(50000.0, 50010.0))
+            .unwrap();
+        assert_eq!(quote.bid_size, 0.003);
+        assert_eq!(quote.ask_size, 0.001);
+    }
+
+    #[test]
+    fn test_weighted_volatility_reacts_faster() {
+        let mut config = create_test_config();
+        config.use_parkinson = false;
+        let mut equal = AvellanedaStoikov::new(config.clone());
+        config.return_decay = Some(0.7);
+        let mut weighted = AvellanedaStoikov::new(config);
+
+        // 平稳阶段后出现剧烈波动
+        let mut prices: Vec<f64> = (0..16).map(|i| 50000.0 + (i % 2) as f64).collect();
+        prices.extend([50500.0, 49500.0, 50600.0, 49400.0]);
+        for price in prices {
+            equal.update_price_history(price);
+            weighted.update_price_history(price);
+        }
+
+        let equal_vol = equal.calculate_standard_volatility();
+        let weighted_vol = weighted.calculate_standard_volatility();
+        assert!(weighted_vol.is_finite());
+        assert!(weighted_vol > equal_vol);
+    }
+
+    #[test]
+    fn test_volatility_ignores_non_positive_prices() {
+        let config = create_test_config();
+        let mut strategy = AvellanedaStoikov::new(config);
+
+        for price in [50000.0, 50010.0, 0.0, 50020.0, 50005.0, -1.0, 50030.0] {
+            strategy.update_price_history(price);
+        }
+        let vol = strategy.calculate_standard_volatility();
+        assert!(vol.is_finite());
+        assert!(vol > 0.0);
+
+        // 有效收益率不足两个时回退到默认值
+        strategy.price_history.clear();
+        for price in [50000.0, 0.0, 50010.0] {
+            strategy.update_price_history(price);
+        }
+        assert_eq!(strategy.calculate_standard_volatility(), 0.01);
+    }
+
+    #[test]
+    fn test_kelly_size_scales_with_edge() {
+        let mut config = create_test_config();
+        config.size_model = SizeModel::Kelly { fraction: 0.5 };
+        let mut strategy = AvellanedaStoikov::new(config);
+
+        let snapshot = |bid_volume: f64, ask_volume: f64| OrderBookSnapshot {
+            best_bid: 50000.0,
+            best_ask: 50010.0,
+            bid_volume,
+            ask_volume,
+            timestamp_ns: UnixNanos::new(1_000_000_000),
+        };
+
+        // 均衡盘口：微观价格 = 中间价，无边际
+        let flat = strategy.on_orderbook_update(&snapshot(1.0, 1.0)).unwrap();
+        assert_eq!(flat.bid_size, 0.0);
+
+        let small = strategy.on_orderbook_update(&snapshot(1.0, 0.9)).unwrap();
+        let large = strategy.on_orderbook_update(&snapshot(1.0, 0.5)).unwrap();
+        assert!(small.bid_size > 0.0);
+        assert!(large.bid_size > small.bid_size);
+
+        // 边际足够大时不超过最大持仓
+        strategy.config.size_model = SizeModel::Kelly { fraction: 100.0 };
+        let capped = strategy.on_orderbook_update(&snapshot(1.0, 0.0)).unwrap();
+        assert_eq!(capped.bid_size, strategy.config.max_position_size);
+    }
+
+    #[test]
+    fn test_performance_tracks_bar_equity() {
+        let config = create_test_config();
+        let mut strategy = AvellanedaStoikov::new(config);
+        let bar = |close: f64| Bar {
+            open: close,
+            high: close + 10.0,
+            low: close - 10.0,
+            close,
+            volume: 1.0,
+            timestamp_ns: 0,
+        };
+
+        strategy.on_fill_with_price(OrderSide::Buy, 1.0, 100.0);
+        for close in [100.0, 101.0, 103.0, 106.0] {
+            strategy.on_bar(&bar(close));
+        }
+
+        // 权益变化 [1, 2, 3]
+        let performance = strategy.performance();
+        assert_eq!(performance.len(), 3);
+        let expected = 2.0 / (2.0f64 / 3.0).sqrt();
+        assert!((performance.rolling_sharpe(3) - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_spread_clamp_reporting() {
+        let snapshot = create_test_snapshot(50000.0, 50010.0);
+
+        // 默认下限 2bps (~10) 大于模型价差 (~1.3)
+        let mut strategy = AvellanedaStoikov::new(create_test_config());
+        let quote = strategy.on_orderbook_update(&snapshot).unwrap();
+        assert_eq!(quote.spread_clamped, Some(ClampDirection::Min));
+        assert!(strategy.raw_optimal_spread() < quote.spread);
+
+        let mut config = create_test_config();
+        config.min_spread_bps = 0.0;
+        config.max_spread_bps = 0.1;
+        let mut strategy = AvellanedaStoikov::new(config);
+        let quote = strategy.on_orderbook_update(&snapshot).unwrap();
+        assert_eq!(quote.spread_clamped, Some(ClampDirection::Max));
+
+        let mut config = create_test_config();
+        config.min_spread_bps = 0.0;
+        let mut strategy = AvellanedaStoikov::new(config);
+        let quote = strategy.on_orderbook_update(&snapshot).unwrap();
+        assert_eq!(quote.spread_clamped, None);
+        assert_eq!(strategy.get_stats().spread_clamp_events, 0);
+
+        let mut strategy = AvellanedaStoikov::new(create_test_config());
+        strategy.on_orderbook_update(&snapshot);
+        strategy.on_orderbook_update(&snapshot);
+        assert_eq!(strategy.get_stats().spread_clamp_events, 2);
+    }
+
+    #[test]
+    fn test_penalty_in_price_terms() {
+        // 买价相对保留价格的额外偏移 = 库存惩罚
+        let penalty_at = |in_price_terms: bool, bid: f64, ask: f64| {
+            let mut config = create_test_config();
+            config.penalty_in_price_terms = in_price_terms;
+            let mut strategy = AvellanedaStoikov::new(config);
+            strategy.on_fill_with_price(OrderSide::Buy, 0.01, bid);
+            let quote = strategy
+                .on_orderbook_update(&create_test_snapshot(bid, ask))
+                .unwrap();
+            quote.reservation_price - quote.bid_price - quote.spread * 0.5
+        };
+
+        // 默认：惩罚与价格水平无关
+        let low = penalty_at(false, 100.0, 100.02);
+        let high = penalty_at(false, 50000.0, 50010.0);
+        assert!((low - high).abs() < 1e-9);
+
+        // 按价格计量：惩罚与中间价成比例
+        let low = penalty_at(true, 100.0, 100.02);
+        let high = penalty_at(true, 50000.0, 50010.0);
+        assert!((high / low - 50005.0 / 100.01).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_depth_weighted_mid() {
+        let snapshot = OrderBookSnapshotL2 {
+            bids: vec![(99.0, 1.0), (98.0, 1.0)],
+            asks: vec![(101.0, 1.0), (102.0, 5.0)],
+            timestamp_ns: UnixNanos::new(1_000_000_000),
+        };
+
+        assert_eq!(snapshot.depth_weighted_mid(1), Some(100.0));
+        // 更深的卖盘把加权中间价往上拉
+        let weighted = snapshot.depth_weighted_mid(2).unwrap();
+        assert!(weighted > 100.0);
+
+        let mut config = create_test_config();
+        config.reference_price = ReferencePrice::DepthWeighted { levels: 2 };
+        let mut strategy = AvellanedaStoikov::new(config);
+        strategy.on_orderbook_update_l2(&snapshot).unwrap();
+        assert_eq!(strategy.get_stats().mid_price, weighted);
+
+        // 未配置档位时使用最优买卖价中间价
+        let mut strategy = AvellanedaStoikov::new(create_test_config());
+        strategy.on_orderbook_update_l2(&snapshot).unwrap();
+        assert_eq!(strategy.get_stats().mid_price, 100.0);
+
+        // 已弃用的 l2_depth_levels 仍然生效
+        let mut json = serde_json::to_value(create_test_config()).unwrap();
+        json.as_object_mut().unwrap().remove("reference_price");
+        json["l2_depth_levels"] = 2.into();
+        let config: ASConfig = serde_json::from_value(json).unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            config.reference_price_source(),
+            ReferencePrice::DepthWeighted { levels: 2 }
+        );
+        let mut strategy = AvellanedaStoikov::new(config.clone());
+        strategy.on_orderbook_update_l2(&snapshot).unwrap();
+        assert_eq!(strategy.get_stats().mid_price, weighted);
+
+        // 与其他参考价格来源同时设置时报错
+        let conflicting = ASConfig {
+            reference_price: ReferencePrice::Microprice,
+            ..config
+        };
+        assert!(conflicting.validate().is_err());
+    }
+
+    #[test]
+    fn test_initial_volatility() {
+        let mut config = create_test_config();
+        config.initial_volatility = 0.05;
+        let mut strategy = AvellanedaStoikov::new(config);
+        assert_eq!(strategy.get_stats().current_volatility, 0.05);
+
+        strategy.on_fill_with_price(OrderSide::Buy, 0.01, 50000.0);
+        let quote = strategy
+            .on_orderbook_update(&create_test_snapshot(50000.0, 50010.0))
+            .unwrap();
+
+        // 预热前保留价格使用配置的回退波动率
+        let expected = 50005.0 - 0.01 * 0.1 * 0.05 * 0.05 * 300.0;
+        assert!((quote.reservation_price - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_inventory_scaled_horizon() {
+        let mut config = create_test_config();
+        config.inventory_scaled_horizon = true;
+        let mut strategy = AvellanedaStoikov::new(config.clone());
+        strategy.on_orderbook_update(&create_test_snapshot(50000.0, 50010.0));
+        assert_eq!(strategy.effective_time_remaining(), 300.0);
+
+        // 库存占用一半，有效时间窗口减半
+        strategy.on_fill_with_price(OrderSide::Buy, 0.025, 50000.0);
+        assert!((strategy.effective_time_remaining() - 150.0).abs() < 1e-9);
+        let scaled_offset = 50005.0 - strategy.reservation_price();
+
+        config.inventory_scaled_horizon = false;
+        let mut unscaled = AvellanedaStoikov::new(config);
+        unscaled.on_orderbook_update(&create_test_snapshot(50000.0, 50010.0));
+        unscaled.on_fill_with_price(OrderSide::Buy, 0.025, 50000.0);
+        assert_eq!(unscaled.effective_time_remaining(), 300.0);
+        let unscaled_offset = 50005.0 - unscaled.reservation_price();
+
+        assert!((scaled_offset - unscaled_offset * 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_spread_bps() {
+        let mut strategy = AvellanedaStoikov::new(create_test_config());
+        let quote = strategy
+            .on_orderbook_update(&create_test_snapshot(50000.0, 50010.0))
+            .unwrap();
+
+        let expected = quote.spread / quote.reservation_price * 10000.0;
+        assert!((quote.spread_bps - expected).abs() < 1e-12);
+        assert_eq!(spread_to_bps(1.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn test_mid_smoothing() {
+        let mut config = create_test_config();
+        config.mid_smoothing_alpha = 0.2;
+        let mut strategy = AvellanedaStoikov::new(config);
+
+        strategy.on_orderbook_update(&create_test_snapshot(50000.0, 50010.0));
+        assert_eq!(strategy.get_stats().mid_price, 50005.0);
+
+        // 原始中间价阶跃 +100，平滑后只移动 20%
+        strategy.on_orderbook_update(&create_test_snapshot(50100.0, 50110.0));
+        let smoothed = strategy.get_stats().mid_price;
+        assert!((smoothed - 50025.0).abs() < 1e-9);
+        assert!(smoothed < 50105.0);
+
+        // 默认 alpha = 1.0 时直接跟随原始中间价
+        let mut strategy = AvellanedaStoikov::new(create_test_config());
+        strategy.on_orderbook_update(&create_test_snapshot(50000.0, 50010.0));
+        strategy.on_orderbook_update(&create_test_snapshot(50100.0, 50110.0));
+        assert_eq!(strategy.get_stats().mid_price, 50105.0);
+    }
+
+    #[test]
+    fn test_log_quotes() {
+        use std::sync::Arc;
+        use tracing::field::{Field, Visit};
+        use tracing_subscriber::layer::{Context, SubscriberExt};
+        use tracing_subscriber::Layer;
+
+        struct FieldCapture(Arc<Mutex<Vec<String>>>);
+
+        impl Visit for FieldCapture {
+            fn record_debug(&mut self, field: &Field, _value: &dyn std::fmt::Debug) {
+                self.0.lock().unwrap().push(field.name().to_string());
+            }
+        }
+
+        struct CaptureLayer(Arc<Mutex<Vec<String>>>);
+
+        impl<S: tracing::Subscriber> Layer<S> for CaptureLayer {
+            fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+                if *event.metadata().level() == tracing::Level::DEBUG {
+                    event.record(&mut FieldCapture(self.0.clone()));
+                }
+            }
+        }
+
+        let fields = Arc::new(Mutex::new(Vec::new()));
+        let subscriber = tracing_subscriber::registry().with(CaptureLayer(fields.clone()));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let mut strategy = AvellanedaStoikov::new(create_test_config());
+            strategy.on_orderbook_update(&create_test_snapshot(50000.0, 50010.0));
+        });
+        assert!(fields.lock().unwrap().is_empty());
+
+        let subscriber = tracing_subscriber::registry().with(CaptureLayer(fields.clone()));
+        tracing::subscriber::with_default(subscriber, || {
+            let mut config = create_test_config();
+            config.log_quotes = true;
+            let mut strategy = AvellanedaStoikov::new(config);
+            strategy.on_orderbook_update(&create_test_snapshot(50000.0, 50010.0));
+        });
+
+        let fields = fields.lock().unwrap();
+        for name in [
+            "reservation_price",
+            "spread",
+            "inventory",
+            "bid_price",
+            "bid_size",
+            "ask_price",
+            "ask_size",
+        ] {
+            assert!(fields.iter().any(|f| f == name), "missing field {name}");
+        }
+    }
+
+    #[test]
+    fn test_final_spread_floor() {
+        for (sigma, inventory) in [(0.5, 0.05), (2.0, -0.05), (1e-6, 0.0), (10.0, 0.049)] {
+            let mut config = create_test_config();
+            config.initial_volatility = sigma;
+            config.inventory_penalty_factor = 1e6;
+            config.min_spread_bps = 50.0;
+            config.max_spread_bps = 50.0;
+            let mut strategy = AvellanedaStoikov::new(config);
+            if inventory > 0.0 {
+                strategy.on_fill_with_price(OrderSide::Buy, inventory, 50000.0);
+            } else if inventory < 0.0 {
+                strategy.on_fill_with_price(OrderSide::Sell, -inventory, 50000.0);
+            }
+
+            let quote = strategy
+                .on_orderbook_update(&create_test_snapshot(50000.0, 50010.0))
+                .unwrap();
+            let floor = 50005.0 * 50.0 / 10000.0;
+            assert!(quote.ask_price - quote.bid_price >= floor - 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_session_time() {
+        let mut strategy = AvellanedaStoikov::new(create_test_config());
+        assert_eq!(strategy.session_elapsed_secs(5_000_000_000), 0.0);
+        assert_eq!(strategy.time_remaining_secs(5_000_000_000), 300.0);
+
+        // 首个有效订单簿时间作为时段起点
+        strategy.on_orderbook_update(&create_test_snapshot(50000.0, 50010.0));
+        assert_eq!(strategy.session_elapsed_secs(61_000_000_000), 60.0);
+        assert_eq!(strategy.time_remaining_secs(61_000_000_000), 240.0);
+
+        strategy.start_session(100_000_000_000);
+        assert_eq!(strategy.session_elapsed_secs(250_000_000_000), 150.0);
+        assert_eq!(strategy.time_remaining_secs(250_000_000_000), 150.0);
+        assert_eq!(strategy.time_remaining_secs(500_000_000_000), 0.0);
+        assert_eq!(strategy.session_elapsed_secs(50_000_000_000), 0.0);
+    }
+
+    #[test]
+    fn test_self_cross_resolution() {
+        // 负风险厌恶 + 零最小价差：模型价差为负并被限制为0，买卖价锁定
+        let mut config = create_test_config();
+        config.risk_aversion = -0.1;
+        config.initial_volatility = 1.0;
+        config.min_spread_bps = 0.0;
+        let mut strategy = AvellanedaStoikov::new(config);
+
+        let quote = strategy
+            .on_orderbook_update(&create_test_snapshot(50000.0, 50010.0))
+            .unwrap();
+
+        assert!(quote.bid_price < quote.ask_price);
+        assert!((quote.ask_price - quote.bid_price - 0.01).abs() < 1e-9);
+        let center = (quote.bid_price + quote.ask_price) * 0.5;
+        assert!((center - quote.reservation_price).abs() < 1e-9);
+        assert_eq!(strategy.get_stats().self_cross_events, 1);
+    }
+
+    #[test]
+    fn test_bar_interval_rescaling() {
+        let mut config = create_test_config();
+        config.initial_volatility = 0.06;
+        let per_bar = AvellanedaStoikov::new(config.clone());
+
+        config.bar_interval_secs = Some(60.0);
+        let per_sec = AvellanedaStoikov::new(config.clone());
+        assert!((per_sec.quote_volatility() - 0.06 / 60f64.sqrt()).abs() < 1e-12);
+
+        // σ² 项随间隔线性缩小
+        let gamma = config.risk_aversion;
+        let kappa = config.price_sensitivity;
+        let adjustment = (2.0 / gamma) * (1.0 + gamma / kappa).ln();
+        let base_per_bar = per_bar.raw_optimal_spread() - adjustment;
+        let base_per_sec = per_sec.raw_optimal_spread() - adjustment;
+        assert!((base_per_bar / base_per_sec - 60.0).abs() < 1e-9);
+    }
+
+    fn arb_config() -> impl proptest::strategy::Strategy<Value = ASConfig> {
+        use proptest::prelude::*;
+
+        (
+            (0.001f64..10.0, 0.1f64..10.0, 1.0f64..3600.0),
+            (1e-4f64..1.0, 1e-4f64..1.0, 2usize..100),
+            (any::<bool>(), 1e-4f64..0.5, 0.0f64..10.0),
+            (0.1f64..50.0, 0.0f64..500.0),
+        )
+            .prop_map(
+                |(
+                    (risk_aversion, price_sensitivity, time_horizon),
+                    (base_order_size, max_inventory, volatility_window),
+                    (use_parkinson, initial_volatility, inventory_penalty_factor),
+                    (min_spread_bps, extra_spread_bps),
+                )| ASConfig {
+                    risk_aversion,
+                    price_sensitivity,
+                    time_horizon,
+                    base_order_size,
+                    max_inventory,
+                    volatility_window,
+                    use_parkinson,
+                    initial_volatility,
+                    inventory_penalty_factor,
+                    min_spread_bps,
+                    max_spread_bps: min_spread_bps + extra_spread_bps,
+                    ..ASConfig::default()
+                },
+            )
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn test_quotes_always_finite_and_ordered(
+            config in arb_config(),
+            books in proptest::collection::vec((1.0f64..100_000.0, 0.0f64..0.01), 1..50),
+        ) {
+            let min_bps = config.min_spread_bps;
+            let max_bps = config.max_spread_bps;
+            let mut strategy = AvellanedaStoikov::new(config);
+
+            for (i, (mid, half_width)) in books.into_iter().enumerate() {
+                let bid = mid * (1.0 - half_width);
+                let ask = mid * (1.0 + half_width);
+                if i % 5 == 4 {
+                    strategy.on_bar(&Bar {
+                        open: mid,
+                        high: ask,
+                        low: bid,
+                        close: mid,
+                        volume: 1.0,
+                        timestamp_ns: i as u64,
+                    });
+                }
+
+                let quote = strategy
+                    .on_orderbook_update(&create_test_snapshot(bid, ask))
+                    .unwrap();
+                let ref_mid = strategy.get_stats().mid_price;
+
+                proptest::prop_assert!(quote.bid_price.is_finite() && quote.ask_price.is_finite());
+                proptest::prop_assert!(quote.ask_price > quote.bid_price);
+                proptest::prop_assert!(quote.bid_size > 0.0 && quote.ask_size > 0.0);
+                let tolerance = ref_mid * 1e-12;
+                proptest::prop_assert!(quote.spread >= ref_mid * min_bps / 10000.0 - tolerance);
+                proptest::prop_assert!(quote.spread <= ref_mid * max_bps / 10000.0 + tolerance);
+            }
+        }
+    }
+
+    #[test]
+    fn test_reconfigure_resizes_history() {
+        let mut strategy = AvellanedaStoikov::new(create_test_config());
+        for i in 0..30 {
+            strategy.update_price_history(50000.0 + i as f64);
+        }
+        assert_eq!(strategy.price_history.capacity(), 20);
+        assert_eq!(strategy.price_history.len(), 20);
+
+        let mut config = create_test_config();
+        config.volatility_window = 5;
+        strategy.reconfigure(config).unwrap();
+        assert_eq!(strategy.price_history.capacity(), 5);
+        assert_eq!(strategy.bar_history.capacity(), 5);
+        assert_eq!(strategy.price_history.iter().next(), Some(&50025.0));
+
+        let mut config = create_test_config();
+        config.volatility_window = 50;
+        strategy.reconfigure(config).unwrap();
+        assert_eq!(strategy.price_history.capacity(), 50);
+        assert_eq!(strategy.price_history.len(), 5);
+
+        // 非法配置被拒绝，原配置保持不变
+        let mut config = create_test_config();
+        config.risk_aversion = 0.0;
+        assert!(strategy.reconfigure(config).is_err());
+        assert_eq!(strategy.config.volatility_window, 50);
+    }
+
+    #[test]
+    fn test_end_of_session_flatten() {
+        let mut config = create_test_config();
+        config.end_of_session_flatten = true;
+        let mut strategy = AvellanedaStoikov::new(config);
+        strategy.start_session(0);
+        strategy.on_fill_with_price(OrderSide::Buy, 0.01, 50000.0);
+
+        let snapshot_at = |secs: u64| OrderBookSnapshot {
+            timestamp_ns: UnixNanos::new(secs * 1_000_000_000),
+            ..create_test_snapshot(50000.0, 50010.0)
+        };
+
+        // 时段前段正常双边报价
+        let quote = strategy.on_orderbook_update(&snapshot_at(100)).unwrap();
+        assert!(quote.bid_size > 0.0 && quote.ask_size > 0.0);
+
+        // 剩余 20s < 10% * 300s：只在中间价卖出全部多头库存
+        let quote = strategy.on_orderbook_update(&snapshot_at(280)).unwrap();
+        assert_eq!(quote.bid_size, 0.0);
+        assert!((quote.ask_size - 0.01).abs() < 1e-12);
+        assert!(quote.ask_price <= 50005.0);
+
+        // 持平后停止报价
+        strategy.on_fill_with_price(OrderSide::Sell, 0.01, 50005.0);
+        let quote = strategy.on_orderbook_update(&snapshot_at(290)).unwrap();
+        assert_eq!(quote.bid_size, 0.0);
+        assert_eq!(quote.ask_size, 0.0);
+
+        // 时段结束后滚动到下一时段，恢复双边报价
+        let quote = strategy.on_orderbook_update(&snapshot_at(305)).unwrap();
+        assert!(quote.bid_size > 0.0 && quote.ask_size > 0.0);
+        assert_eq!(strategy.session_elapsed_secs(305_000_000_000), 5.0);
+
+        // 跳过多个时段时对齐到当前时段起点，下一时段末照常进入平仓窗口
+        strategy.on_fill_with_price(OrderSide::Buy, 0.01, 50000.0);
+        let quote = strategy.on_orderbook_update(&snapshot_at(1250)).unwrap();
+        assert!(quote.bid_size > 0.0 && quote.ask_size > 0.0);
+        assert_eq!(strategy.session_elapsed_secs(1_250_000_000_000), 50.0);
+        let quote = strategy.on_orderbook_update(&snapshot_at(1490)).unwrap();
+        assert_eq!(quote.bid_size, 0.0);
+        assert!(quote.ask_size > 0.0);
+    }
+
+    #[test]
+    fn test_apply_config_update() {
+        let mut strategy = AvellanedaStoikov::new(create_test_config());
+
+        let update: PartialASConfig =
+            serde_json::from_str(r#"{"risk_aversion": 0.5, "max_spread_bps": 150.0}"#).unwrap();
+        strategy.apply_config_update(&update).unwrap();
+
+        let expected = ASConfig {
+            risk_aversion: 0.5,
+            max_spread_bps: 150.0,
+            ..create_test_config()
+        };
+        assert_eq!(
+            serde_json::to_value(strategy.config()).unwrap(),
+            serde_json::to_value(&expected).unwrap()
+        );
+
+        // 合并后非法的更新被拒绝
+        let invalid = PartialASConfig {
+            min_spread_bps: Some(500.0),
+            ..Default::default()
+        };
+        assert!(strategy.apply_config_update(&invalid).is_err());
+        assert_eq!(strategy.config().min_spread_bps, 2.0);
+    }
+
+    #[test]
+    fn test_garman_klass_volatility() {
+        let bars = [
+            (100.0, 102.0, 99.0, 101.0),
+            (101.0, 101.5, 98.0, 98.5),
+            (98.5, 100.0, 97.0, 99.5),
+            (99.5, 103.0, 99.0, 102.5),
+        ];
+
+        let mut config = create_test_config();
+        config.volatility_estimator = Some(VolatilityEstimator::GarmanKlass);
+        let mut gk = AvellanedaStoikov::new(config);
+        let mut parkinson = AvellanedaStoikov::new(create_test_config());
+
+        for (i, &(open, high, low, close)) in bars.iter().enumerate() {
+            let bar = Bar {
+                open,
+                high,
+                low,
+                close,
+                volume: 1.0,
+                timestamp_ns: i as u64,
+            };
+            gk.on_bar(&bar);
+            parkinson.on_bar(&bar);
+        }
+
+        let gk_vol = gk.get_stats().current_volatility;
+        let parkinson_vol = parkinson.get_stats().current_volatility;
+        assert!(gk_vol > 0.0);
+        assert!((gk_vol - garman_klass_volatility(&bars)).abs() < 1e-12);
+        assert!((gk_vol - parkinson_vol).abs() > 1e-6);
+    }
+
+    #[test]
+    fn test_min_order_size_suppresses_dust() {
+        let mut config = create_test_config();
+        config.min_order_size = 0.0005;
+        let mut strategy = AvellanedaStoikov::new(config);
+
+        let quote = strategy
+            .on_orderbook_update(&create_test_snapshot(50000.0, 50010.0))
+            .unwrap();
+        assert_eq!(quote.bid_size, 0.001);
+
+        // 库存占用 90%，买单数量缩减到 0.0001 < 0.0005；减仓的卖单不受影响
+        strategy.on_fill_with_price(OrderSide::Buy, 0.045, 50000.0);
+        let quote = strategy
+            .on_orderbook_update(&create_test_snapshot(50000.0, 50010.0))
+            .unwrap();
+        assert_eq!(quote.bid_size, 0.0);
+        assert_eq!(quote.ask_size, 0.001);
+    }
+
+    #[test]
+    fn test_config_from_env() {
+        let vars = std::collections::HashMap::from([
+            ("AS_RISK_AVERSION", "0.25"),
+            ("AS_MAX_SPREAD_BPS", "80"),
+            ("AS_VOLATILITY_WINDOW", "50"),
+            ("AS_LOG_QUOTES", "true"),
+            ("AS_BID_SIZE", "0.002"),
+            ("AS_INSTRUMENT_ID", "ETHUSDT.BINANCE"),
+            ("OTHER_RISK_AVERSION", "9.0"),
+        ]);
+        let env = |key: &str| vars.get(key).map(|v| v.to_string());
+
+        let config = ASConfig::from_env_with("AS", env).unwrap();
+        let defaults = ASConfig::default();
+        assert_eq!(config.risk_aversion, 0.25);
+        assert_eq!(config.max_spread_bps, 80.0);
+        assert_eq!(config.volatility_window, 50);
+        assert!(config.log_quotes);
+        assert_eq!(config.bid_size, Some(0.002));
+        assert_eq!(config.instrument_id, "ETHUSDT.BINANCE");
+        assert_eq!(config.min_spread_bps, defaults.min_spread_bps);
+        assert_eq!(config.time_horizon, defaults.time_horizon);
+
+        // 无法解析或校验失败时报错
+        let bad = |key: &str| (key == "AS_RISK_AVERSION").then(|| "abc".to_string());
+        assert!(ASConfig::from_env_with("AS", bad).is_err());
+        let invalid = |key: &str| (key == "AS_MAX_INVENTORY").then(|| "0".to_string());
+        assert!(ASConfig::from_env_with("AS", invalid).is_err());
+
+        // 真实环境变量（使用独立前缀避免与其他测试冲突）
+        // 枚举与复合字段使用 JSON 格式，裸字符串视为 JSON 字符串
+        let vars = std::collections::HashMap::from([
+            ("AS_SKEW_SHAPE", "Tanh"),
+            ("AS_ROUNDING_POLICY", "\"Nearest\""),
+            ("AS_INVENTORY_BAND", "[-0.5, 0.5]"),
+            ("AS_SIZE_MODEL", r#"{"Kelly":{"fraction":0.5}}"#),
+            ("AS_VOLATILITY_ESTIMATOR", "GarmanKlass"),
+            ("AS_MAX_NOTIONAL", "1000"),
+            ("AS_QUEUE_AWARE", "true"),
+            ("AS_MAKER_FEE_BPS", "-0.5"),
+        ]);
+        let env = |key: &str| vars.get(key).map(|v| v.to_string());
+        let config = ASConfig::from_env_with("AS", env).unwrap();
+        assert_eq!(config.skew_shape, SkewShape::Tanh);
+        assert_eq!(config.rounding_policy, RoundingPolicy::Nearest);
+        assert_eq!(config.inventory_band, (-0.5, 0.5));
+        assert_eq!(config.size_model, SizeModel::Kelly { fraction: 0.5 });
+        assert_eq!(
+            config.volatility_estimator,
+            Some(VolatilityEstimator::GarmanKlass)
+        );
+        assert_eq!(config.max_notional, Some(1000.0));
+        assert!(config.queue_aware);
+        assert_eq!(config.maker_fee_bps, -0.5);
+        let bad = |key: &str| (key == "AS_SKEW_SHAPE").then(|| "Cubic".to_string());
+        assert!(ASConfig::from_env_with("AS", bad).is_err());
+
+        // 每个序列化字段都能从环境变量设置
+        let keys = std::cell::RefCell::new(std::collections::HashSet::new());
+        ASConfig::from_env_with("AS", |key| {
+            keys.borrow_mut().insert(key.to_string());
+            None
+        })
+        .unwrap();
+        let fields = serde_json::to_value(ASConfig::default()).unwrap();
+        for field in fields.as_object().unwrap().keys() {
+            let key = format!("AS_{}", field.to_uppercase());
+            assert!(keys.borrow().contains(&key), "{} 未从环境变量读取", key);
+        }
+
+        std::env::set_var("AS_ENV_TEST_MIN_SPREAD_BPS", "5");
+        let config = ASConfig::from_env("AS_ENV_TEST").unwrap();
+        assert_eq!(config.min_spread_bps, 5.0);
+        assert_eq!(config.risk_aversion, defaults.risk_aversion);
+    }
+
+    #[test]
+    fn test_non_finite_book_rejected() {
+        let mut strategy = AvellanedaStoikov::new(create_test_config());
+        strategy.on_orderbook_update(&create_test_snapshot(50000.0, 50010.0));
+        let before = strategy.get_stats();
+
+        let mut bad_volume = create_test_snapshot(50000.0, 50010.0);
+        bad_volume.bid_volume = -1.0;
+        let mut nan_volume = create_test_snapshot(50000.0, 50010.0);
+        nan_volume.ask_volume = f64::NAN;
+
+        for snapshot in [
+            create_test_snapshot(f64::NAN, 50010.0),
+            create_test_snapshot(50000.0, f64::INFINITY),
+            bad_volume,
+            nan_volume,
+        ] {
+            assert!(strategy.on_orderbook_update(&snapshot).is_none());
+        }
+
+        let after = strategy.get_stats();
+        assert_eq!(after.orderbook_updates, before.orderbook_updates);
+        assert_eq!(after.mid_price, before.mid_price);
+        assert_eq!(strategy.price_history.len(), 1);
+    }
+
+    #[test]
+    fn test_inventory_band() {
+        let mut config = create_test_config();
+        config.inventory_band = (-0.01, 0.01);
+        let mut strategy = AvellanedaStoikov::new(config);
+
+        // 区间内：报价围绕中间价对称
+        strategy.on_fill_with_price(OrderSide::Buy, 0.005, 50000.0);
+        let quote = strategy
+            .on_orderbook_update(&create_test_snapshot(50000.0, 50010.0))
+            .unwrap();
+        assert_eq!(strategy.skew_inventory(), 0.0);
+        assert_eq!(quote.reservation_price, 50005.0);
+        assert!(((quote.bid_price + quote.ask_price) * 0.5 - 50005.0).abs() < 1e-9);
+
+        // 区间外：按超出部分向下偏斜
+        strategy.on_fill_with_price(OrderSide::Buy, 0.015, 50000.0);
+        let quote = strategy
+            .on_orderbook_update(&create_test_snapshot(50000.0, 50010.0))
+            .unwrap();
+        assert!((strategy.skew_inventory() - 0.01).abs() < 1e-12);
+        assert!(quote.reservation_price < 50005.0);
+        assert!((quote.bid_price + quote.ask_price) * 0.5 < 50005.0);
+    }
+
+    #[test]
+    fn test_spread_surface_monotonic() {
+        let config = create_test_config();
+        let gammas = [0.01, 0.1, 0.5, 1.0];
+        // 价差对 γ 单调递增要求 σ²T 大于流动性项的斜率（约 1/κ²）
+        let sigmas = [0.05, 0.1, 0.2, 0.5];
+        let surface = spread_surface(&config, &gammas, &sigmas);
+
+        assert_eq!(surface.len(), gammas.len());
+        for (i, row) in surface.iter().enumerate() {
+            assert_eq!(row.len(), sigmas.len());
+            for j in 0..sigmas.len() {
+                if j > 0 {
+                    assert!(row[j] > row[j - 1]);
+                }
+                if i > 0 {
+                    assert!(row[j] > surface[i - 1][j]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_skew_shapes() {
+        let skew_at = |shape: SkewShape, inventory: f64| {
+            let mut config = create_test_config();
+            config.skew_shape = shape;
+            let mut strategy = AvellanedaStoikov::new(config);
+            strategy.inventory.data = inventory;
+            strategy.skew_inventory()
+        };
+
+        // 半仓（max_inventory = 0.05）：二次和 tanh 的偏斜都小于线性
+        let linear = skew_at(SkewShape::Linear, 0.025);
+        let quadratic = skew_at(SkewShape::Quadratic, 0.025);
+        let tanh = skew_at(SkewShape::Tanh, 0.025);
+        assert!((linear - 0.025).abs() < 1e-12);
+        assert!((quadratic - 0.0125).abs() < 1e-12);
+        assert!(quadratic < tanh && tanh < linear);
+
+        // 方向保持：空头库存偏斜为负
+        assert!(skew_at(SkewShape::Quadratic, -0.025) < 0.0);
+        assert!(skew_at(SkewShape::Tanh, -0.025) < 0.0);
+
+        // 远超上限时 tanh 饱和于 max_inventory，线性和二次继续放大
+        let far = 0.5;
+        assert!(skew_at(SkewShape::Tanh, far) <= 0.05);
+        assert!(skew_at(SkewShape::Linear, far) > 0.05 * 9.0);
+        assert!(skew_at(SkewShape::Quadratic, far) > skew_at(SkewShape::Linear, far));
+    }
+
+    #[test]
+    fn test_calibrate_intensity_recovers_parameters() {
+        let (a, kappa) = (140.0, 1.5);
+
+        // 确定性的乘性噪声（±2%），模拟观测误差
+        let fills: Vec<(f64, f64)> = (0..40)
+            .map(|i| {
+                let delta = i as f64 * 0.1;
+                let noise = 1.0 + 0.02 * (i as f64 * 1.7).sin();
+                (delta, a * (-kappa * delta).exp() * noise)
+            })
+            .collect();
+
+        let (fitted_a, fitted_kappa) = calibrate_intensity(&fills);
+        assert!((fitted_a - a).abs() / a < 0.05, "A = {}", fitted_a);
+        assert!(
+            (fitted_kappa - kappa).abs() / kappa < 0.05,
+            "κ = {}",
+            fitted_kappa
+        );
+
+        // 无噪声时精确恢复
+        let exact: Vec<(f64, f64)> = (0..10)
+            .map(|i| (i as f64 * 0.5, a * (-kappa * i as f64 * 0.5).exp()))
+            .collect();
+        let (fitted_a, fitted_kappa) = calibrate_intensity(&exact);
+        assert!((fitted_a - a).abs() < 1e-6);
+        assert!((fitted_kappa - kappa).abs() < 1e-9);
+
+        // 数据不足或退化
+        assert_eq!(calibrate_intensity(&[(0.1, 10.0)]), (0.0, 0.0));
+        assert_eq!(calibrate_intensity(&[(0.1, 10.0), (0.1, 5.0)]), (0.0, 0.0));
+        assert_eq!(calibrate_intensity(&[(0.1, 0.0), (0.2, -1.0)]), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_pnl_report() {
+        let mut strategy = AvellanedaStoikov::new(create_test_config());
+        strategy.on_orderbook_update(&create_test_snapshot(50000.0, 50010.0));
+        strategy.on_fill_with_price(OrderSide::Buy, 0.01, 50000.0);
+        strategy.on_fill_with_price(OrderSide::Sell, 0.005, 50010.0);
+        strategy.reset_session();
+        strategy.on_fill_with_price(OrderSide::Sell, 0.005, 50020.0);
+
+        let report = strategy.pnl_report();
+        assert_eq!(report.session_start_ns, Some(1_000_000_000));
+        assert_eq!(report.session_end_ns, 1_000_000_000);
+        assert!((report.realized_pnl - 0.15).abs() < 1e-9);
+        assert!((report.session_realized_pnl - 0.1).abs() < 1e-9);
+        assert_eq!(report.mark_price, 50005.0);
+    }
+
+    #[test]
+    fn test_stats_pnl_attribution() {
+        let mut strategy = AvellanedaStoikov::new(create_test_config());
+
+        strategy.on_orderbook_update(&create_test_snapshot(49990.0, 50010.0));
+        strategy.on_fill_with_price(OrderSide::Buy, 0.01, 49995.0);
+
+        let mut snapshot = create_test_snapshot(50090.0, 50110.0);
+        snapshot.timestamp_ns = UnixNanos::new(2_000_000_000);
+        strategy.on_orderbook_update(&snapshot);
+        strategy.on_fill_with_price(OrderSide::Sell, 0.01, 50105.0);
+
+        // 价差盈亏: 0.01*5 + 0.01*5；库存盈亏: 0.01*(50100-50000)
+        let stats = strategy.get_stats();
+        assert!((stats.spread_pnl - 0.1).abs() < 1e-9);
+        assert!((stats.inventory_pnl - 1.0).abs() < 1e-9);
+        assert!(
+            (stats.spread_pnl + stats.inventory_pnl - strategy.pnl().realized_pnl()).abs() < 1e-9
+        );
+    }
+
+    #[test]
+    fn test_distribute_lots_preserves_total() {
+        let lot = 0.001;
+        let weights = LadderSizing::Geometric { decay: 0.7 }.weights(5);
+        let total = 0.0137;
+
+        let sizes = distribute_lots(total, &weights, lot);
+        let sum: f64 = sizes.iter().sum();
+        assert!((total - sum).abs() < lot, "sum = {}", sum);
+        assert!((sum - 0.013).abs() < 1e-12);
+        for size in &sizes {
+            let lots = size / lot;
+            assert!((lots - lots.round()).abs() < 1e-9);
+        }
+
+        // 逐档向下取整会丢失多个步长
+        let weight_sum: f64 = weights.iter().sum();
+        let naive: f64 = weights
+            .iter()
+            .map(|w| round_down_to_lot(total * w / weight_sum, lot))
+            .sum();
+        assert!(total - naive >= lot);
+    }
+
+    #[test]
+    fn test_quote_ladder() {
+        let mut config = create_test_config();
+        config.ladder_levels = 4;
+        config.ladder_step_bps = 10.0;
+        config.ladder_sizing = LadderSizing::Geometric { decay: 0.6 };
+        config.lot_size = 0.0001;
+        config.base_order_size = 0.0013;
+        let mut strategy = AvellanedaStoikov::new(config);
+
+        let top = strategy
+            .on_orderbook_update(&create_test_snapshot(50000.0, 50010.0))
+            .unwrap();
+        let before = strategy.get_stats();
+        let ladder = strategy.quote_ladder(&top);
+        assert_eq!(ladder.len(), 4);
+        // 展开多档不计入报价统计
+        assert_eq!(strategy.get_stats().quote_updates, before.quote_updates);
+
+        // 价格逐档向外
+        assert_eq!(ladder[0].bid_price, top.bid_price);
+        assert_eq!(ladder[0].ask_price, top.ask_price);
+        for pair in ladder.windows(2) {
+            assert!((pair[0].bid_price - pair[1].bid_price - 50.005).abs() < 1e-9);
+            assert!((pair[1].ask_price - pair[0].ask_price - 50.005).abs() < 1e-9);
+            assert!(pair[1].bid_size <= pair[0].bid_size);
+        }
+
+        // 总数量与目标相差不超过一个步长
+        let intended = top.bid_size * 4.0;
+        let total: f64 = ladder.iter().map(|level| level.bid_size).sum();
+        assert!((intended - total).abs() < 0.0001 + 1e-12);
+    }
+
+    #[test]
+    fn test_flatten_at_time_of_day() {
+        assert_eq!(parse_utc_time_of_day("21:30").unwrap(), 21 * 3600 + 30 * 60);
+        assert_eq!(parse_utc_time_of_day("00:00").unwrap(), 0);
+        assert!(parse_utc_time_of_day("24:00").is_err());
+        assert!(parse_utc_time_of_day("12:60").is_err());
+        assert!(parse_utc_time_of_day("1230").is_err());
+
+        // 2024-01-01 21:29:59 / 21:30:00 UTC
+        let flatten_at = parse_utc_time_of_day("21:30").unwrap();
+        let before_ns = (1_704_144_599u64) * 1_000_000_000;
+        assert!(!time_of_day_reached(before_ns, flatten_at));
+        assert!(time_of_day_reached(before_ns + 1_000_000_000, flatten_at));
+
+        // 触发后只报减仓一侧
+        let mut strategy = AvellanedaStoikov::new(create_test_config());
+        strategy.on_orderbook_update(&create_test_snapshot(50000.0, 50010.0));
+        strategy.on_fill_with_price(OrderSide::Buy, 0.003, 50000.0);
+        strategy.request_flatten();
+        let quote = strategy.calculate_quotes(2_000_000_000);
+        assert_eq!(quote.bid_size, 0.0);
+        assert!((quote.ask_size - 0.003).abs() < 1e-12);
+        assert!(quote.ask_price <= 50005.0);
+
+        // 新交易日清除平仓请求，恢复双边报价
+        assert_eq!(utc_day(before_ns), 19_723);
+        strategy.reset_session();
+        assert!(!strategy.is_flatten_requested());
+        assert!(strategy.calculate_quotes(2_000_000_000).bid_size > 0.0);
+    }
+
+    #[test]
+    fn test_break_even_volatility() {
+        let mut config = create_test_config();
+        // 价差固定为 10bp，便于手算
+        config.min_spread_bps = 10.0;
+        config.max_spread_bps = 10.0;
+        config.maker_fee_bps = 1.0;
+        config.expected_holding_secs = 60.0;
+        let mut strategy = AvellanedaStoikov::new(config);
+        assert_eq!(strategy.break_even_volatility(), 0.0);
+
+        strategy.on_orderbook_update(&create_test_snapshot(49995.0, 50005.0));
+
+        // e = 50/50000 - 2*0.0001 = 0.0008，σ* = 0.0008 / √(120/π) ≈ 1.2944e-4
+        let expected = 0.0008 / (120.0 / std::f64::consts::PI).sqrt();
+        assert!((strategy.break_even_volatility() - expected).abs() < 1e-12);
+        assert!((strategy.break_even_volatility() - 1.2944e-4).abs() < 1e-8);
+
+        // 手续费吃掉全部价差
+        let mut config = create_test_config();
+        config.maker_fee_bps = 10.0;
+        let mut strategy = AvellanedaStoikov::new(config);
+        strategy.on_orderbook_update(&create_test_snapshot(49995.0, 50005.0));
+        assert_eq!(strategy.break_even_volatility(), 0.0);
+    }
+
+    #[test]
+    fn test_halt_above_break_even_volatility() {
+        let mut config = create_test_config();
+        config.halt_above_break_even_volatility = true;
+        config.bar_interval_secs = Some(1.0);
+        let mut strategy = AvellanedaStoikov::new(config.clone());
+
+        // 初始波动率 0.01/s 远高于 2bp 价差对应的盈亏平衡波动率
+        assert!(strategy
+            .on_orderbook_update(&create_test_snapshot(49995.0, 50005.0))
+            .is_none());
+        assert!(strategy.is_volatility_halted());
+        assert!(strategy.is_halted());
+        // 与单日亏损熔断分开：不会锁定
+        assert!(!strategy.is_killed());
+
+        // 价差放宽后盈亏平衡波动率高于报价波动率，无需 reset_session 即恢复报价
+        strategy
+            .apply_config_update(&PartialASConfig {
+                min_spread_bps: Some(2000.0),
+                max_spread_bps: Some(5000.0),
+                ..PartialASConfig::default()
+            })
+            .unwrap();
+        assert!(strategy
+            .on_orderbook_update(&create_test_snapshot(49995.0, 50005.0))
+            .is_some());
+        assert!(!strategy.is_halted());
+
+        // 波动率足够低时正常报价
+        config.initial_volatility = 1e-6;
+        let mut strategy = AvellanedaStoikov::new(config);
+        assert!(strategy
+            .on_orderbook_update(&create_test_snapshot(49995.0, 50005.0))
+            .is_some());
+        assert!(!strategy.is_halted());
+    }
+
+    #[test]
+    fn test_break_even_halt_per_second_volatility() {
+        let mut config = create_test_config();
+        config.halt_above_break_even_volatility = true;
+        config.min_spread_bps = 10.0;
+        config.max_spread_bps = 10.0;
+        config.expected_holding_secs = 60.0;
+        // 每根1分钟K线 5e-4，每秒约 6.45e-5，低于盈亏平衡波动率 ≈ 1.6e-4
+        config.initial_volatility = 5e-4;
+
+        // 未声明采样间隔时无法换算到每秒，拒绝启用
+        assert!(config.validate().is_err());
+
+        config.bar_interval_secs = Some(60.0);
+        assert!(config.validate().is_ok());
+        let mut strategy = AvellanedaStoikov::new(config.clone());
+        assert!(strategy
+            .on_orderbook_update(&create_test_snapshot(49995.0, 50005.0))
+            .is_some());
+        assert!(strategy.quote_volatility() < strategy.break_even_volatility());
+        assert!(strategy.break_even_volatility() < 5e-4);
+        assert!(!strategy.is_halted());
+
+        // 同样的每根K线波动率按1秒K线理解时高于盈亏平衡波动率
+        config.bar_interval_secs = Some(1.0);
+        let mut strategy = AvellanedaStoikov::new(config);
+        assert!(strategy
+            .on_orderbook_update(&create_test_snapshot(49995.0, 50005.0))
+            .is_none());
+        assert!(strategy.is_volatility_halted());
+    }
+
+    #[test]
+    fn test_skew_time_decay() {
+        let mut config = create_test_config();
+        config.skew_time_decay = 0.01;
+
+        let snapshot_at = |secs: u64| {
+            let mut snapshot = create_test_snapshot(50000.0, 50010.0);
+            snapshot.timestamp_ns = UnixNanos::new(secs * 1_000_000_000);
+            snapshot
+        };
+
+        // 1秒时建仓，持有10分钟
+        let mut aged = AvellanedaStoikov::new(config.clone());
+        aged.on_orderbook_update(&snapshot_at(1));
+        aged.on_fill_with_price(OrderSide::Buy, 0.01, 50000.0);
+        aged.on_orderbook_update(&snapshot_at(601));
+
+        // 601秒时刚建仓
+        let mut fresh = AvellanedaStoikov::new(config);
+        fresh.on_orderbook_update(&snapshot_at(601));
+        fresh.on_fill_with_price(OrderSide::Buy, 0.01, 50000.0);
+
+        assert!((aged.inventory_age_secs() - 600.0).abs() < 1e-6);
+        assert_eq!(fresh.inventory_age_secs(), 0.0);
+        assert!((aged.skew_inventory() - 0.01 * 7.0).abs() < 1e-9);
+        assert!((fresh.skew_inventory() - 0.01).abs() < 1e-12);
+        assert!(aged.reservation_price() < fresh.reservation_price());
+
+        // 加仓按数量加权平均建仓时间
+        aged.on_fill_with_price(OrderSide::Buy, 0.01, 50000.0);
+        assert!((aged.inventory_age_secs() - 300.0).abs() < 1e-6);
+
+        // 持平后清零
+        aged.on_fill_with_price(OrderSide::Sell, 0.02, 50000.0);
+        assert_eq!(aged.inventory_age_secs(), 0.0);
+    }
+
+    #[test]
+    fn test_set_inventory() {
+        let mut config = create_test_config();
+        config.post_fill_cooldown_ns = 1_000_000_000;
+        config.skew_time_decay = 0.01;
+        let mut strategy = AvellanedaStoikov::new(config);
+
+        strategy.on_orderbook_update(&create_test_snapshot(50000.0, 50010.0));
+        strategy.set_inventory(0.02, 50005.0);
+        assert_eq!(strategy.get_stats().current_inventory, 0.02);
+        assert_eq!(strategy.get_stats().inventory_adjustments, 0);
+        // 建仓时间为最近一次行情时间，而不是 0
+        assert_eq!(strategy.inventory_age_secs(), 0.0);
+        assert!((strategy.skew_inventory() - 0.02).abs() < 1e-12);
+
+        // 不触发成交后冷却：双边照常报价
+        let quote = strategy
+            .on_orderbook_update(&create_test_snapshot(50000.0, 50010.0))
+            .unwrap();
+        assert!(quote.bid_size > 0.0 && quote.ask_size > 0.0);
+        assert!(quote.reservation_price < 50005.0);
+    }
+
+    #[test]
+    fn test_tight_book_policy() {
+        let mut config = create_test_config();
+        // 价格单位 1.0，模型价差被限制在 1bp（0.1）以内，窄于盘口
+        config.price_precision = 0;
+        config.min_spread_bps = 0.1;
+        config.max_spread_bps = 1.0;
+
+        let mut joining = AvellanedaStoikov::new(config.clone());
+        let quote = joining
+            .on_orderbook_update(&create_test_snapshot(1000.0, 1001.0))
+            .unwrap();
+        assert_eq!(quote.bid_price, 1000.0);
+        assert_eq!(quote.ask_price, 1001.0);
+        // 价差字段保持模型价差
+        assert_eq!(quote.spread, joining.optimal_spread());
+
+        config.tight_book_policy = TightBookPolicy::Skip;
+        let mut skipping = AvellanedaStoikov::new(config.clone());
+        assert!(skipping
+            .on_orderbook_update(&create_test_snapshot(1000.0, 1001.0))
+            .is_none());
+        // 两个价格单位宽时正常报价
+        assert!(skipping
+            .on_orderbook_update(&create_test_snapshot(1000.0, 1002.0))
+            .is_some());
+
+        // 常见价位上 f64 价差略大于一个价格单位（100.01 - 100.00 = 0.010000000000005116）
+        config.price_precision = 2;
+        for (bid, ask) in [(100.00, 100.01), (50000.00, 50000.01)] {
+            let mut skipping = AvellanedaStoikov::new(config.clone());
+            assert!(
+                skipping
+                    .on_orderbook_update(&create_test_snapshot(bid, ask))
+                    .is_none(),
+                "{} / {} 应视为一个价格单位",
+                bid,
+                ask
+            );
+
+            config.tight_book_policy = TightBookPolicy::Join;
+            let mut joining = AvellanedaStoikov::new(config.clone());
+            let quote = joining
+                .on_orderbook_update(&create_test_snapshot(bid, ask))
+                .unwrap();
+            assert!(quote.bid_price <= bid && quote.ask_price >= ask);
+            config.tight_book_policy = TightBookPolicy::Skip;
+        }
+    }
+
+    #[test]
+    fn test_tick_size_config() {
+        let mut config = create_test_config();
+        config.price_precision = 1;
+        assert!((config.tick_size() - 0.1).abs() < 1e-12);
+
+        // 价格单位 0.5：半个点宽的盘口即为一个价格单位
+        config.tick_size = Some(0.5);
+        config.min_spread_bps = 0.1;
+        config.max_spread_bps = 1.0;
+        config.tight_book_policy = TightBookPolicy::Skip;
+        assert!(config.validate().is_ok());
+        let mut strategy = AvellanedaStoikov::new(config.clone());
+        assert_eq!(strategy.tick_size(), 0.5);
+        assert!(strategy
+            .on_orderbook_update(&create_test_snapshot(1000.0, 1000.5))
+            .is_none());
+        assert!(strategy
+            .on_orderbook_update(&create_test_snapshot(1000.0, 1001.0))
+            .is_some());
+
+        // 非正或超出价格精度的价格单位无效
+        for tick in [0.0, -0.5, 0.25] {
+            config.tick_size = Some(tick);
+            assert!(config.validate().is_err(), "tick_size {}", tick);
+        }
+    }
+
+    #[test]
+    fn test_error_variants() {
+        let mut config = create_test_config();
+        config.risk_aversion = 0.0;
+        assert!(matches!(
+            config.validate(),
+            Err(StrategyError::InvalidConfig(_))
+        ));
+
+        let bad = |key: &str| (key == "AS_RISK_AVERSION").then(|| "abc".to_string());
+        assert!(matches!(
+            ASConfig::from_env_with("AS", bad),
+            Err(StrategyError::InvalidConfig(_))
+        ));
+        assert!(matches!(
+            parse_utc_time_of_day("noon"),
+            Err(StrategyError::InvalidConfig(_))
+        ));
+
+        let mut strategy = AvellanedaStoikov::new(create_test_config());
+        assert!(matches!(
+            strategy.restore_state(&[1, 2, 3]),
+            Err(StrategyError::Serde(_))
+        ));
+    }
+
+    #[test]
+    fn test_config_from_file() {
+        let dir = std::env::temp_dir();
+        let path = dir.join(format!("as-config-{}.json", std::process::id()));
+
+        let config = create_test_config();
+        std::fs::write(&path, serde_json::to_string(&config).unwrap()).unwrap();
+        let loaded = ASConfig::from_file(&path).unwrap();
+        assert_eq!(loaded.instrument_id, config.instrument_id);
+        assert_eq!(loaded.risk_aversion, config.risk_aversion);
+
+        std::fs::write(&path, "{ not json").unwrap();
+        assert!(matches!(
+            ASConfig::from_file(&path),
+            Err(StrategyError::Serde(_))
+        ));
+
+        let mut invalid = create_test_config();
+        invalid.max_inventory = -1.0;
+        std::fs::write(&path, serde_json::to_string(&invalid).unwrap()).unwrap();
+        assert!(matches!(
+            ASConfig::from_file(&path),
+            Err(StrategyError::InvalidConfig(_))
+        ));
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            ASConfig::from_file(&path),
+            Err(StrategyError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_quote_update_serde_roundtrip() {
+        let mut strategy = AvellanedaStoikov::new(create_test_config());
+        let quote = strategy
+            .on_orderbook_update(&create_test_snapshot(50000.0, 50010.0))
+            .unwrap();
+
+        let json = serde_json::to_string(&quote).unwrap();
+        let decoded: QuoteUpdate = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, quote);
+
+        let snapshot = create_test_snapshot(50000.0, 50010.0);
+        let decoded: OrderBookSnapshot =
+            serde_json::from_str(&serde_json::to_string(&snapshot).unwrap()).unwrap();
+        assert_eq!(decoded.best_ask, snapshot.best_ask);
+        assert_eq!(decoded.timestamp_ns, snapshot.timestamp_ns);
+    }
+
+    #[test]
+    fn test_price_improvement_policy() {
+        let mut config = create_test_config();
+        // 盘口 10 个价格单位宽，模型价差被限制在 1bp（约 5.0）以内
+        config.min_spread_bps = 0.1;
+        config.max_spread_bps = 1.0;
+        config.allow_price_improvement = false;
+        let snapshot = create_test_snapshot(50000.0, 50010.0);
+
+        let mut clamped = AvellanedaStoikov::new(config.clone());
+        let quote = clamped.on_orderbook_update(&snapshot).unwrap();
+        assert_eq!(quote.bid_price, 50000.0);
+        assert_eq!(quote.ask_price, 50010.0);
+        assert_eq!(quote.spread, clamped.optimal_spread());
+
+        config.allow_price_improvement = true;
+        let mut improving = AvellanedaStoikov::new(config);
+        let quote = improving.on_orderbook_update(&snapshot).unwrap();
+        assert!(quote.bid_price > 50000.0);
+        assert!(quote.ask_price < 50010.0);
+    }
+
+    #[test]
+    fn test_notional_skew() {
+        let skew_at = |max_notional: Option<f64>, mid: f64| {
+            let mut config = create_test_config();
+            config.max_notional = max_notional;
+            let mut strategy = AvellanedaStoikov::new(config);
+            strategy.mid_price.data = mid;
+            strategy.inventory.data = 0.05;
+            mid - strategy.reservation_price()
+        };
+
+        // 按数量偏斜：与价格水平无关
+        let low = skew_at(None, 50000.0);
+        let high = skew_at(None, 100000.0);
+        assert!(low > 0.0);
+        assert!((high - low).abs() < 1e-9);
+
+        // 按名义价值偏斜：价格翻倍偏斜翻倍，mid = max_notional / max_inventory 时与数量口径一致
+        let max_notional = 50000.0 * create_test_config().max_inventory;
+        let notional_low = skew_at(Some(max_notional), 50000.0);
+        let notional_high = skew_at(Some(max_notional), 100000.0);
+        assert!((notional_low - low).abs() < 1e-9);
+        assert!((notional_high / notional_low - 2.0).abs() < 1e-9);
+
+        let mut config = create_test_config();
+        config.max_notional = Some(0.0);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_post_fill_cooldown() {
+        let mut config = create_test_config();
+        config.post_fill_cooldown_ns = 500_000_000;
+        let mut strategy = AvellanedaStoikov::new(config);
+        let snapshot_at = |ns: u64| OrderBookSnapshot {
+            timestamp_ns: UnixNanos::new(ns),
+            ..create_test_snapshot(50000.0, 50010.0)
+        };
+
+        strategy.on_orderbook_update(&snapshot_at(1_000_000_000));
+        strategy.on_fill_with_price(OrderSide::Buy, 0.001, 49995.0);
+
+        // 冷却期内只报卖价
+        let quote = strategy
+            .on_orderbook_update(&snapshot_at(1_200_000_000))
+            .unwrap();
+        assert_eq!(quote.bid_size, 0.0);
+        assert!(quote.ask_size > 0.0);
+
+        // 冷却结束后恢复双边报价
+        let quote = strategy
+            .on_orderbook_update(&snapshot_at(1_500_000_000))
+            .unwrap();
+        assert!(quote.bid_size > 0.0);
+        assert!(quote.ask_size > 0.0);
+
+        // 冷却从成交时间起算，而不是成交前最近一次行情的时间
+        strategy.on_fill_at(OrderSide::Sell, 0.001, 50010.0, 1_800_000_000);
+        let quote = strategy
+            .on_orderbook_update(&snapshot_at(2_100_000_000))
+            .unwrap();
+        assert!(quote.bid_size > 0.0);
+        assert_eq!(quote.ask_size, 0.0);
+        let quote = strategy
+            .on_orderbook_update(&snapshot_at(2_300_000_000))
+            .unwrap();
+        assert!(quote.ask_size > 0.0);
+    }
+
+    #[test]
+    fn test_reference_price_sources() {
+        let book = OrderBookSnapshotL2 {
+            bids: vec![(99.0, 3.0), (98.0, 1.0)],
+            asks: vec![(101.0, 1.0), (102.0, 5.0)],
+            timestamp_ns: UnixNanos::new(1_000_000_000),
+        };
+        let top = book.top_of_book();
+
+        let reference_for = |source: ReferencePrice, trade: Option<f64>| {
+            let mut config = create_test_config();
+            config.reference_price = source;
+            let mut strategy = AvellanedaStoikov::new(config);
+            if let Some(price) = trade {
+                strategy.on_trade(price);
+            }
+            strategy.on_orderbook_update_l2(&book).unwrap();
+            strategy.get_stats().mid_price
+        };
+
+        assert_eq!(reference_for(ReferencePrice::Mid, Some(100.5)), 100.0);
+        // 买盘更厚，微观价格偏向卖价
+        assert_eq!(
+            reference_for(ReferencePrice::Microprice, None),
+            microprice(&top)
+        );
+        assert_eq!(microprice(&top), 100.5);
+        assert_eq!(reference_for(ReferencePrice::LastTrade, Some(100.4)), 100.4);
+        assert_eq!(
+            reference_for(ReferencePrice::DepthWeighted { levels: 2 }, None),
+            book.depth_weighted_mid(2).unwrap()
+        );
+
+        // 缺少数据时回退到中间价
+        assert_eq!(reference_for(ReferencePrice::LastTrade, None), 100.0);
+        let mut config = create_test_config();
+        config.reference_price = ReferencePrice::DepthWeighted { levels: 2 };
+        let mut strategy = AvellanedaStoikov::new(config);
+        strategy.on_orderbook_update(&top).unwrap();
+        assert_eq!(strategy.get_stats().mid_price, 100.0);
+
+        assert_eq!(
+            "depth_weighted:3".parse::<ReferencePrice>(),
+            Ok(ReferencePrice::DepthWeighted { levels: 3 })
+        );
+        assert_eq!("Microprice".parse(), Ok(ReferencePrice::Microprice));
+        assert!("vwap".parse::<ReferencePrice>().is_err());
+    }
+
+    #[test]
+    fn test_injected_clock_throttle_boundary() {
+        let mut config = create_test_config();
+        config.min_quote_interval_ns = 100_000_000;
+        let clock = Arc::new(ManualClock::new(5_000_000_000));
+        let mut strategy = AvellanedaStoikov::new(config).with_clock(clock.clone());
+        // 快照时间戳固定不变，只有注入的时钟在推进
+        let snapshot = create_test_snapshot(50000.0, 50010.0);
+
+        assert!(strategy.on_orderbook_update(&snapshot).is_some());
+        clock.advance(99_999_999);
+        assert!(strategy.on_orderbook_update(&snapshot).is_none());
+        clock.advance(1);
+        assert!(strategy.on_orderbook_update(&snapshot).is_some());
+        assert!(strategy.on_orderbook_update(&snapshot).is_none());
+        assert_eq!(strategy.session_elapsed_secs(clock.now_ns()), 0.1);
+
+        // 未注入时钟时以快照时间戳计时
+        let mut config = create_test_config();
+        config.min_quote_interval_ns = 100_000_000;
+        let mut strategy = AvellanedaStoikov::new(config);
+        assert!(strategy.on_orderbook_update(&snapshot).is_some());
+        assert!(strategy.on_orderbook_update(&snapshot).is_none());
+    }
+
+    #[test]
+    fn test_consolidated_reference_price() {
+        let home = create_test_snapshot(50000.0, 50010.0);
+        let mut consolidated = ConsolidatedBook::new();
+        consolidated.update("BINANCE", home);
+        consolidated.update("OKX", create_test_snapshot(50004.0, 50012.0));
+
+        let mut strategy = AvellanedaStoikov::new(create_test_config());
+        strategy
+            .on_orderbook_update_consolidated(&home, &consolidated)
+            .unwrap();
+        assert_eq!(strategy.get_stats().mid_price, 50007.0);
+
+        // 合并盘口为空时使用本交易所中间价
+        let mut strategy = AvellanedaStoikov::new(create_test_config());
+        strategy
+            .on_orderbook_update_consolidated(&home, &ConsolidatedBook::new())
+            .unwrap();
+        assert_eq!(strategy.get_stats().mid_price, 50005.0);
+    }
+
+    #[test]
+    fn test_fill_probability() {
+        let strategy = AvellanedaStoikov::new(create_test_config());
+
+        let near = strategy.fill_probability(1.0, 0.01);
+        let far = strategy.fill_probability(3.0, 0.01);
+        assert!(near > far);
+        assert!(strategy.fill_probability(3.0, 0.1) > far);
+
+        // 与公式一致：A = 100, κ = 1.5
+        let expected = 1.0 - (-100.0 * (-1.5f64 * 3.0).exp() * 0.01).exp();
+        assert!((far - expected).abs() < 1e-12);
+
+        assert_eq!(strategy.fill_probability(1.0, 0.0), 0.0);
+        for (distance, horizon) in [(0.0, 1e9), (1e9, 1.0), (-5.0, 1.0)] {
+            let probability = strategy.fill_probability(distance, horizon);
+            assert!((0.0..=1.0).contains(&probability));
+        }
+    }
+
+    #[test]
+    fn test_optimal_spread_small_gamma_limit() {
+        let (sigma, t, kappa) = (0.02, 300.0, 1.5);
+
+        // γ = 0 时为解析极限 2/κ
+        assert_eq!(optimal_spread(0.0, sigma, t, kappa), 2.0 / kappa);
+
+        let tiny = optimal_spread(1e-9, sigma, t, kappa);
+        assert!(tiny.is_finite());
+        assert!((tiny - 2.0 / kappa).abs() < 1e-9);
+
+        // 阈值两侧连续
+        let below = optimal_spread(SMALL_GAMMA_RATIO * kappa * 0.999, sigma, t, kappa);
+        let above = optimal_spread(SMALL_GAMMA_RATIO * kappa * 1.001, sigma, t, kappa);
+        assert!((below - above).abs() < 1e-9);
+
+        // 正常 γ 下与原公式一致
+        let gamma = 0.1;
+        let direct = gamma * sigma * sigma * t + (2.0 / gamma) * (1.0 + gamma / kappa).ln();
+        assert!((optimal_spread(gamma, sigma, t, kappa) - direct).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_ladder_sized_by_fill_probability() {
+        let mut config = create_test_config();
+        config.price_sensitivity = 0.1;
+        config.ladder_levels = 4;
+        config.ladder_step_bps = 1.0;
+        config.ladder_sizing = LadderSizing::InverseFillProbability {
+            horizon_secs: 0.001,
+        };
+        config.lot_size = 0.0001;
+        config.base_order_size = 0.03;
+        let mut strategy = AvellanedaStoikov::new(config);
+        let top = strategy
+            .on_orderbook_update(&create_test_snapshot(50000.0, 50010.0))
+            .unwrap();
+
+        let ladder = strategy.quote_ladder(&top);
+        // 越远的档位成交概率越低、数量越大
+        for pair in ladder.windows(2) {
+            assert!(pair[1].bid_size >= pair[0].bid_size);
+            assert!(pair[1].ask_size >= pair[0].ask_size);
+        }
+        assert!(ladder[3].bid_size > ladder[0].bid_size);
+        assert!(ladder[3].ask_size > ladder[0].ask_size);
+
+        // 每侧总数量 0.03 × 4 被限制在 max_position_size 以内
+        let total: f64 = ladder.iter().map(|level| level.bid_size).sum();
+        assert!(total <= 0.1 + 1e-12);
+        assert!(total > 0.1 - 0.0001 - 1e-12);
+
+        assert_eq!(
+            LadderSizing::InverseFillProbability { horizon_secs: 1.0 }
+                .weights_by_fill_probability(&[0.5, 0.25, 0.0]),
+            vec![2.0, 4.0, 1e12]
+        );
+    }
+
+    #[test]
+    fn test_queue_aware_improve_or_join() {
+        let mut config = create_test_config();
+        // 模型价差约 5.0，窄于 10.0 宽的盘口；排队感知时报价先加入最优价
+        config.min_spread_bps = 0.1;
+        config.max_spread_bps = 1.0;
+        config.queue_aware = true;
+        config.max_queue_volume = 5.0;
+        let mut strategy = AvellanedaStoikov::new(config.clone());
+        let tick = strategy.tick_size();
+
+        let snapshot = |bid_volume: f64, ask_volume: f64| OrderBookSnapshot {
+            bid_volume,
+            ask_volume,
+            ..create_test_snapshot(50000.0, 50010.0)
+        };
+
+        // 买一排队量大：改善一个价格单位；卖一排队量小：加入
+        let quote = strategy.on_orderbook_update(&snapshot(20.0, 1.0)).unwrap();
+        assert!((quote.bid_price - (50000.0 + tick)).abs() < 1e-9);
+        assert_eq!(quote.ask_price, 50010.0);
+
+        let quote = strategy.on_orderbook_update(&snapshot(1.0, 20.0)).unwrap();
+        assert_eq!(quote.bid_price, 50000.0);
+        assert!((quote.ask_price - (50010.0 - tick)).abs() < 1e-9);
+
+        // 一个价格单位宽的盘口无法改善
+        let quote = strategy
+            .on_orderbook_update(&OrderBookSnapshot {
+                bid_volume: 20.0,
+                ask_volume: 20.0,
+                ..create_test_snapshot(50000.0, 50000.0 + tick)
+            })
+            .unwrap();
+        assert!(quote.bid_price <= 50000.0);
+        assert!(quote.ask_price >= 50000.0 + tick);
+
+        // 双边手续费约 9.991，改善一个价格单位后的价差 9.99 不足以覆盖，只加入最优价
+        let mut fee_floor = AvellanedaStoikov::new(ASConfig {
+            maker_fee_bps: 0.999,
+            ..config.clone()
+        });
+        let quote = fee_floor
+            .on_orderbook_update(&snapshot(20.0, 20.0))
+            .unwrap();
+        assert_eq!(quote.bid_price, 50000.0);
+        assert_eq!(quote.ask_price, 50010.0);
+        assert_eq!(quote.spread, fee_floor.optimal_spread());
+
+        // 不允许改善盘口时，排队量再大也只挂在最优价
+        let mut no_improvement = AvellanedaStoikov::new(ASConfig {
+            allow_price_improvement: false,
+            ..config
+        });
+        let quote = no_improvement
+            .on_orderbook_update(&snapshot(20.0, 20.0))
+            .unwrap();
+        assert_eq!(quote.bid_price, 50000.0);
+        assert_eq!(quote.ask_price, 50010.0);
+    }
+
+    #[test]
+    fn test_adverse_selection_widens_spread() {
+        let mut config = create_test_config();
+        config.adverse_selection = Some(AdverseSelectionConfig {
+            window: 3,
+            markout_ns: 1_000_000,
+            threshold: 0.6,
+            spread_multiplier: 2.0,
+            cooldown_ns: 10_000_000,
+        });
+        let mut strategy = AvellanedaStoikov::new(config.clone());
+        let mut baseline = AvellanedaStoikov::new(config);
+
+        let mut snapshot = create_test_snapshot(100.0, 100.1);
+        let mut ts = 1_000_000_000;
+        strategy.on_orderbook_update(&snapshot);
+        baseline.on_orderbook_update(&snapshot);
+
+        // 有毒订单流：每次买入后价格立即下跌
+        for _ in 0..3 {
+            strategy.on_fill_with_price(OrderSide::Buy, 0.001, snapshot.best_bid);
+            strategy.on_fill_with_price(OrderSide::Sell, 0.001, snapshot.best_bid);
+            strategy.on_fill_with_price(OrderSide::Buy, 0.001, snapshot.best_bid);
+            snapshot.best_bid -= 0.5;
+            snapshot.best_ask -= 0.5;
+            ts += 1_000_000;
+            snapshot.timestamp_ns = UnixNanos::new(ts);
+            strategy.on_orderbook_update(&snapshot);
+        }
+        assert!(strategy.adverse_selection_score() >= 0.6);
+
+        // 同一盘口下对比：库存与波动率一致，仅价差倍数不同
+        strategy.inventory.data = 0.0;
+        let widened = strategy.calculate_quotes(ts);
+        baseline.mid_price.data = strategy.mid_price.data;
+        baseline.volatility.data = strategy.volatility.data;
+        let normal = baseline.calculate_quotes(ts);
+        assert!((widened.spread - 2.0 * normal.spread).abs() < 1e-9);
+
+        // 冷却结束后恢复原价差
+        let after = strategy.calculate_quotes(ts + 10_000_000);
+        assert!((after.spread - normal.spread).abs() < 1e-9);
+
+        strategy.reset();
+        assert_eq!(strategy.adverse_selection_score(), 0.0);
+
+        let mut invalid = create_test_config();
+        invalid.adverse_selection = Some(AdverseSelectionConfig {
+            window: 3,
+            markout_ns: 0,
+            threshold: 0.5,
+            spread_multiplier: 0.5,
+            cooldown_ns: 0,
+        });
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_try_new_validates_config() {
+        assert!(AvellanedaStoikov::try_new(create_test_config()).is_ok());
+
+        let mut config = create_test_config();
+        config.risk_aversion = 0.0;
+        assert!(matches!(
+            AvellanedaStoikov::try_new(config),
+            Err(StrategyError::InvalidConfig(_))
+        ));
+
+        let invalid_configs = [
+            ASConfig {
+                flatten_fraction: 1.5,
+                ..create_test_config()
+            },
+            ASConfig {
+                flatten_fraction: -0.1,
+                ..create_test_config()
+            },
+            ASConfig {
+                lot_size: -0.001,
+                ..create_test_config()
+            },
+            ASConfig {
+                min_order_size: -0.001,
+                ..create_test_config()
+            },
+            ASConfig {
+                hedge_threshold: -1.0,
+                ..create_test_config()
+            },
+            ASConfig {
+                bar_interval_secs: Some(0.0),
+                ..create_test_config()
+            },
+            ASConfig {
+                bar_interval_secs: Some(f64::NAN),
+                ..create_test_config()
+            },
+        ];
+        for config in invalid_configs {
+            assert!(
+                matches!(config.validate(), Err(StrategyError::InvalidConfig(_))),
+                "{:?}",
+                config
+            );
+        }
+    }
+
+    #[test]
+    fn test_reconfigure_rejects_contract_type_change() {
+        let mut strategy = AvellanedaStoikov::new(create_test_config());
+        strategy.on_fill_with_price(OrderSide::Buy, 0.01, 50000.0);
+
+        let mut config = create_test_config();
+        config.inverse = true;
+        assert!(matches!(
+            strategy.reconfigure(config),
+            Err(StrategyError::InvalidConfig(_))
+        ));
+        assert!(!strategy.config().inverse);
+        assert!(!strategy.pnl().is_inverse());
+    }
+
+    #[test]
+    fn test_volatility_recorder() {
+        let mut config = create_test_config();
+        config.record_volatility = true;
+        config.vol_blend_weight = 0.5;
+        let mut strategy = AvellanedaStoikov::new(config);
+
+        for i in 0..5 {
+            let close = 100.0 + i as f64;
+            strategy.on_bar(&Bar {
+                open: close - 0.5,
+                high: close + 1.0,
+                low: close - 1.0,
+                close,
+                volume: 10.0,
+                timestamp_ns: (i + 1) * 60_000_000_000,
+            });
+        }
+        strategy.on_trade(104.5);
+        strategy.on_trade(104.0);
+
+        let recorder = strategy.volatility_recorder().unwrap();
+        assert_eq!(recorder.len(), 7);
+        assert!(recorder.samples().all(|(_, vol)| vol > 0.0));
+        assert_eq!(recorder.samples().nth(4).unwrap().0, 300_000_000_000);
+
+        // 成交后记录的是混合成交波动率后的报价波动率
+        let (_, last) = recorder.samples().last().unwrap();
+        assert_eq!(last, strategy.quote_volatility());
+        assert_ne!(last, strategy.volatility.data);
+
+        // 默认关闭
+        assert!(AvellanedaStoikov::new(create_test_config())
+            .volatility_recorder()
+            .is_none());
+    }
+
+    #[test]
+    fn test_volatility_blend() {
+        let mut config = create_test_config();
+        config.vol_blend_weight = 0.5;
+        let mut strategy = AvellanedaStoikov::new(config);
+
+        // 只有成交数据时完全使用成交波动率
+        for price in [100.0, 101.0, 99.5, 100.8, 99.0] {
+            strategy.on_trade(price);
+        }
+        let trade_vol = strategy.trade_volatility().unwrap();
+        assert!(trade_vol > 0.0);
+        assert_eq!(strategy.quote_volatility(), trade_vol);
+
+        // 第一根K线内有4笔收益率：K线周期成交波动率为逐笔的 √4 倍
+        for i in 0..5 {
+            if i == 1 {
+                let per_bar = strategy.trade_volatility_per_bar().unwrap();
+                assert!((per_bar - 2.0 * trade_vol).abs() < 1e-12);
+            }
+            let close = 100.0 + (i % 2) as f64 * 0.1;
+            strategy.on_bar(&Bar {
+                open: close,
+                high: close + 0.05,
+                low: close - 0.05,
+                close,
+                volume: 1.0,
+                timestamp_ns: i * 60_000_000_000,
+            });
+        }
+        let bar_vol = strategy.volatility.data;
+        let trade_bar_vol = strategy.trade_volatility_per_bar().unwrap();
+        assert!(bar_vol > 0.0 && (bar_vol - trade_bar_vol).abs() > 1e-6);
+        // 之后的K线没有成交，每根K线成交笔数按 EWMA 下降
+        assert!(trade_bar_vol < 2.0 * trade_vol);
+
+        let blended = strategy.quote_volatility();
+        assert!(blended > bar_vol.min(trade_bar_vol) && blended < bar_vol.max(trade_bar_vol));
+        assert!((blended - 0.5 * (trade_bar_vol + bar_vol)).abs() < 1e-12);
+
+        // 权重为0时保持只用K线波动率
+        let mut bars_only = AvellanedaStoikov::new(create_test_config());
+        bars_only.on_trade(100.0);
+        bars_only.on_trade(101.0);
+        assert_eq!(bars_only.quote_volatility(), bars_only.volatility.data);
+    }
+
+    #[test]
+    fn test_per_side_inventory_limits() {
+        let mut config = create_test_config();
+        config.max_long_inventory = Some(0.08);
+        config.max_short_inventory = Some(0.04);
+        let mut strategy = AvellanedaStoikov::new(config);
+        strategy.on_orderbook_update(&create_test_snapshot(50000.0, 50010.0));
+
+        // 持续买入：超过空头上限对应的库存量后仍继续报价
+        let mut long_fills = 0;
+        while strategy.calculate_quotes(1_000_000_000).bid_size > 0.0 {
+            strategy.on_fill_with_price(OrderSide::Buy, 0.001, 50000.0);
+            long_fills += 1;
+        }
+        // 多头达到上限：不再买入，减仓的卖单仍按原数量报价
+        let at_limit = strategy.calculate_quotes(1_000_000_000);
+        assert_eq!(at_limit.bid_size, 0.0);
+        assert_eq!(at_limit.ask_size, 0.001);
+
+        strategy.reset();
+        strategy.on_orderbook_update(&create_test_snapshot(50000.0, 50010.0));
+        let mut short_fills = 0;
+        while strategy.calculate_quotes(1_000_000_000).ask_size > 0.0 {
+            strategy.on_fill_with_price(OrderSide::Sell, 0.001, 50010.0);
+            short_fills += 1;
+        }
+
+        assert!(long_fills > short_fills);
+        assert!(strategy.inventory.data.abs() <= 0.04 + 1e-9);
+        assert_eq!(strategy.calculate_quotes(1_000_000_000).bid_size, 0.001);
+        assert!(long_fills as f64 * 0.001 > 0.04);
+
+        let mut invalid = create_test_config();
+        invalid.max_short_inventory = Some(0.0);
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_reference_price_guard() {
+        let mut strategy = AvellanedaStoikov::new(create_test_config());
+        let snapshot = create_test_snapshot(50000.0, 50010.0);
+        let unguarded = strategy.on_orderbook_update(&snapshot).unwrap();
+
+        // 保护价格低于模型买价：买价被压到保护价格，卖价不受影响
+        let guard = unguarded.bid_price - 5.0;
+        strategy.set_reference_price_guard(Some(guard));
+        let quote = strategy.on_orderbook_update(&snapshot).unwrap();
+        assert_eq!(quote.bid_price, guard);
+        assert_eq!(quote.ask_price, unguarded.ask_price);
+
+        // 保护价格高于模型卖价：卖价被抬到保护价格
+        let guard = unguarded.ask_price + 5.0;
+        strategy.set_reference_price_guard(Some(guard));
+        let quote = strategy.on_orderbook_update(&snapshot).unwrap();
+        assert_eq!(quote.ask_price, guard);
+        assert_eq!(quote.bid_price, unguarded.bid_price);
+        assert!(quote.bid_price <= guard && quote.ask_price >= guard);
+
+        // 保护价格在报价之间时不改变报价
+        strategy.set_reference_price_guard(Some(50005.0));
+        assert_eq!(strategy.on_orderbook_update(&snapshot), Some(unguarded));
+
+        strategy.set_reference_price_guard(Some(f64::NAN));
+        assert_eq!(strategy.reference_price_guard(), None);
+    }
+
+    #[test]
+    fn test_inventory_half_life() {
+        let half_life = |penalty_factor: f64| {
+            let mut config = create_test_config();
+            config.inventory_penalty_factor = penalty_factor;
+            let mut strategy = AvellanedaStoikov::new(config);
+            strategy.on_orderbook_update(&create_test_snapshot(100.0, 100.1));
+            assert_eq!(strategy.inventory_half_life(), f64::INFINITY);
+
+            strategy.inventory.data = 0.02;
+            let long = strategy.inventory_half_life();
+            strategy.inventory.data = -0.02;
+            let short = strategy.inventory_half_life();
+            assert!((long - short).abs() < 1e-9 * long);
+            long
+        };
+
+        let weak = half_life(2.0);
+        let strong = half_life(20.0);
+        assert!(weak.is_finite() && weak > 0.0);
+        assert!(strong < weak);
+    }
+
+    #[test]
+    fn test_inverse_contract_pnl() {
+        let mut config = create_test_config();
+        config.inverse = true;
+        config.max_inventory = 5000.0;
+        config.max_position_size = 10000.0;
+        let mut strategy = AvellanedaStoikov::new(config);
+
+        strategy.on_orderbook_update(&create_test_snapshot(39990.0, 40010.0));
+        assert!((strategy.notional(1000.0) - 1000.0 / 40000.0).abs() < 1e-15);
+        strategy.on_fill_with_price(OrderSide::Buy, 1000.0, 40000.0);
+
+        strategy.on_orderbook_update(&create_test_snapshot(49990.0, 50010.0));
+        strategy.on_fill_with_price(OrderSide::Sell, 1000.0, 50000.0);
+
+        // 反向合约盈亏解析式：张数 × (1/开仓价 - 1/平仓价)
+        let expected = 1000.0 * (1.0 / 40000.0 - 1.0 / 50000.0);
+        assert!((strategy.pnl().realized_pnl() - expected).abs() < 1e-15);
+        assert!(strategy.pnl().is_inverse());
+
+        strategy.reset();
+        assert!(strategy.pnl().is_inverse());
+        assert!(!AvellanedaStoikov::new(create_test_config())
+            .pnl()
+            .is_inverse());
+    }
+
+    #[test]
+    fn test_quote_listener() {
+        let mut strategy = AvellanedaStoikov::new(create_test_config());
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&received);
+        strategy.set_on_quote(move |quote| sink.lock().unwrap().push(*quote));
+
+        let quote = strategy
+            .on_orderbook_update(&create_test_snapshot(50000.0, 50010.0))
+            .unwrap();
+        // 直接计算模型报价不触发监听器
+        strategy.calculate_quotes(1_000_000_000);
+        assert_eq!(*received.lock().unwrap(), vec![quote]);
+
+        // 监听器收到限制到盘口后的最终报价，而不是模型报价
+        let mut clamped = AvellanedaStoikov::new(ASConfig {
+            allow_price_improvement: false,
+            max_quote_deviation_bps: 50.0,
+            ..create_test_config()
+        });
+        let sink = Arc::clone(&received);
+        clamped.set_on_quote(move |quote| sink.lock().unwrap().push(*quote));
+        let wide = create_test_snapshot(49900.0, 50100.0);
+        let model = AvellanedaStoikov::new(create_test_config())
+            .on_orderbook_update(&wide)
+            .unwrap();
+        let quote = clamped.on_orderbook_update(&wide).unwrap();
+        assert_eq!(quote.bid_price, 49900.0);
+        assert_ne!(quote.bid_price, model.bid_price);
+        assert_eq!(received.lock().unwrap().last(), Some(&quote));
+
+        // 价格带拒绝报价时不触发监听器
+        clamped.volatility.data = 5.0;
+        let mut snapshot = create_test_snapshot(50000.0, 50010.0);
+        snapshot.timestamp_ns = UnixNanos::new(2_000_000_000);
+        assert!(clamped.on_orderbook_update(&snapshot).is_none());
+        assert_eq!(received.lock().unwrap().len(), 2);
+
+        strategy.clear_quote_listener();
+        strategy
+            .on_orderbook_update(&create_test_snapshot(50000.0, 50010.0))
+            .unwrap();
+        assert_eq!(received.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_quote_distances_under_skew() {
+        let mut strategy = AvellanedaStoikov::new(create_test_config());
+        let snapshot = create_test_snapshot(50000.0, 50010.0);
+        let flat = strategy.on_orderbook_update(&snapshot).unwrap();
+        assert!((flat.bid_distance_bps - flat.ask_distance_bps).abs() < 1e-9);
+
+        let mid = strategy.mid_price.data;
+        assert!((flat.bid_distance_bps - (mid - flat.bid_price) / mid * 1e4).abs() < 1e-12);
+        assert!((flat.ask_distance_bps - (flat.ask_price - mid) / mid * 1e4).abs() < 1e-12);
+
+        // 多头库存：报价下移，买价离中间价更远、卖价更近
+        strategy.inventory.data = 0.03;
+        let long = strategy.on_orderbook_update(&snapshot).unwrap();
+        assert!(long.bid_distance_bps > flat.bid_distance_bps);
+        assert!(long.ask_distance_bps < flat.ask_distance_bps);
+
+        // 空头库存：方向相反
+        strategy.inventory.data = -0.03;
+        let short = strategy.on_orderbook_update(&snapshot).unwrap();
+        assert!(short.bid_distance_bps < short.ask_distance_bps);
+    }
+
+    #[test]
+    fn test_quote_deviation_band() {
+        let mut config = create_test_config();
+        config.max_quote_deviation_bps = 50.0;
+        let mut strategy = AvellanedaStoikov::new(config);
+        let snapshot = create_test_snapshot(50000.0, 50010.0);
+        assert!(strategy.on_orderbook_update(&snapshot).is_some());
+
+        // 极端波动率使价差撑到上限，两侧距中间价约100基点，超出价格带
+        strategy.volatility.data = 5.0;
+        assert!(strategy.on_orderbook_update(&snapshot).is_none());
+
+        strategy.config.max_quote_deviation_bps = 0.0;
+        let quote = strategy.on_orderbook_update(&snapshot).unwrap();
+        assert!(quote.bid_distance_bps > 50.0 && quote.ask_distance_bps > 50.0);
+
+        let mut config = create_test_config();
+        config.max_quote_deviation_bps = -1.0;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_fair_value_skew_steadies_reservation() {
+        // 中间价在 50005 上下 ±20 来回跳动
+        let reservation_range = |config: ASConfig| {
+            let mut strategy = AvellanedaStoikov::new(config);
+            strategy.inventory.data = 0.02;
+            let mut prices = Vec::new();
+            for i in 0..40 {
+                let offset = if i % 2 == 0 { 20.0 } else { -20.0 };
+                let snapshot = create_test_snapshot(50000.0 + offset, 50010.0 + offset);
+                let quote = strategy.on_orderbook_update(&snapshot).unwrap();
+                // 中间价始终跟随当前盘口
+                assert_eq!(strategy.get_stats().mid_price, 50005.0 + offset);
+                if i >= 20 {
+                    prices.push(quote.reservation_price);
+                }
+            }
+            let max = prices.iter().cloned().fold(f64::MIN, f64::max);
+            let min = prices.iter().cloned().fold(f64::MAX, f64::min);
+            max - min
+        };
+
+        let raw = reservation_range(create_test_config());
+        let mut config = create_test_config();
+        config.mid_smoothing_alpha = 0.1;
+        config.skew_around_fair_value = true;
+        let steady = reservation_range(config);
+
+        assert!((raw - 40.0).abs() < 1e-6);
+        assert!(steady < raw * 0.2, "steady = {steady}, raw = {raw}");
+    }
+
+    #[test]
+    fn test_book_outcome_distinguishes_throttle_from_suppression() {
+        let mut config = create_test_config();
+        config.min_quote_interval_ns = 100_000_000;
+        config.tight_book_policy = TightBookPolicy::Skip;
+        config.price_precision = 0;
+        config.max_quote_deviation_bps = 50.0;
+        let clock = Arc::new(ManualClock::new(5_000_000_000));
+        let mut strategy = AvellanedaStoikov::new(config).with_clock(clock.clone());
+        let snapshot = create_test_snapshot(50000.0, 50010.0);
+
+        assert!(matches!(
+            strategy.on_orderbook_update_outcome(&snapshot),
+            BookOutcome::Quote(_)
+        ));
+        // 节流期间保留挂单
+        assert_eq!(
+            strategy.on_orderbook_update_outcome(&snapshot),
+            BookOutcome::Throttled
+        );
+
+        // 以下情况都要求撤销挂单
+        clock.advance(100_000_000);
+        assert_eq!(
+            strategy.on_orderbook_update_outcome(&create_test_snapshot(50010.0, 50000.0)),
+            BookOutcome::Suppressed(SuppressReason::InvalidBook)
+        );
+        assert_eq!(
+            strategy.on_orderbook_update_outcome(&create_test_snapshot(50000.0, 50001.0)),
+            BookOutcome::Suppressed(SuppressReason::TightBook)
+        );
+        strategy.volatility.data = 5.0;
+        assert_eq!(
+            strategy.on_orderbook_update_outcome(&snapshot),
+            BookOutcome::Suppressed(SuppressReason::PriceBand)
+        );
+        assert!(strategy.on_orderbook_update(&snapshot).is_none());
+    }
+
+    #[test]
+    fn test_no_order_side_fill_ignored() {
+        let mut strategy = AvellanedaStoikov::new(create_test_config());
+        strategy.on_orderbook_update(&create_test_snapshot(50000.0, 50010.0));
+        strategy.on_fill_with_price(OrderSide::NoOrderSide, 0.01, 50000.0);
+
+        let stats = strategy.get_stats();
+        assert_eq!(stats.current_inventory, 0.0);
+        assert_eq!(stats.inventory_adjustments, 0);
+        assert_eq!(strategy.pnl().realized_pnl(), 0.0);
     }
 }
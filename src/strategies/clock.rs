@@ -0,0 +1,42 @@
+//! 时钟抽象 - 让依赖时间的逻辑（节流、时段、冷却）在测试和回放中可控
+//!
+//! 策略默认以订单簿快照时间戳作为当前时间；注入时钟后改用时钟时间。
+
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// 时间来源
+pub trait Clock: Debug + Send + Sync {
+    /// 当前时间（Unix 纳秒）
+    fn now_ns(&self) -> u64;
+}
+
+/// 手动推进的时钟
+#[derive(Debug, Default)]
+pub struct ManualClock {
+    now_ns: AtomicU64,
+}
+
+impl ManualClock {
+    pub fn new(start_ns: u64) -> Self {
+        Self {
+            now_ns: AtomicU64::new(start_ns),
+        }
+    }
+
+    /// 设置当前时间
+    pub fn set(&self, now_ns: u64) {
+        self.now_ns.store(now_ns, Ordering::Relaxed);
+    }
+
+    /// 向前推进并返回推进后的时间
+    pub fn advance(&self, ns: u64) -> u64 {
+        self.now_ns.fetch_add(ns, Ordering::Relaxed) + ns
+    }
+}
+
+impl Clock for ManualClock {
+    fn now_ns(&self) -> u64 {
+        self.now_ns.load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,106 @@
+//! 执行连接监控 - 执行客户端断线时撤销全部挂单
+//!
+//! 与看门狗一样只依赖外部传入的连接状态，便于用模拟客户端测试。
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// 执行客户端连接状态
+pub trait ConnectionStatus {
+    fn is_connected(&self) -> bool;
+}
+
+/// 由外部（例如客户端回调）更新的连接标志
+impl ConnectionStatus for AtomicBool {
+    fn is_connected(&self) -> bool {
+        self.load(Ordering::Acquire)
+    }
+}
+
+/// 连接监控动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionAction {
+    /// 撤销策略的所有挂单
+    CancelAll,
+}
+
+/// 断线监控：检测 已连接 -> 断开 的状态变化
+#[derive(Debug, Clone)]
+pub struct DisconnectMonitor {
+    cancel_on_disconnect: bool,
+    connected: bool,
+}
+
+impl DisconnectMonitor {
+    pub fn new(cancel_on_disconnect: bool) -> Self {
+        Self {
+            cancel_on_disconnect,
+            connected: false,
+        }
+    }
+
+    /// 轮询连接状态，刚断开时返回撤单动作（每次断线只触发一次）
+    pub fn poll<C: ConnectionStatus + ?Sized>(&mut self, client: &C) -> Option<ConnectionAction> {
+        let connected = client.is_connected();
+        let was_connected = std::mem::replace(&mut self.connected, connected);
+
+        (was_connected && !connected && self.cancel_on_disconnect)
+            .then_some(ConnectionAction::CancelAll)
+    }
+
+    /// 最近一次轮询时是否已连接
+    pub fn is_connected(&self) -> bool {
+        self.connected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    /// 模拟执行客户端
+    struct MockClient {
+        connected: Cell<bool>,
+    }
+
+    impl ConnectionStatus for MockClient {
+        fn is_connected(&self) -> bool {
+            self.connected.get()
+        }
+    }
+
+    #[test]
+    fn test_disconnect_triggers_cancel_once() {
+        let client = MockClient {
+            connected: Cell::new(true),
+        };
+        let mut monitor = DisconnectMonitor::new(true);
+
+        assert_eq!(monitor.poll(&client), None);
+        assert!(monitor.is_connected());
+
+        client.connected.set(false);
+        assert_eq!(monitor.poll(&client), Some(ConnectionAction::CancelAll));
+        // 持续断开不重复撤单
+        assert_eq!(monitor.poll(&client), None);
+
+        // 重连后再次断开会再次触发
+        client.connected.set(true);
+        assert_eq!(monitor.poll(&client), None);
+        client.connected.set(false);
+        assert_eq!(monitor.poll(&client), Some(ConnectionAction::CancelAll));
+    }
+
+    #[test]
+    fn test_disabled_and_never_connected() {
+        let flag = AtomicBool::new(true);
+        let mut monitor = DisconnectMonitor::new(false);
+        monitor.poll(&flag);
+        flag.store(false, Ordering::Release);
+        assert_eq!(monitor.poll(&flag), None);
+
+        // 从未连接过时不触发
+        let mut monitor = DisconnectMonitor::new(true);
+        assert_eq!(monitor.poll(&AtomicBool::new(false)), None);
+    }
+}
@@ -0,0 +1,124 @@
+//! 多交易所合并盘口 - 类 NBBO 的全市场最优买卖价
+//!
+//! 在本交易所报价、以全市场价格定价时使用：各交易所的最新快照合并为
+//! 一个最优买卖价，其中间价作为参考价格。
+
+use crate::strategies::avellaneda_stoikov::{is_valid_book, OrderBookSnapshot};
+use nautilus_core::UnixNanos;
+
+/// 多交易所合并盘口
+#[derive(Debug, Clone, Default)]
+pub struct ConsolidatedBook {
+    /// 各交易所最新快照（交易所数量很少，线性查找即可）
+    venues: Vec<(String, OrderBookSnapshot)>,
+}
+
+impl ConsolidatedBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 更新某个交易所的快照，无效盘口（空边、交叉等）会移除该交易所
+    pub fn update(&mut self, venue: &str, snapshot: OrderBookSnapshot) {
+        let existing = self.venues.iter().position(|(name, _)| name == venue);
+        match (existing, is_valid_book(&snapshot)) {
+            (Some(index), true) => self.venues[index].1 = snapshot,
+            (Some(index), false) => {
+                self.venues.swap_remove(index);
+            }
+            (None, true) => self.venues.push((venue.to_string(), snapshot)),
+            (None, false) => {}
+        }
+    }
+
+    /// 移除某个交易所（例如行情中断时）
+    pub fn remove(&mut self, venue: &str) {
+        self.venues.retain(|(name, _)| name != venue);
+    }
+
+    /// 当前纳入合并的交易所数量
+    pub fn venue_count(&self) -> usize {
+        self.venues.len()
+    }
+
+    /// 合并后的最优买卖价：买价取各交易所最高、卖价取最低，同价位数量相加，
+    /// 时间戳取最新的快照；没有任何交易所时返回 None
+    ///
+    /// 跨交易所可能出现交叉盘口（买价高于卖价），调用方按需处理
+    pub fn best(&self) -> Option<OrderBookSnapshot> {
+        let mut venues = self.venues.iter().map(|(_, snapshot)| snapshot);
+        let mut best = *venues.next()?;
+
+        for snapshot in venues {
+            if snapshot.best_bid > best.best_bid {
+                best.best_bid = snapshot.best_bid;
+                best.bid_volume = snapshot.bid_volume;
+            } else if snapshot.best_bid == best.best_bid {
+                best.bid_volume += snapshot.bid_volume;
+            }
+
+            if snapshot.best_ask < best.best_ask {
+                best.best_ask = snapshot.best_ask;
+                best.ask_volume = snapshot.ask_volume;
+            } else if snapshot.best_ask == best.best_ask {
+                best.ask_volume += snapshot.ask_volume;
+            }
+
+            best.timestamp_ns = UnixNanos::new(
+                best.timestamp_ns
+                    .as_u64()
+                    .max(snapshot.timestamp_ns.as_u64()),
+            );
+        }
+
+        Some(best)
+    }
+
+    /// 合并盘口中间价
+    pub fn mid(&self) -> Option<f64> {
+        self.best()
+            .map(|best| (best.best_bid + best.best_ask) * 0.5)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(bid: f64, ask: f64, ts: u64) -> OrderBookSnapshot {
+        OrderBookSnapshot {
+            best_bid: bid,
+            best_ask: ask,
+            bid_volume: 1.0,
+            ask_volume: 2.0,
+            timestamp_ns: UnixNanos::new(ts),
+        }
+    }
+
+    #[test]
+    fn test_merges_venues() {
+        let mut book = ConsolidatedBook::new();
+        assert!(book.best().is_none());
+
+        book.update("BINANCE", snapshot(50000.0, 50010.0, 1));
+        book.update("OKX", snapshot(50002.0, 50010.0, 2));
+
+        let best = book.best().unwrap();
+        assert_eq!(best.best_bid, 50002.0);
+        assert_eq!(best.bid_volume, 1.0);
+        assert_eq!(best.best_ask, 50010.0);
+        assert_eq!(best.ask_volume, 4.0);
+        assert_eq!(best.timestamp_ns.as_u64(), 2);
+        assert_eq!(book.mid(), Some(50006.0));
+
+        // 同一交易所的新快照覆盖旧快照，无效快照移除该交易所
+        book.update("OKX", snapshot(49990.0, 50005.0, 3));
+        assert_eq!(book.best().unwrap().best_bid, 50000.0);
+        assert_eq!(book.best().unwrap().best_ask, 50005.0);
+        book.update("OKX", snapshot(0.0, 50005.0, 4));
+        assert_eq!(book.venue_count(), 1);
+
+        book.remove("BINANCE");
+        assert!(book.mid().is_none());
+    }
+}
@@ -0,0 +1,350 @@
+//! Decimal 精度报价 - 避免 f64 舍入误差产生不在价格网格上的报价
+//!
+//! 波动率、保留价格等模型计算仍使用 f64，只有最终的买卖价和数量转换为
+//! `Decimal` 并按品种价格单位和数量精度取整：
+//! - 默认买价向下取整、卖价向上取整（不比模型报价更激进），方向由 `RoundingPolicy` 控制
+//! - 取整后价差仍不足以覆盖双边 maker 手续费（且至少一个价格单位）时，按价格单位继续放宽
+//! - 数量向零取整（不超过模型数量）
+
+use crate::strategies::avellaneda_stoikov::{
+    ASConfig, AvellanedaStoikov, OrderBookSnapshot, QuoteUpdate, RoundingPolicy,
+};
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::{Decimal, RoundingStrategy};
+
+/// 先按该精度消除 f64 表示误差（如 0.1 存为 0.09999...），再按品种精度定向取整
+const F64_NOISE_DP: u32 = 10;
+
+/// Decimal 报价
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecimalQuote {
+    pub bid_price: Decimal,
+    pub ask_price: Decimal,
+    pub bid_size: Decimal,
+    pub ask_size: Decimal,
+}
+
+/// Decimal 报价引擎
+#[derive(Debug, Clone, Copy)]
+pub struct DecimalQuoteEngine {
+    price_precision: u32,
+    size_precision: u32,
+    /// 最小价格单位，买卖价取整到其整数倍
+    tick: Decimal,
+    /// maker 手续费率（基点），价差下限为 2 * 费率 * 中间价
+    maker_fee_bps: f64,
+    /// 价格取整方向
+    rounding_policy: RoundingPolicy,
+}
+
+impl DecimalQuoteEngine {
+    pub fn new(price_precision: u32, size_precision: u32) -> Self {
+        Self {
+            price_precision,
+            size_precision,
+            tick: Decimal::new(1, price_precision),
+            maker_fee_bps: 0.0,
+            rounding_policy: RoundingPolicy::Conservative,
+        }
+    }
+
+    /// 设置最小价格单位（默认 10^-price_precision），无法表示为 Decimal 或非正时保持不变
+    pub fn with_tick_size(mut self, tick_size: f64) -> Self {
+        if let Some(tick) = to_decimal(tick_size).filter(|tick| *tick > Decimal::ZERO) {
+            self.tick = tick;
+        }
+        self
+    }
+
+    /// 设置价格取整方向
+    pub fn with_rounding_policy(mut self, rounding_policy: RoundingPolicy) -> Self {
+        self.rounding_policy = rounding_policy;
+        self
+    }
+
+    /// 设置 maker 手续费率（基点），取整后的价差不低于双边手续费
+    pub fn with_maker_fee_bps(mut self, maker_fee_bps: f64) -> Self {
+        self.maker_fee_bps = maker_fee_bps;
+        self
+    }
+
+    /// 使用配置中的品种精度、价格单位、maker 手续费率和取整方向
+    pub fn from_config(config: &ASConfig) -> Self {
+        Self::new(config.price_precision, config.size_precision)
+            .with_tick_size(config.tick_size())
+            .with_maker_fee_bps(config.maker_fee_bps)
+            .with_rounding_policy(config.rounding_policy)
+    }
+
+    /// 将订单簿更新交给策略处理，并把得到的报价转换为 Decimal
+    ///
+    /// 策略不报价（预热、熔断、盘口检查未通过等）时返回 None
+    pub fn on_orderbook_update(
+        &self,
+        strategy: &mut AvellanedaStoikov,
+        snapshot: &OrderBookSnapshot,
+    ) -> Option<DecimalQuote> {
+        let quote = strategy.on_orderbook_update(snapshot)?;
+        self.convert(&quote)
+    }
+
+    /// 将 f64 报价转换为价格单位整数倍的 Decimal 报价（含非有限值时返回 None）
+    pub fn convert(&self, quote: &QuoteUpdate) -> Option<DecimalQuote> {
+        let price = |value: f64, strategy: RoundingStrategy| {
+            let ticks = (to_decimal(value)? / self.tick).round_dp_with_strategy(0, strategy);
+            Some((ticks * self.tick).round_dp(self.price_precision))
+        };
+        let size = |value: f64| {
+            Some(
+                to_decimal(value)?
+                    .round_dp_with_strategy(self.size_precision, RoundingStrategy::ToZero),
+            )
+        };
+
+        let (bid_rounding, ask_rounding) = match self.rounding_policy {
+            RoundingPolicy::Conservative => (
+                RoundingStrategy::ToNegativeInfinity,
+                RoundingStrategy::ToPositiveInfinity,
+            ),
+            RoundingPolicy::Nearest => (
+                RoundingStrategy::MidpointAwayFromZero,
+                RoundingStrategy::MidpointAwayFromZero,
+            ),
+            RoundingPolicy::Aggressive => (
+                RoundingStrategy::ToPositiveInfinity,
+                RoundingStrategy::ToNegativeInfinity,
+            ),
+        };
+        // 向内取整可能使买卖价交叉，由手续费下限放宽回去
+        let (bid_price, ask_price) = self.widen_to_fee_floor(
+            price(quote.bid_price, bid_rounding)?,
+            price(quote.ask_price, ask_rounding)?,
+        )?;
+
+        Some(DecimalQuote {
+            bid_price,
+            ask_price,
+            bid_size: size(quote.bid_size)?,
+            ask_size: size(quote.ask_size)?,
+        })
+    }
+
+    /// 取整后价差低于下限 max((bid + ask) * fee, 一个价格单位) 时，按价格单位放宽（卖价先放宽），
+    /// 保证买卖价不相等也不交叉
+    fn widen_to_fee_floor(&self, bid: Decimal, ask: Decimal) -> Option<(Decimal, Decimal)> {
+        let fee = to_decimal(self.maker_fee_bps)? / Decimal::from(10_000);
+        // 费率不低于100%时价差永远无法覆盖手续费
+        if fee >= Decimal::ONE {
+            return None;
+        }
+        let tick = self.tick;
+        let min_spread = |bid: Decimal, ask: Decimal| ((bid + ask) * fee).max(tick);
+
+        let shortfall = min_spread(bid, ask) - (ask - bid);
+        if shortfall <= Decimal::ZERO {
+            return Some((bid, ask));
+        }
+
+        // 放宽后中间价也会移动，按新中间价重新检查直到满足
+        let mut ticks = (shortfall / tick).ceil();
+        loop {
+            let ask_ticks = (ticks / Decimal::TWO).ceil();
+            let widened_bid = bid - (ticks - ask_ticks) * tick;
+            let widened_ask = ask + ask_ticks * tick;
+            if widened_ask - widened_bid >= min_spread(widened_bid, widened_ask) {
+                return Some((widened_bid, widened_ask));
+            }
+            ticks += Decimal::ONE;
+        }
+    }
+}
+
+#[inline]
+fn to_decimal(value: f64) -> Option<Decimal> {
+    Some(Decimal::from_f64(value)?.round_dp(F64_NOISE_DP))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nautilus_core::UnixNanos;
+    use rust_decimal_macros::dec;
+
+    fn f64_quote(bid_price: f64, ask_price: f64, size: f64) -> QuoteUpdate {
+        QuoteUpdate {
+            bid_price,
+            ask_price,
+            bid_size: size,
+            ask_size: size,
+            spread: ask_price - bid_price,
+            spread_bps: 0.0,
+            bid_distance_bps: 0.0,
+            ask_distance_bps: 0.0,
+            reservation_price: (bid_price + ask_price) * 0.5,
+            spread_clamped: None,
+        }
+    }
+
+    #[test]
+    fn test_prices_on_precision_grid() {
+        let config = ASConfig::default();
+        let engine = DecimalQuoteEngine::from_config(&config);
+        let mut strategy = AvellanedaStoikov::new(config.clone());
+        let snapshot = OrderBookSnapshot {
+            best_bid: 50000.0,
+            best_ask: 50010.0,
+            bid_volume: 1.0,
+            ask_volume: 1.0,
+            timestamp_ns: UnixNanos::new(1_000_000_000),
+        };
+
+        let quote = engine
+            .on_orderbook_update(&mut strategy, &snapshot)
+            .unwrap();
+        for price in [quote.bid_price, quote.ask_price] {
+            assert!(price.scale() <= config.price_precision);
+            assert_eq!(price.round_dp(config.price_precision), price);
+        }
+        for size in [quote.bid_size, quote.ask_size] {
+            assert!(size.scale() <= config.size_precision);
+        }
+
+        // 策略拒绝报价（交叉盘口）时不转换
+        let crossed = OrderBookSnapshot {
+            best_bid: 50010.0,
+            best_ask: 50000.0,
+            timestamp_ns: UnixNanos::new(2_000_000_000),
+            ..snapshot
+        };
+        assert!(engine
+            .on_orderbook_update(&mut strategy, &crossed)
+            .is_none());
+    }
+
+    #[test]
+    fn test_directional_rounding() {
+        let engine = DecimalQuoteEngine::new(2, 3);
+
+        let quote = engine
+            .convert(&f64_quote(100.126, 100.131, 0.0019))
+            .unwrap();
+        assert_eq!(quote.bid_price, dec!(100.12));
+        assert_eq!(quote.ask_price, dec!(100.14));
+        assert_eq!(quote.bid_size, dec!(0.001));
+
+        // f64 表示误差不应导致价格被多舍一档
+        let quote = engine
+            .convert(&f64_quote(0.1 + 0.2, 0.7 - 0.3, 0.3))
+            .unwrap();
+        assert_eq!(quote.bid_price, dec!(0.3));
+        assert_eq!(quote.ask_price, dec!(0.4));
+        assert_eq!(quote.bid_size, dec!(0.3));
+
+        assert!(engine.convert(&f64_quote(f64::NAN, 1.0, 1.0)).is_none());
+    }
+
+    #[test]
+    fn test_tick_size_grid() {
+        // 价格单位 0.5（两位小数精度）：取整到 0.5 的整数倍
+        let engine = DecimalQuoteEngine::new(2, 3).with_tick_size(0.5);
+        let quote = engine.convert(&f64_quote(100.26, 100.74, 0.001)).unwrap();
+        assert_eq!(quote.bid_price, dec!(100.0));
+        assert_eq!(quote.ask_price, dec!(101.0));
+
+        // 落在同一格时按价格单位放宽，而不是按 0.01
+        let quote = engine.convert(&f64_quote(100.5, 100.5, 0.001)).unwrap();
+        assert_eq!(quote.ask_price - quote.bid_price, dec!(0.5));
+
+        let config = ASConfig {
+            price_precision: 2,
+            tick_size: Some(0.05),
+            ..ASConfig::default()
+        };
+        let quote = DecimalQuoteEngine::from_config(&config)
+            .convert(&f64_quote(100.12, 100.18, 0.001))
+            .unwrap();
+        assert_eq!(quote.bid_price, dec!(100.10));
+        assert_eq!(quote.ask_price, dec!(100.20));
+    }
+
+    #[test]
+    fn test_fee_aware_rounding() {
+        let engine = DecimalQuoteEngine::new(0, 3).with_maker_fee_bps(1.0);
+        let fee_floor = |bid: Decimal, ask: Decimal| (bid + ask) * dec!(0.0001);
+
+        // 就近取整会把买价抬高、卖价压低，价差 8 低于双边手续费 10
+        let model = f64_quote(49995.6, 50004.4, 0.001);
+        let naive_bid = Decimal::from_f64(model.bid_price).unwrap().round();
+        let naive_ask = Decimal::from_f64(model.ask_price).unwrap().round();
+        assert!(naive_ask - naive_bid < fee_floor(naive_bid, naive_ask));
+
+        let quote = engine.convert(&model).unwrap();
+        assert_eq!(quote.bid_price, dec!(49995));
+        assert_eq!(quote.ask_price, dec!(50005));
+        assert!(quote.ask_price - quote.bid_price >= fee_floor(quote.bid_price, quote.ask_price));
+
+        // 已在价格网格上但低于下限：逐个价格单位放宽
+        let quote = engine.convert(&f64_quote(49996.0, 50005.0, 0.001)).unwrap();
+        assert_eq!(quote.bid_price, dec!(49995));
+        assert_eq!(quote.ask_price, dec!(50006));
+        assert!(quote.ask_price - quote.bid_price >= fee_floor(quote.bid_price, quote.ask_price));
+
+        // 无手续费时不放宽
+        let quote = DecimalQuoteEngine::new(0, 3)
+            .convert(&f64_quote(49996.0, 50005.0, 0.001))
+            .unwrap();
+        assert_eq!(quote.ask_price - quote.bid_price, dec!(9));
+    }
+
+    #[test]
+    fn test_rounding_policy_direction() {
+        let rounded = |policy: RoundingPolicy, bid: f64, ask: f64| {
+            let quote = DecimalQuoteEngine::new(2, 3)
+                .with_rounding_policy(policy)
+                .convert(&f64_quote(bid, ask, 0.001))
+                .unwrap();
+            (quote.bid_price, quote.ask_price)
+        };
+
+        // 价格靠近下方价格单位
+        assert_eq!(
+            rounded(RoundingPolicy::Conservative, 100.124, 100.174),
+            (dec!(100.12), dec!(100.18))
+        );
+        assert_eq!(
+            rounded(RoundingPolicy::Nearest, 100.124, 100.174),
+            (dec!(100.12), dec!(100.17))
+        );
+        assert_eq!(
+            rounded(RoundingPolicy::Aggressive, 100.124, 100.174),
+            (dec!(100.13), dec!(100.17))
+        );
+
+        // 价格靠近上方价格单位
+        assert_eq!(
+            rounded(RoundingPolicy::Conservative, 100.126, 100.176),
+            (dec!(100.12), dec!(100.18))
+        );
+        assert_eq!(
+            rounded(RoundingPolicy::Nearest, 100.126, 100.176),
+            (dec!(100.13), dec!(100.18))
+        );
+        assert_eq!(
+            rounded(RoundingPolicy::Aggressive, 100.126, 100.176),
+            (dec!(100.13), dec!(100.17))
+        );
+
+        // 向内取整导致相等或交叉时放宽到至少一个价格单位
+        let (bid, ask) = rounded(RoundingPolicy::Aggressive, 100.121, 100.129);
+        assert!(bid < ask);
+        assert_eq!((bid, ask), (dec!(100.12), dec!(100.13)));
+        let (bid, ask) = rounded(RoundingPolicy::Conservative, 100.12, 100.12);
+        assert_eq!(ask - bid, dec!(0.01));
+
+        // 默认保守取整
+        assert_eq!(
+            DecimalQuoteEngine::from_config(&ASConfig::default()).rounding_policy,
+            RoundingPolicy::Conservative
+        );
+    }
+}
@@ -2,8 +2,23 @@
 //!
 //! 作为 Nautilus Trader 的插件实现各种交易策略
 
+pub mod adverse_selection;
+pub mod amend;
 pub mod avellaneda_stoikov;
+pub mod clock;
+pub mod connection;
+pub mod consolidated_book;
+pub mod decimal_quote;
 pub mod nautilus_compatible;
+pub mod order_tracker;
+pub mod paper_broker;
+pub mod performance;
+pub mod pnl;
+pub mod portfolio;
+pub mod quote_recorder;
+pub mod spread_capture;
+pub mod volatility_recorder;
+pub mod watchdog;
 
 pub use avellaneda_stoikov::{AvellanedaStoikov, ASConfig};
 pub use nautilus_compatible::{
@@ -11,4 +26,7 @@ pub use nautilus_compatible::{
     NautilusASConfig,
     create_strategy,
     create_strategy_with_config,
+    try_create_strategy_with_config,
 };
+pub use paper_broker::{PaperBroker, PaperFill};
+pub use portfolio::{PortfolioMarketMaker, PortfolioStats};
@@ -3,20 +3,44 @@
 //! 本策略实现了 Nautilus Trader 的策略接口，支持使用官方回测引擎
 //! 进行策略回测和实盘交易。
 
+use crate::error::{StrategyError, StrategyResult};
+use crate::strategies::amend::{
+    plan_quote_action, AmendMode, CancelScheduler, OrderUpdateStats, QuoteAction, RestingQuote,
+};
 use crate::strategies::avellaneda_stoikov::{
-    ASConfig, AvellanedaStoikov as BaseStrategy, OrderBookSnapshot, QuoteUpdate,
+    time_of_day_reached, utc_day, ASConfig, AvellanedaStoikov as BaseStrategy, BookOutcome,
+    HedgeSignal, OrderBookSnapshot, PartialASConfig, QuoteUpdate, StrategyStats,
 };
+use crate::strategies::clock::Clock as TimeSource;
+use crate::strategies::connection::{ConnectionAction, ConnectionStatus, DisconnectMonitor};
+use crate::strategies::decimal_quote::DecimalQuoteEngine;
+use crate::strategies::order_tracker::OpenOrderTracker;
+use crate::strategies::watchdog::{BookWatchdog, WatchdogAction};
 use anyhow::Result;
 use nautilus_common::actor::{DataActor, DataActorCore};
-use nautilus_model::enums::{OrderSide, TimeInForce};
+use nautilus_common::timer::TimeEvent;
+use nautilus_model::data::QuoteTick;
+use nautilus_model::enums::{BookType, LiquiditySide, OrderSide, TimeInForce};
 use nautilus_model::events::order::{
-    canceled::OrderCanceled, filled::OrderFilled, rejected::OrderRejected,
+    accepted::OrderAccepted, canceled::OrderCanceled, filled::OrderFilled,
+    rejected::OrderRejected,
 };
-use nautilus_model::identifiers::{InstrumentId, StrategyId};
+use nautilus_model::identifiers::{ClientOrderId, InstrumentId, StrategyId};
 use nautilus_model::orderbook::OrderBook as NautilusOrderBook;
 use nautilus_model::types::{Price, Quantity};
 use nautilus_trading::strategy::{Strategy, StrategyConfig, StrategyCore};
+use rust_decimal::prelude::ToPrimitive;
 use std::ops::{Deref, DerefMut};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::Arc;
+
+/// 行情看门狗定时器名称
+const WATCHDOG_TIMER: &str = "AS-BOOK-WATCHDOG";
+
+/// 配置热更新通道容量
+const CONFIG_UPDATE_CAPACITY: usize = 16;
 
 /// 与 Nautilus 兼容的策略配置
 #[derive(Debug, Clone)]
@@ -25,6 +49,37 @@ pub struct NautilusASConfig {
     pub base_config: ASConfig,
     /// Nautilus 策略配置
     pub strategy_config: StrategyConfig,
+    /// 策略状态持久化路径（停止时写入，启动时恢复）
+    pub state_path: Option<PathBuf>,
+    /// 模拟运行：只记录将要提交的订单，不实际下单
+    pub dry_run: bool,
+    /// 订单簿最大静默时间（纳秒），超时则撤单并重新订阅（None 表示禁用看门狗）
+    pub max_book_age_ns: Option<u64>,
+    /// 看门狗检查间隔（纳秒）
+    pub watchdog_interval_ns: u64,
+    /// 报价限价单的有效期类型
+    pub time_in_force: TimeInForce,
+    /// 报价限价单是否只做市（会吃单时由交易所拒绝，避免支付吃单手续费）
+    pub post_only: bool,
+    /// 对冲市价单是否设置只减仓（仅衍生品支持，现货会被交易所拒绝）
+    pub reduce_only_hedges: bool,
+    /// 最大挂单数量，达到上限后不再提交新订单（0表示不限制）
+    pub max_open_orders: usize,
+    /// 执行客户端断线时撤销全部挂单（默认关闭）
+    ///
+    /// 需要调用方通过 `with_connection_status` 提供连接状态来源，否则不生效；
+    /// 实盘入口目前没有接入执行客户端的连接状态
+    pub cancel_on_disconnect: bool,
+    /// 报价变化时的更新方式（改单或撤单重挂）
+    pub amend_mode: AmendMode,
+    /// 每日强制平仓时刻（UTC 零点起的秒数），到达后停止双边报价并平仓
+    pub flatten_at_secs: Option<u32>,
+    /// 挂单最大存活时间（纳秒），超时后在定时器中撤单并按当前报价重挂（None 表示不限制）
+    pub max_quote_age_ns: Option<u64>,
+    /// 订阅 QuoteTick（仅最优买卖价）代替 L2 订单簿，适用于只提供盘口报价的交易所
+    pub use_quote_ticks: bool,
+    /// 相邻两次撤单的最小间隔（纳秒），超出频率时撤单重挂延后、单纯撤单排队（0表示不限速）
+    pub min_cancel_interval_ns: u64,
 }
 
 impl Default for NautilusASConfig {
@@ -32,11 +87,31 @@ impl Default for NautilusASConfig {
         Self {
             base_config: ASConfig::default(),
             strategy_config: StrategyConfig::default(),
+            state_path: None,
+            dry_run: false,
+            max_book_age_ns: None,
+            watchdog_interval_ns: 1_000_000_000,
+            time_in_force: TimeInForce::Gtc,
+            post_only: false,
+            reduce_only_hedges: false,
+            max_open_orders: 0,
+            cancel_on_disconnect: false,
+            amend_mode: AmendMode::CancelReplace,
+            flatten_at_secs: None,
+            max_quote_age_ns: None,
+            use_quote_ticks: false,
+            min_cancel_interval_ns: 0,
         }
     }
 }
 
 impl NautilusASConfig {
+    /// 解析基础配置中的交易品种ID
+    pub fn instrument_id(&self) -> StrategyResult<InstrumentId> {
+        let value = &self.base_config.instrument_id;
+        InstrumentId::from_str(value).map_err(|_| StrategyError::InstrumentParse(value.clone()))
+    }
+
     pub fn new(base_config: ASConfig) -> Self {
         Self {
             base_config,
@@ -45,7 +120,43 @@ impl NautilusASConfig {
                 order_id_tag: Some("AVSTO".to_string()),
                 ..Default::default()
             },
+            state_path: None,
+            dry_run: false,
+            max_book_age_ns: None,
+            watchdog_interval_ns: 1_000_000_000,
+            time_in_force: TimeInForce::Gtc,
+            post_only: false,
+            reduce_only_hedges: false,
+            max_open_orders: 0,
+            cancel_on_disconnect: false,
+            amend_mode: AmendMode::CancelReplace,
+            flatten_at_secs: None,
+            max_quote_age_ns: None,
+            use_quote_ticks: false,
+            min_cancel_interval_ns: 0,
+        }
+    }
+}
+
+/// 成交手续费统计（手续费由引擎/交易所在成交回报中给出）
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FillFeeStats {
+    /// maker 成交额
+    pub maker_notional: f64,
+    /// taker 成交额
+    pub taker_notional: f64,
+    /// 成交回报中的手续费合计（返佣为负）
+    pub commission: f64,
+}
+
+impl FillFeeStats {
+    /// 记录一笔成交
+    pub fn record(&mut self, liquidity_side: LiquiditySide, notional: f64, commission: f64) {
+        match liquidity_side {
+            LiquiditySide::Taker => self.taker_notional += notional,
+            _ => self.maker_notional += notional,
         }
+        self.commission += commission;
     }
 }
 
@@ -62,17 +173,107 @@ pub struct NautilusAvellanedaStoikov {
     current_quote: Option<QuoteUpdate>,
     /// 是否正在交易
     is_trading: bool,
+    /// 策略状态持久化路径
+    state_path: Option<PathBuf>,
+    /// 模拟运行（不实际下单）
+    dry_run: bool,
+    /// 行情看门狗
+    watchdog: Option<BookWatchdog>,
+    /// 看门狗检查间隔（纳秒）
+    watchdog_interval_ns: u64,
+    /// 报价限价单的有效期类型
+    time_in_force: TimeInForce,
+    /// 报价限价单是否只做市
+    post_only: bool,
+    /// 对冲单是否只减仓
+    reduce_only_hedges: bool,
+    /// 进行中的对冲单（订单ID、未成交数量），成交完毕或关闭前不再提交新的对冲单
+    pending_hedge: Option<(ClientOrderId, f64)>,
+    /// 挂单数量追踪
+    open_orders: OpenOrderTracker<ClientOrderId>,
+    /// 配置热更新通道（每次订单簿更新前轮询）
+    config_updates: Option<Receiver<PartialASConfig>>,
+    /// 执行客户端连接状态（随看门狗定时器轮询）
+    connection: Option<Arc<dyn ConnectionStatus + Send + Sync>>,
+    /// 断线监控
+    disconnect_monitor: DisconnectMonitor,
+    /// 报价更新方式
+    amend_mode: AmendMode,
+    /// 当前买单（订单ID、价格、数量）
+    resting_bid: Option<(ClientOrderId, RestingQuote)>,
+    /// 当前卖单（订单ID、价格、数量）
+    resting_ask: Option<(ClientOrderId, RestingQuote)>,
+    /// 改单/重挂次数统计
+    order_updates: OrderUpdateStats,
+    /// 成交手续费统计
+    fill_fees: FillFeeStats,
+    /// 交易所已接受的订单数量
+    accepted_orders: u64,
+    /// 每日强制平仓时刻（UTC 零点起的秒数）
+    flatten_at_secs: Option<u32>,
+    /// 当前交易日（UTC 日），跨日时开始新交易日
+    trading_day: Option<u64>,
+    /// 挂单最大存活时间（纳秒）
+    max_quote_age_ns: Option<u64>,
+    /// 使用 QuoteTick 行情
+    use_quote_ticks: bool,
+    /// 撤单限速（排队中的撤单在行情更新和定时器中放行）
+    cancels: CancelScheduler<ClientOrderId>,
+    /// 注入的时钟（测试中替代 Nautilus 时钟）
+    time_source: Option<Arc<dyn TimeSource>>,
+    /// 模拟运行已生成的模拟订单数量（用于生成模拟订单ID）
+    dry_run_orders: u64,
 }
 
 impl NautilusAvellanedaStoikov {
+    /// 创建策略（不校验基础策略配置，见 [`BaseStrategy::new`]）
+    ///
+    /// 交易品种ID无法解析时 panic，需要返回错误时使用 [`Self::try_new`]
     pub fn new(config: NautilusASConfig) -> Self {
         let instrument_id = InstrumentId::from(config.base_config.instrument_id.as_str());
+        Self::with_instrument_id(config, instrument_id)
+    }
+
+    /// 校验基础策略配置并解析交易品种ID后创建
+    pub fn try_new(config: NautilusASConfig) -> StrategyResult<Self> {
+        config.base_config.validate()?;
+        let instrument_id = config.instrument_id()?;
+        Ok(Self::with_instrument_id(config, instrument_id))
+    }
+
+    /// 使用已解析的交易品种ID创建
+    fn with_instrument_id(config: NautilusASConfig, instrument_id: InstrumentId) -> Self {
         Self {
             core: StrategyCore::new(config.strategy_config),
             base_strategy: BaseStrategy::new(config.base_config),
             instrument_id,
             current_quote: None,
             is_trading: false,
+            state_path: config.state_path,
+            dry_run: config.dry_run,
+            watchdog: config.max_book_age_ns.map(BookWatchdog::new),
+            watchdog_interval_ns: config.watchdog_interval_ns,
+            time_in_force: config.time_in_force,
+            post_only: config.post_only,
+            reduce_only_hedges: config.reduce_only_hedges,
+            pending_hedge: None,
+            open_orders: OpenOrderTracker::new(config.max_open_orders),
+            config_updates: None,
+            connection: None,
+            disconnect_monitor: DisconnectMonitor::new(config.cancel_on_disconnect),
+            amend_mode: config.amend_mode,
+            resting_bid: None,
+            resting_ask: None,
+            order_updates: OrderUpdateStats::default(),
+            fill_fees: FillFeeStats::default(),
+            accepted_orders: 0,
+            flatten_at_secs: config.flatten_at_secs,
+            trading_day: None,
+            max_quote_age_ns: config.max_quote_age_ns,
+            use_quote_ticks: config.use_quote_ticks,
+            cancels: CancelScheduler::new(config.min_cancel_interval_ns),
+            time_source: None,
+            dry_run_orders: 0,
         }
     }
 
@@ -81,37 +282,552 @@ impl NautilusAvellanedaStoikov {
         Self::new(NautilusASConfig::new(base_config))
     }
 
-    /// 更新订单
-    fn update_orders(&mut self) -> Result<()> {
-        if let Some(quote) = self.current_quote {
-            // 取消现有订单
-            self.cancel_all_orders(self.instrument_id, None, None)?;
+    /// 注入时钟：撤单限速、挂单存活时间等包装层定时逻辑改用该时钟（测试中使用）
+    pub fn with_clock(mut self, clock: Arc<dyn TimeSource>) -> Self {
+        self.time_source = Some(clock);
+        self
+    }
 
-            // 提交新订单 - 使用 PRICE_PRECISION 和 QUANTITY_PRECISION（假设为 2 和 4，可根据实际调整）
-            const PRICE_PRECISION: u8 = 2;
-            const QUANTITY_PRECISION: u8 = 4;
+    /// 当前时间：优先使用注入的时钟，否则使用 Nautilus 时钟
+    fn now_ns(&mut self) -> u64 {
+        match self.time_source.as_ref() {
+            Some(clock) => clock.now_ns(),
+            None => self.clock().timestamp_ns().as_u64(),
+        }
+    }
 
-            let bid_order = self.create_limit_order(
-                self.instrument_id,
-                OrderSide::Buy,
-                Price::new(quote.bid_price, PRICE_PRECISION),
-                Quantity::new(quote.bid_size, QUANTITY_PRECISION),
-            )?;
+    /// 设置执行客户端连接状态来源，断线时按配置撤单
+    pub fn with_connection_status(
+        mut self,
+        connection: Arc<dyn ConnectionStatus + Send + Sync>,
+    ) -> Self {
+        self.connection = Some(connection);
+        self
+    }
+
+    /// 轮询执行连接，断线时撤销所有挂单
+    fn check_connection(&mut self) -> Result<()> {
+        let Some(connection) = self.connection.as_ref() else {
+            return Ok(());
+        };
+
+        if let Some(ConnectionAction::CancelAll) = self.disconnect_monitor.poll(connection.as_ref())
+        {
+            log::warn!("执行客户端断开连接，撤销所有挂单: {}", self.instrument_id);
+            self.current_quote = None;
+            self.cancel_all_quotes()?;
+        }
+        Ok(())
+    }
+
+    /// 最近一次计算的报价（不重新计算）
+    pub fn current_quote(&self) -> Option<QuoteUpdate> {
+        self.current_quote
+    }
+
+    /// 是否正在交易
+    pub fn is_trading(&self) -> bool {
+        self.is_trading
+    }
+
+    /// 改单/重挂次数统计
+    pub fn order_update_stats(&self) -> OrderUpdateStats {
+        self.order_updates
+    }
+
+    /// 交易所已接受的订单数量
+    pub fn accepted_orders(&self) -> u64 {
+        self.accepted_orders
+    }
+
+    /// 成交额与成交回报中的手续费统计
+    pub fn fill_fee_stats(&self) -> FillFeeStats {
+        self.fill_fees
+    }
+
+    /// 基础策略统计（报价次数、订单簿更新次数、库存等）
+    pub fn get_stats(&self) -> StrategyStats {
+        self.base_strategy.get_stats()
+    }
+
+    /// 创建配置热更新通道，返回发送端（重复调用会替换之前的通道）
+    pub fn config_update_sender(&mut self) -> SyncSender<PartialASConfig> {
+        let (tx, rx) = mpsc::sync_channel(CONFIG_UPDATE_CAPACITY);
+        self.config_updates = Some(rx);
+        tx
+    }
+
+    /// 应用所有待处理的配置更新
+    fn apply_pending_config_updates(&mut self) {
+        let Some(rx) = self.config_updates.as_ref() else {
+            return;
+        };
+        let updates: Vec<PartialASConfig> = rx.try_iter().collect();
+
+        for update in updates {
+            match self.base_strategy.apply_config_update(&update) {
+                Ok(()) => log::info!("已应用配置更新: {:?}", update),
+                Err(e) => log::error!("配置更新无效，已忽略: {} ({:?})", e, update),
+            }
+        }
+    }
+
+    /// 跨 UTC 日时开始新交易日：重置当日盈亏基准，解除单日亏损熔断和强制平仓
+    fn check_day_rollover(&mut self, now_ns: u64) {
+        let today = utc_day(now_ns);
+        if self.trading_day.replace(today).is_some_and(|day| today > day) {
+            log::info!("进入新交易日，重置当日盈亏并恢复双边报价: {}", self.instrument_id);
+            self.base_strategy.reset_session();
+        }
+    }
+
+    /// 到达强制平仓时刻后切换到只减仓报价（跨日后由 `check_day_rollover` 恢复）
+    fn check_flatten_time(&mut self, now_ns: u64) {
+        let Some(flatten_at) = self.flatten_at_secs else {
+            return;
+        };
+
+        if !self.base_strategy.is_flatten_requested() && time_of_day_reached(now_ns, flatten_at) {
+            log::warn!("到达强制平仓时刻，停止双边报价并平仓: {}", self.instrument_id);
+            self.base_strategy.request_flatten();
+        }
+    }
+
+    /// 订阅订单簿行情
+    fn subscribe_market_data(&mut self) {
+        if self.use_quote_ticks {
+            self.subscribe_quotes(self.instrument_id, None, None);
+        } else {
+            self.subscribe_book_deltas(self.instrument_id, BookType::L2_MBP, None, None, true, None);
+        }
+    }
+
+    /// 取消行情订阅
+    fn unsubscribe_market_data(&mut self) {
+        if self.use_quote_ticks {
+            self.unsubscribe_quotes(self.instrument_id, None, None);
+        } else {
+            self.unsubscribe_book_deltas(self.instrument_id, None, None);
+        }
+    }
+
+    /// 行情中断处理：撤销所有订单并重新订阅
+    fn handle_stale_book(&mut self) -> Result<()> {
+        log::warn!("订单簿行情中断，撤单并重新订阅: {}", self.instrument_id);
+
+        self.current_quote = None;
+        self.cancel_all_quotes()?;
+
+        self.unsubscribe_market_data();
+        self.subscribe_market_data();
+        Ok(())
+    }
+
+    /// 熔断处理：撤销所有挂单并停止报价，返回是否处于熔断状态
+    fn enforce_kill_switch(&mut self) -> Result<bool> {
+        if !self.base_strategy.is_halted() {
+            return Ok(false);
+        }
+
+        if self.current_quote.take().is_some() {
+            log::error!("触发熔断（单日亏损或波动率过高），撤销所有订单并停止报价");
+            self.cancel_all_quotes()?;
+        }
+
+        Ok(true)
+    }
+
+    /// 行情快照公共处理：配置热更新、定时检查、计算报价并更新订单
+    fn on_snapshot(&mut self, snapshot: OrderBookSnapshot) -> Result<()> {
+        if !self.is_trading {
+            return Ok(());
+        }
+
+        self.apply_pending_config_updates();
+
+        let now_ns = self.now_ns();
+        self.check_day_rollover(now_ns);
+        if self.flatten_at_secs.is_some() {
+            self.check_flatten_time(now_ns);
+        }
+        if let Some(watchdog) = self.watchdog.as_mut() {
+            watchdog.on_book(now_ns);
+        }
+
+        // 更新策略状态
+        let outcome = self.base_strategy.on_orderbook_update_outcome(&snapshot);
+        if self.enforce_kill_switch()? {
+            return Ok(());
+        }
+
+        self.flush_pending_cancels(now_ns)?;
+        match outcome {
+            BookOutcome::Quote(quote) => {
+                self.current_quote = Some(quote);
+                self.update_orders(now_ns)
+            }
+            // 节流：保留当前报价和挂单
+            BookOutcome::Throttled => Ok(()),
+            // 无效盘口、价格带等拒绝报价：撤销两侧挂单，避免旧报价继续挂在交易所
+            BookOutcome::Suppressed(reason) => {
+                log::debug!("本次不报价: {:?}", reason);
+                self.current_quote = None;
+                self.cancel_resting_quotes(now_ns)
+            }
+        }
+    }
+
+    /// 按当前报价更新两侧订单
+    fn update_orders(&mut self, now_ns: u64) -> Result<()> {
+        for side in [OrderSide::Buy, OrderSide::Sell] {
+            if let Some((price, size)) = self.rounded_side(side) {
+                self.update_side(side, price, size, now_ns)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 当前报价某一侧取整后的 (价格, 数量)
+    ///
+    /// 按品种精度远离中间价取整，并保证取整后的价差覆盖双边 maker 手续费；
+    /// 无报价或报价含非有限值时返回 None
+    fn rounded_side(&self, side: OrderSide) -> Option<(f64, f64)> {
+        let quote = self.current_quote?;
+        let rounded = DecimalQuoteEngine::from_config(self.base_strategy.config()).convert(&quote)?;
+        let (price, size) = match side {
+            OrderSide::Buy => (rounded.bid_price, rounded.bid_size),
+            _ => (rounded.ask_price, rounded.ask_size),
+        };
+        Some((price.to_f64()?, size.to_f64()?))
+    }
+
+    /// 某一侧仍在挂单中的订单（已成交、撤销或被拒的订单不再视为挂单）
+    fn resting(&self, side: OrderSide) -> Option<(ClientOrderId, RestingQuote)> {
+        match side {
+            OrderSide::Buy => self.resting_bid,
+            _ => self.resting_ask,
+        }
+        .filter(|(order_id, _)| self.open_orders.contains(order_id))
+    }
+
+    fn set_resting(&mut self, side: OrderSide, resting: Option<(ClientOrderId, RestingQuote)>) {
+        match side {
+            OrderSide::Buy => self.resting_bid = resting,
+            _ => self.resting_ask = resting,
+        }
+    }
+
+    /// 按更新方式调整单侧挂单（数量为0即低于最小步长的一侧不挂单）
+    fn update_side(&mut self, side: OrderSide, price: f64, size: f64, now_ns: u64) -> Result<()> {
+        let resting = self.resting(side);
+        // 撤单仍在排队：原挂单还在交易所，等撤单发出后再重新报价
+        if resting.is_some_and(|(order_id, _)| self.cancels.is_pending(&order_id)) {
+            return Ok(());
+        }
+        let action =
+            plan_quote_action(self.amend_mode, resting.map(|(_, quote)| quote), price, size);
+
+        // 撤单受限速时保留原挂单，等下次报价再撤单重挂
+        if action == QuoteAction::CancelReplace && !self.cancels.can_cancel(now_ns) {
+            return Ok(());
+        }
+        self.order_updates.record(action);
+
+        let resting_quote = match action {
+            QuoteAction::Keep => return Ok(()),
+            QuoteAction::Cancel => {
+                let queued = match resting {
+                    Some((order_id, _)) => !self.request_cancel(order_id, now_ns)?,
+                    None => false,
+                };
+                // 撤单排队时保留挂单记录，撤单发出后才清除
+                if queued {
+                    resting
+                } else {
+                    None
+                }
+            }
+            QuoteAction::Amend => {
+                let Some((order_id, _)) = resting else {
+                    return Ok(());
+                };
+                self.send_modify(order_id, price, size)?;
+                Some((
+                    order_id,
+                    RestingQuote {
+                        price,
+                        size,
+                        submitted_ns: now_ns,
+                    },
+                ))
+            }
+            QuoteAction::Submit | QuoteAction::CancelReplace => {
+                if let Some((order_id, _)) = resting {
+                    self.request_cancel(order_id, now_ns)?;
+                }
+                self.send_limit_order(side, price, size)?.map(|order_id| {
+                    (
+                        order_id,
+                        RestingQuote {
+                            price,
+                            size,
+                            submitted_ns: now_ns,
+                        },
+                    )
+                })
+            }
+        };
+
+        self.set_resting(side, resting_quote);
+        Ok(())
+    }
+
+    /// 撤销两侧报价挂单（报价被拒绝时调用，排队中的撤单发出前保留挂单记录）
+    fn cancel_resting_quotes(&mut self, now_ns: u64) -> Result<()> {
+        for side in [OrderSide::Buy, OrderSide::Sell] {
+            let resting = self.resting(side);
+            if let Some((order_id, _)) = resting {
+                log::info!("当前不报价，撤销挂单: {}", order_id);
+                if !self.request_cancel(order_id, now_ns)? {
+                    continue;
+                }
+            }
+            self.set_resting(side, None);
+        }
+        Ok(())
+    }
+
+    /// 撤销本品种所有订单（断线、行情中断、熔断、停止时调用）
+    fn cancel_all_quotes(&mut self) -> Result<()> {
+        self.resting_bid = None;
+        self.resting_ask = None;
+        if self.dry_run {
+            log::info!("[DRY RUN] 将撤销所有订单: {}", self.instrument_id);
+            self.open_orders.clear();
+            return Ok(());
+        }
+        self.cancel_all_orders(self.instrument_id, None, None)
+    }
+
+    /// 挂单超过 `max_quote_age_ns` 的方向
+    fn stale_sides(&self, now_ns: u64) -> Vec<OrderSide> {
+        let Some(max_age_ns) = self.max_quote_age_ns else {
+            return Vec::new();
+        };
+
+        [
+            (OrderSide::Buy, self.resting_bid),
+            (OrderSide::Sell, self.resting_ask),
+        ]
+        .into_iter()
+        .filter_map(|(side, resting)| {
+            resting
+                .filter(|(order_id, quote)| {
+                    self.open_orders.contains(order_id) && quote.is_expired(now_ns, max_age_ns)
+                })
+                .map(|_| side)
+        })
+        .collect()
+    }
 
-            let ask_order = self.create_limit_order(
-                self.instrument_id,
-                OrderSide::Sell,
-                Price::new(quote.ask_price, PRICE_PRECISION),
-                Quantity::new(quote.ask_size, QUANTITY_PRECISION),
+    /// 撤销过期挂单，当前报价仍有效时按该报价重新提交
+    fn refresh_stale_quotes(&mut self, now_ns: u64) -> Result<()> {
+        for side in self.stale_sides(now_ns) {
+            // 撤单受限速时保留过期挂单，等下次定时器再刷新
+            if !self.cancels.can_cancel(now_ns) {
+                break;
+            }
+            if let Some((order_id, _)) = self.resting(side) {
+                log::info!("挂单超过最大存活时间，撤单: {}", order_id);
+                self.request_cancel(order_id, now_ns)?;
+            }
+            self.set_resting(side, None);
+
+            if let Some((price, size)) = self.rounded_side(side) {
+                self.update_side(side, price, size, now_ns)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 按撤单限速撤单：可以立即撤单时发送并返回 true，否则排队并返回 false
+    fn request_cancel(&mut self, order_id: ClientOrderId, now_ns: u64) -> Result<bool> {
+        match self.cancels.request(order_id, now_ns) {
+            Some(order_id) => {
+                self.send_cancel(order_id)?;
+                Ok(true)
+            }
+            None => {
+                log::debug!("撤单限速，排队撤单: {}", order_id);
+                Ok(false)
+            }
+        }
+    }
+
+    /// 放行到期的排队撤单（已不在挂单中的订单直接跳过），撤单发出后清除对应挂单记录
+    fn flush_pending_cancels(&mut self, now_ns: u64) -> Result<()> {
+        let open_orders = &self.open_orders;
+        self.cancels.retain(|order_id| open_orders.contains(order_id));
+        let Some(order_id) = self.cancels.poll(now_ns) else {
+            return Ok(());
+        };
+        for side in [OrderSide::Buy, OrderSide::Sell] {
+            if self.resting(side).is_some_and(|(resting_id, _)| resting_id == order_id) {
+                self.set_resting(side, None);
+            }
+        }
+        self.send_cancel(order_id)
+    }
+
+    /// 按品种价格精度（`price_precision`）构造订单价格
+    fn order_price(&self, price: f64) -> Price {
+        Price::new(price, self.base_strategy.config().price_precision as u8)
+    }
+
+    /// 按品种数量精度（`size_precision`）构造订单数量
+    fn order_quantity(&self, size: f64) -> Quantity {
+        Quantity::new(size, self.base_strategy.config().size_precision as u8)
+    }
+
+    /// 从缓存中取出订单
+    fn cached_order(&self, order_id: &ClientOrderId) -> Result<nautilus_model::orders::OrderAny> {
+        self.cache()
+            .order(order_id)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Order not found in cache: {order_id}"))
+    }
+
+    /// 提交报价限价单并登记挂单，返回订单ID；达到挂单上限时放弃提交并返回 None
+    ///
+    /// 模拟运行时只记录日志，以模拟订单ID登记挂单
+    fn send_limit_order(
+        &mut self,
+        side: OrderSide,
+        price: f64,
+        size: f64,
+    ) -> Result<Option<ClientOrderId>> {
+        if !self.open_orders.can_submit() {
+            log::warn!(
+                "挂单数量已达上限 {}，放弃提交: {} {} @ {}",
+                self.open_orders.open_count(),
+                side,
+                size,
+                price
+            );
+            return Ok(None);
+        }
+
+        if self.dry_run {
+            self.dry_run_orders += 1;
+            let order_id = ClientOrderId::from(format!("DRY-RUN-{}", self.dry_run_orders).as_str());
+            log::info!("[DRY RUN] 将提交: {} {:.4} @ {:.2} ({})", side, size, price, order_id);
+            self.open_orders.on_submitted(order_id, size);
+            return Ok(Some(order_id));
+        }
+
+        let order = self.create_limit_order(
+            self.instrument_id,
+            side,
+            self.order_price(price),
+            self.order_quantity(size),
+        )?;
+        let order_id = order.client_order_id();
+        self.open_orders.on_submitted(order_id, size);
+        self.submit_order(order, None, None)?;
+        Ok(Some(order_id))
+    }
+
+    /// 原地修改挂单价格和数量（模拟运行时只记录日志）
+    fn send_modify(&mut self, order_id: ClientOrderId, price: f64, size: f64) -> Result<()> {
+        if self.dry_run {
+            log::info!("[DRY RUN] 将改单: {} -> {:.4} @ {:.2}", order_id, size, price);
+        } else {
+            let order = self.cached_order(&order_id)?;
+            self.modify_order(
+                order,
+                Some(self.order_quantity(size)),
+                Some(self.order_price(price)),
+                None,
+                None,
             )?;
+        }
+        self.open_orders.on_submitted(order_id, size);
+        Ok(())
+    }
+
+    /// 撤销单个订单（模拟运行时只记录日志并直接关闭模拟挂单）
+    fn send_cancel(&mut self, order_id: ClientOrderId) -> Result<()> {
+        if self.dry_run {
+            log::info!("[DRY RUN] 将撤单: {}", order_id);
+            self.open_orders.on_closed(&order_id);
+            return Ok(());
+        }
+        let order = self.cached_order(&order_id)?;
+        self.cancel_order(order, None)
+    }
 
-            self.submit_order(bid_order, None, None)?;
-            self.submit_order(ask_order, None, None)?;
+    /// 提交对冲市价单，已有对冲单未完成时不重复提交
+    fn submit_hedge(&mut self, signal: HedgeSignal) -> Result<()> {
+        if self.dry_run {
+            log::info!("[DRY RUN] 将提交对冲单: {} {}", signal.side, signal.quantity);
+            return Ok(());
+        }
+
+        if let Some((order_id, _)) = self.pending_hedge {
+            // IOC 剩余部分过期时不一定有撤单回报，以缓存中的订单状态为准
+            let closed = self.cache().order(&order_id).is_none_or(|order| order.is_closed());
+            if !closed {
+                log::debug!("对冲单 {} 尚未完成，暂不提交新的对冲单", order_id);
+                return Ok(());
+            }
+            self.pending_hedge = None;
         }
 
+        let instrument_id = self.instrument_id;
+        let quantity = self.order_quantity(signal.quantity);
+        let reduce_only = self.reduce_only_hedges.then_some(true);
+        let core = self.core_mut();
+        let order_factory = core
+            .order_factory
+            .as_mut()
+            .ok_or(StrategyError::OrderFactoryUnavailable)?;
+
+        let order = order_factory.market(
+            instrument_id,
+            signal.side,
+            quantity,
+            Some(TimeInForce::Ioc), // 立即成交或取消
+            reduce_only,            // 只减仓（仅衍生品）
+            None,                   // 报价数量
+            None,                   // 执行算法ID
+            None,                   // 执行算法参数
+            None,                   // 标签
+            None,                   // 客户端订单ID
+        );
+
+        log::warn!("库存超过对冲阈值，提交对冲单: {} {}", signal.side, signal.quantity);
+        self.pending_hedge = Some((order.client_order_id(), quantity.as_f64()));
+        self.submit_order(order, None, None)?;
         Ok(())
     }
 
+    /// 是否为进行中的对冲单
+    fn is_pending_hedge(&self, order_id: &ClientOrderId) -> bool {
+        self.pending_hedge.is_some_and(|(id, _)| id == *order_id)
+    }
+
+    /// 对冲单成交，全部成交后允许提交新的对冲单
+    fn on_hedge_filled(&mut self, quantity: f64) {
+        if let Some((_, remaining)) = self.pending_hedge.as_mut() {
+            *remaining -= quantity;
+            if *remaining <= 1e-12 {
+                self.pending_hedge = None;
+            }
+        }
+    }
+
     /// 创建限价订单
     fn create_limit_order(
         &mut self,
@@ -119,21 +835,23 @@ impl NautilusAvellanedaStoikov {
         side: OrderSide,
         price: Price,
         quantity: Quantity,
-    ) -> Result<nautilus_model::orders::OrderAny> {
+    ) -> StrategyResult<nautilus_model::orders::OrderAny> {
+        let time_in_force = self.time_in_force;
+        let post_only = self.post_only;
         let core = self.core_mut();
         let order_factory = core
             .order_factory
             .as_mut()
-            .ok_or_else(|| anyhow::anyhow!("OrderFactory not available"))?;
+            .ok_or(StrategyError::OrderFactoryUnavailable)?;
 
         Ok(order_factory.limit(
             instrument_id,
             side,
             quantity,
             price,
-            Some(TimeInForce::Gtc), // 当日有效（使用 Some 包装）
+            Some(time_in_force),    // 有效期类型
             None,                   // 过期时间
-            None,                   // 只做市
+            Some(post_only),        // 只做市
             None,                   // 只减仓
             None,                   // 报价数量
             None,                   // 显示数量
@@ -158,15 +876,51 @@ impl Strategy for NautilusAvellanedaStoikov {
     fn on_start(&mut self) -> Result<()> {
         log::info!("Avellaneda-Stoikov 策略启动");
 
+        let ladder_levels = self.base_strategy.config().ladder_levels;
+        if ladder_levels > 1 {
+            log::warn!("ladder_levels = {} 仅用于离线分析，每侧只挂最优一档", ladder_levels);
+        }
+
+        // 恢复上次停止时保存的状态
+        if let Some(path) = &self.state_path {
+            if path.exists() {
+                let bytes = std::fs::read(path)?;
+                self.base_strategy.restore_state(&bytes)?;
+                log::info!("已恢复策略状态: {:?}", path);
+            }
+        }
+
+        self.subscribe_market_data();
+
+        let needs_timer = self.watchdog.is_some()
+            || self.connection.is_some()
+            || self.max_quote_age_ns.is_some()
+            || self.cancels.min_interval_ns() > 0;
+        if needs_timer {
+            let interval_ns = self.watchdog_interval_ns;
+            self.clock()
+                .set_timer_ns(WATCHDOG_TIMER, interval_ns, None, None, None, None, None)?;
+        }
+
         // 开始交易
         self.is_trading = true;
 
         Ok(())
     }
 
+    // 订单被交易所接受时调用
+    fn on_order_accepted(&mut self, event: OrderAccepted) {
+        log::debug!("订单已接受: {}", event.client_order_id);
+        self.accepted_orders += 1;
+    }
+
     // 订单拒绝时调用
     fn on_order_rejected(&mut self, event: OrderRejected) {
         log::warn!("订单拒绝: {} - {}", event.client_order_id, event.reason);
+        if self.is_pending_hedge(&event.client_order_id) {
+            self.pending_hedge = None;
+        }
+        self.open_orders.on_closed(&event.client_order_id);
     }
 }
 
@@ -180,17 +934,19 @@ impl DataActor for NautilusAvellanedaStoikov {
         self.is_trading = false;
 
         // 取消所有订单
-        self.cancel_all_orders(self.instrument_id, None, None)?;
+        self.cancel_all_quotes()?;
+
+        // 持久化策略状态
+        if let Some(path) = &self.state_path {
+            std::fs::write(path, self.base_strategy.serialize_state()?)?;
+            log::info!("已保存策略状态: {:?}", path);
+        }
 
         Ok(())
     }
 
     // 订单簿数据更新时调用
     fn on_book(&mut self, order_book: &NautilusOrderBook) -> Result<()> {
-        if !self.is_trading {
-            return Ok(());
-        }
-
         // 转换为基础策略格式
         let snapshot = OrderBookSnapshot {
             best_bid: order_book.best_bid_price().map(|p| p.as_f64()).unwrap_or(0.0),
@@ -205,26 +961,52 @@ impl DataActor for NautilusAvellanedaStoikov {
                 .unwrap_or(0.0),
             timestamp_ns: order_book.ts_last,
         };
+        self.on_snapshot(snapshot)
+    }
 
-        // 更新策略状态
-        self.current_quote = self.base_strategy.on_orderbook_update(&snapshot);
-
-        // 更新订单
-        self.update_orders()?;
-
-        Ok(())
+    // 盘口报价更新时调用（直接由最优买卖价构造快照，不经过订单簿）
+    fn on_quote(&mut self, quote: &QuoteTick) -> Result<()> {
+        self.on_snapshot(quote_tick_snapshot(quote))
     }
 
     // 订单成交时调用
     fn on_order_filled(&mut self, event: &OrderFilled) -> Result<()> {
-        // 更新库存
-        // let side = match event.order_side {
-        //     OrderSide::Buy => crate::strategies::avellaneda_stoikov::OrderSide::Buy,
-        //     OrderSide::Sell => crate::strategies::avellaneda_stoikov::OrderSide::Sell,
-        //     OrderSide::NoOrderSide => return Ok(()),
-        // };
+        // 方向缺失的成交回报无法更新库存，记录后忽略
+        if event.order_side == OrderSide::NoOrderSide {
+            log::warn!(
+                "收到无方向的成交回报，已忽略: {} (数量 {} @ {})",
+                event.client_order_id,
+                event.last_qty,
+                event.last_px
+            );
+            return Ok(());
+        }
 
-        self.base_strategy.on_fill(event.order_side, event.last_qty.as_f64());
+        if self.is_pending_hedge(&event.client_order_id) {
+            // 对冲成交只更新库存和盈亏，不触发报价冷却和价差捕获统计
+            self.base_strategy.on_hedge_fill(
+                event.order_side,
+                event.last_qty.as_f64(),
+                event.last_px.as_f64(),
+                event.ts_event.as_u64(),
+            );
+            self.on_hedge_filled(event.last_qty.as_f64());
+        } else {
+            // 更新库存（成交后冷却从成交时间起算）
+            self.base_strategy.on_fill_at(
+                event.order_side,
+                event.last_qty.as_f64(),
+                event.last_px.as_f64(),
+                event.ts_event.as_u64(),
+            );
+            self.open_orders
+                .on_filled(&event.client_order_id, event.last_qty.as_f64());
+        }
+        self.fill_fees.record(
+            event.liquidity_side,
+            event.last_qty.as_f64() * event.last_px.as_f64(),
+            event.commission.map_or(0.0, |commission| commission.as_f64()),
+        );
 
         log::info!(
             "订单成交: {} {} @ {} | 库存: {:.4}",
@@ -234,12 +1016,44 @@ impl DataActor for NautilusAvellanedaStoikov {
             self.base_strategy.get_stats().current_inventory
         );
 
+        if self.enforce_kill_switch()? {
+            return Ok(());
+        }
+
+        if let Some(signal) = self.base_strategy.check_hedge() {
+            self.submit_hedge(signal)?;
+        }
+
         Ok(())
     }
 
+    // 定时器事件
+    fn on_time_event(&mut self, event: &TimeEvent) -> Result<()> {
+        if event.name.as_str() != WATCHDOG_TIMER || !self.is_trading {
+            return Ok(());
+        }
+
+        self.check_connection()?;
+
+        let now_ns = self.now_ns();
+        let action = self.watchdog.as_mut().and_then(|w| w.check(now_ns));
+        if let Some(WatchdogAction::CancelAndResubscribe) = action {
+            return self.handle_stale_book();
+        }
+
+        self.flush_pending_cancels(now_ns)?;
+
+        // 行情平静时没有订单簿更新触发重新报价，由定时器刷新过期挂单
+        self.refresh_stale_quotes(now_ns)
+    }
+
     // 订单取消时调用
     fn on_order_canceled(&mut self, event: &OrderCanceled) -> Result<()> {
         log::info!("订单取消: {}", event.client_order_id);
+        if self.is_pending_hedge(&event.client_order_id) {
+            self.pending_hedge = None;
+        }
+        self.open_orders.on_closed(&event.client_order_id);
         Ok(())
     }
 }
@@ -259,13 +1073,585 @@ impl DerefMut for NautilusAvellanedaStoikov {
     }
 }
 
+/// 由 QuoteTick 构造基础策略快照
+pub fn quote_tick_snapshot(quote: &QuoteTick) -> OrderBookSnapshot {
+    OrderBookSnapshot {
+        best_bid: quote.bid_price.as_f64(),
+        best_ask: quote.ask_price.as_f64(),
+        bid_volume: quote.bid_size.as_f64(),
+        ask_volume: quote.ask_size.as_f64(),
+        timestamp_ns: quote.ts_event,
+    }
+}
+
 /// 创建策略实例的辅助函数
 pub fn create_strategy() -> NautilusAvellanedaStoikov {
     let base_config = ASConfig::default();
     NautilusAvellanedaStoikov::from_base_config(base_config)
 }
 
-/// 使用自定义配置创建策略实例
+/// 使用自定义配置创建策略实例（不校验配置）
 pub fn create_strategy_with_config(config: ASConfig) -> NautilusAvellanedaStoikov {
     NautilusAvellanedaStoikov::from_base_config(config)
 }
+
+/// 校验自定义配置后创建策略实例
+pub fn try_create_strategy_with_config(
+    config: ASConfig,
+) -> StrategyResult<NautilusAvellanedaStoikov> {
+    NautilusAvellanedaStoikov::try_new(NautilusASConfig::new(config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategies::clock::ManualClock;
+    use nautilus_core::UnixNanos;
+    use nautilus_model::data::order::BookOrder;
+    use nautilus_model::types::Money;
+
+    #[test]
+    fn test_limit_order_options_from_config() {
+        let strategy = NautilusAvellanedaStoikov::new(NautilusASConfig {
+            time_in_force: TimeInForce::Day,
+            post_only: true,
+            ..NautilusASConfig::new(ASConfig::default())
+        });
+
+        assert_eq!(strategy.time_in_force, TimeInForce::Day);
+        assert!(strategy.post_only);
+
+        // 默认保持 GTC、非只做市
+        let strategy = create_strategy();
+        assert_eq!(strategy.time_in_force, TimeInForce::Gtc);
+        assert!(!strategy.post_only);
+    }
+
+    #[test]
+    fn test_try_new_rejects_invalid_config() {
+        assert!(try_create_strategy_with_config(ASConfig::default()).is_ok());
+
+        let config = ASConfig {
+            risk_aversion: 0.0,
+            ..ASConfig::default()
+        };
+        assert!(NautilusAvellanedaStoikov::try_new(NautilusASConfig::new(config)).is_err());
+
+        // 无法解析的交易品种ID返回错误而不是 panic
+        let config = ASConfig {
+            instrument_id: "not an instrument".to_string(),
+            ..ASConfig::default()
+        };
+        assert!(matches!(
+            NautilusAvellanedaStoikov::try_new(NautilusASConfig::new(config)),
+            Err(StrategyError::InstrumentParse(_))
+        ));
+    }
+
+    #[test]
+    fn test_min_cancel_interval_from_config() {
+        let strategy = NautilusAvellanedaStoikov::new(NautilusASConfig {
+            min_cancel_interval_ns: 50_000_000,
+            ..NautilusASConfig::new(ASConfig::default())
+        });
+        assert_eq!(strategy.cancels.min_interval_ns(), 50_000_000);
+        assert!(strategy.cancels.can_cancel(0));
+
+        // 默认不限速
+        assert_eq!(create_strategy().cancels.min_interval_ns(), 0);
+    }
+
+    #[test]
+    fn test_max_open_orders_from_config() {
+        let mut strategy = NautilusAvellanedaStoikov::new(NautilusASConfig {
+            max_open_orders: 1,
+            ..NautilusASConfig::new(ASConfig::default())
+        });
+        assert!(strategy.open_orders.can_submit());
+
+        strategy
+            .open_orders
+            .on_submitted(ClientOrderId::from("O-1"), 0.001);
+        assert!(!strategy.open_orders.can_submit());
+    }
+
+    #[test]
+    fn test_amend_mode_from_config() {
+        let strategy = NautilusAvellanedaStoikov::new(NautilusASConfig {
+            amend_mode: AmendMode::Amend,
+            ..NautilusASConfig::new(ASConfig::default())
+        });
+        assert_eq!(strategy.amend_mode, AmendMode::Amend);
+        assert_eq!(strategy.order_update_stats(), OrderUpdateStats::default());
+
+        // 默认撤单重挂
+        assert_eq!(create_strategy().amend_mode, AmendMode::CancelReplace);
+    }
+
+    #[test]
+    fn test_flatten_at_requests_flatten() {
+        let mut strategy = NautilusAvellanedaStoikov::new(NautilusASConfig {
+            flatten_at_secs: Some(21 * 3600),
+            ..NautilusASConfig::new(ASConfig::default())
+        });
+
+        // 20:59:59 UTC 未触发，21:00:00 UTC 触发
+        let day_ns = 19_723 * 86_400 * 1_000_000_000u64;
+        let check = |strategy: &mut NautilusAvellanedaStoikov, now_ns: u64| {
+            strategy.check_day_rollover(now_ns);
+            strategy.check_flatten_time(now_ns);
+        };
+        check(&mut strategy, day_ns + (21 * 3600 - 1) * 1_000_000_000);
+        assert!(!strategy.base_strategy.is_flatten_requested());
+        check(&mut strategy, day_ns + 21 * 3600 * 1_000_000_000);
+        assert!(strategy.base_strategy.is_flatten_requested());
+
+        // 次日零点后解除，次日 21:00 再次触发
+        let next_day_ns = day_ns + 86_400 * 1_000_000_000;
+        check(&mut strategy, next_day_ns + 1_000_000_000);
+        assert!(!strategy.base_strategy.is_flatten_requested());
+        check(&mut strategy, next_day_ns + 21 * 3600 * 1_000_000_000);
+        assert!(strategy.base_strategy.is_flatten_requested());
+    }
+
+    #[test]
+    fn test_day_rollover_resets_kill_switch() {
+        // 未配置强制平仓时刻也按 UTC 日重置当日盈亏
+        let mut strategy = NautilusAvellanedaStoikov::new(NautilusASConfig::new(ASConfig {
+            max_daily_loss: 5.0,
+            ..ASConfig::default()
+        }));
+        let day_ns = 19_723 * 86_400 * 1_000_000_000u64;
+        strategy.check_day_rollover(day_ns + 3600 * 1_000_000_000);
+
+        strategy.base_strategy.on_orderbook_update(&OrderBookSnapshot {
+            best_bid: 50000.0,
+            best_ask: 50010.0,
+            bid_volume: 1.0,
+            ask_volume: 1.0,
+            timestamp_ns: UnixNanos::new(day_ns + 3600 * 1_000_000_000),
+        });
+        strategy.base_strategy.on_fill_with_price(OrderSide::Buy, 0.01, 50000.0);
+        strategy.base_strategy.on_fill_with_price(OrderSide::Sell, 0.01, 49000.0);
+        assert!(strategy.base_strategy.is_killed());
+
+        // 当日 23:59:59 仍然熔断
+        strategy.check_day_rollover(day_ns + (86_400 - 1) * 1_000_000_000);
+        assert!(strategy.base_strategy.is_killed());
+
+        // 跨过零点：解除熔断，当日盈亏从零开始
+        strategy.check_day_rollover(day_ns + 86_400 * 1_000_000_000);
+        assert!(!strategy.base_strategy.is_killed());
+        assert_eq!(strategy.base_strategy.daily_pnl(), 0.0);
+    }
+
+    #[test]
+    fn test_stale_quote_scheduled_for_cancel() {
+        let mut strategy = NautilusAvellanedaStoikov::new(NautilusASConfig {
+            max_quote_age_ns: Some(5_000_000_000),
+            ..NautilusASConfig::new(ASConfig::default())
+        });
+
+        let bid_id = ClientOrderId::from("O-BID");
+        let ask_id = ClientOrderId::from("O-ASK");
+        strategy.open_orders.on_submitted(bid_id, 0.001);
+        strategy.open_orders.on_submitted(ask_id, 0.001);
+        strategy.resting_bid = Some((
+            bid_id,
+            RestingQuote {
+                price: 49995.0,
+                size: 0.001,
+                submitted_ns: 1_000_000_000,
+            },
+        ));
+        strategy.resting_ask = Some((
+            ask_id,
+            RestingQuote {
+                price: 50015.0,
+                size: 0.001,
+                submitted_ns: 4_000_000_000,
+            },
+        ));
+
+        assert!(strategy.stale_sides(5_999_999_999).is_empty());
+        // 只有超过存活时间的买单需要撤单
+        assert_eq!(strategy.stale_sides(6_000_000_000), vec![OrderSide::Buy]);
+
+        // 已关闭的订单不再处理
+        strategy.open_orders.on_closed(&bid_id);
+        assert!(strategy.stale_sides(6_000_000_000).is_empty());
+
+        // 未配置时不检查
+        assert!(create_strategy().stale_sides(u64::MAX).is_empty());
+    }
+
+    #[test]
+    fn test_rounded_side_covers_fees() {
+        let mut strategy = create_strategy_with_config(ASConfig {
+            price_precision: 2,
+            size_precision: 4,
+            maker_fee_bps: 1.0,
+            ..ASConfig::default()
+        });
+        assert_eq!(strategy.rounded_side(OrderSide::Buy), None);
+
+        strategy.current_quote = Some(QuoteUpdate {
+            bid_price: 49995.556,
+            ask_price: 50004.444,
+            bid_size: 0.00125,
+            ask_size: 0.001,
+            spread: 8.888,
+            spread_bps: 1.78,
+            bid_distance_bps: 0.89,
+            ask_distance_bps: 0.89,
+            reservation_price: 50000.0,
+            spread_clamped: None,
+        });
+
+        // 远离中间价取整后价差 8.90 仍低于双边手续费 10.00，放宽到覆盖手续费
+        let (bid, bid_size) = strategy.rounded_side(OrderSide::Buy).unwrap();
+        let (ask, _) = strategy.rounded_side(OrderSide::Sell).unwrap();
+        assert_eq!(bid_size, 0.0012);
+        assert!(bid <= 49995.55);
+        assert!(ask >= 50004.45);
+        assert!(ask - bid >= (ask + bid) * 0.0001 - 1e-9);
+    }
+
+    #[test]
+    fn test_error_variants() {
+        let config = NautilusASConfig::new(ASConfig {
+            instrument_id: "not an instrument".to_string(),
+            ..ASConfig::default()
+        });
+        assert!(matches!(
+            config.instrument_id(),
+            Err(StrategyError::InstrumentParse(_))
+        ));
+        assert!(NautilusASConfig::new(ASConfig::default()).instrument_id().is_ok());
+
+        // 未注册到交易节点时没有订单工厂
+        let mut strategy = create_strategy();
+        let instrument_id = strategy.instrument_id;
+        assert!(matches!(
+            strategy.create_limit_order(
+                instrument_id,
+                OrderSide::Buy,
+                Price::from("50000.00"),
+                Quantity::from("0.0010"),
+            ),
+            Err(StrategyError::OrderFactoryUnavailable)
+        ));
+    }
+
+    #[test]
+    fn test_config_update_channel() {
+        let mut strategy = create_strategy();
+        let tx = strategy.config_update_sender();
+
+        tx.send(PartialASConfig {
+            risk_aversion: Some(0.3),
+            ..Default::default()
+        })
+        .unwrap();
+        strategy.apply_pending_config_updates();
+
+        assert_eq!(strategy.base_strategy.config().risk_aversion, 0.3);
+    }
+
+    #[test]
+    fn test_no_order_side_fill_ignored() {
+        let mut strategy = create_strategy();
+        let event = OrderFilled {
+            order_side: OrderSide::NoOrderSide,
+            ..Default::default()
+        };
+
+        assert!(strategy.on_order_filled(&event).is_ok());
+        let stats = strategy.base_strategy.get_stats();
+        assert_eq!(stats.current_inventory, 0.0);
+        assert_eq!(stats.inventory_adjustments, 0);
+    }
+
+    #[test]
+    fn test_hedge_fill_tracked_separately() {
+        let mut strategy = NautilusAvellanedaStoikov::new(NautilusASConfig {
+            dry_run: true,
+            ..NautilusASConfig::new(ASConfig::default())
+        });
+        let hedge_id = ClientOrderId::from("HEDGE-1");
+        strategy.pending_hedge = Some((hedge_id, 0.02));
+        let fill = |order_id: ClientOrderId, side: OrderSide| OrderFilled {
+            client_order_id: order_id,
+            order_side: side,
+            last_qty: Quantity::from("0.010"),
+            last_px: Price::from("50000.00"),
+            ..Default::default()
+        };
+
+        strategy
+            .on_order_filled(&fill(ClientOrderId::from("QUOTE-1"), OrderSide::Buy))
+            .unwrap();
+        strategy.on_order_filled(&fill(hedge_id, OrderSide::Sell)).unwrap();
+        assert_eq!(strategy.base_strategy.get_stats().current_inventory, 0.0);
+        // 对冲成交不参与价差捕获配对
+        assert!(strategy.base_strategy.spread_capture_histogram().is_empty());
+        assert!(strategy.is_pending_hedge(&hedge_id));
+
+        // 全部成交后清除
+        strategy.on_order_filled(&fill(hedge_id, OrderSide::Sell)).unwrap();
+        assert!(strategy.pending_hedge.is_none());
+        assert!(!NautilusASConfig::default().reduce_only_hedges);
+    }
+
+    #[test]
+    fn test_disconnect_clears_quote() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let connected = Arc::new(AtomicBool::new(true));
+        let mut strategy = NautilusAvellanedaStoikov::new(NautilusASConfig {
+            dry_run: true,
+            cancel_on_disconnect: true,
+            ..NautilusASConfig::new(ASConfig::default())
+        })
+        .with_connection_status(connected.clone());
+
+        strategy.check_connection().unwrap();
+        strategy.current_quote = Some(QuoteUpdate {
+            bid_price: 50000.0,
+            ask_price: 50010.0,
+            bid_size: 0.001,
+            ask_size: 0.001,
+            spread: 10.0,
+            spread_bps: 2.0,
+            bid_distance_bps: 1.0,
+            ask_distance_bps: 1.0,
+            reservation_price: 50005.0,
+            spread_clamped: None,
+        });
+
+        connected.store(false, Ordering::Release);
+        strategy.check_connection().unwrap();
+        assert!(strategy.current_quote().is_none());
+    }
+
+    #[test]
+    fn test_current_quote_after_on_book() {
+        let config = ASConfig::default();
+        let mut strategy = NautilusAvellanedaStoikov::new(NautilusASConfig {
+            dry_run: true,
+            ..NautilusASConfig::new(config.clone())
+        })
+        .with_clock(Arc::new(ManualClock::new(1_000_000_000)));
+        assert!(strategy.current_quote().is_none());
+        assert!(!strategy.is_trading());
+
+        let mut book = NautilusOrderBook::new(strategy.instrument_id, BookType::L2_MBP);
+        book.add(
+            BookOrder::new(OrderSide::Buy, Price::from("50000.00"), Quantity::from("1.0"), 1),
+            0,
+            1,
+            UnixNanos::from(1_000_000_000),
+        );
+        book.add(
+            BookOrder::new(OrderSide::Sell, Price::from("50010.00"), Quantity::from("1.0"), 2),
+            0,
+            2,
+            UnixNanos::from(1_000_000_000),
+        );
+
+        strategy.is_trading = true;
+        strategy.on_book(&book).unwrap();
+
+        let mut expected_strategy = BaseStrategy::new(config);
+        let expected = expected_strategy
+            .on_orderbook_update(&OrderBookSnapshot {
+                best_bid: 50000.0,
+                best_ask: 50010.0,
+                bid_volume: 1.0,
+                ask_volume: 1.0,
+                timestamp_ns: book.ts_last,
+            })
+            .unwrap();
+
+        let quote = strategy.current_quote().unwrap();
+        assert_eq!(quote.bid_price, expected.bid_price);
+        assert_eq!(quote.ask_price, expected.ask_price);
+        assert_eq!(quote.bid_size, expected.bid_size);
+        assert_eq!(quote.ask_size, expected.ask_size);
+    }
+
+    #[test]
+    fn test_quote_tick_matches_book() {
+        let config = ASConfig::default();
+        let new_strategy = || {
+            let mut strategy = NautilusAvellanedaStoikov::new(NautilusASConfig {
+                dry_run: true,
+                use_quote_ticks: true,
+                ..NautilusASConfig::new(config.clone())
+            })
+            .with_clock(Arc::new(ManualClock::new(1_000_000_000)));
+            strategy.is_trading = true;
+            strategy
+        };
+
+        let mut book_strategy = new_strategy();
+        let mut book = NautilusOrderBook::new(book_strategy.instrument_id, BookType::L2_MBP);
+        book.add(
+            BookOrder::new(OrderSide::Buy, Price::from("50000.00"), Quantity::from("1.5"), 1),
+            0,
+            1,
+            UnixNanos::from(1_000_000_000),
+        );
+        book.add(
+            BookOrder::new(OrderSide::Sell, Price::from("50010.00"), Quantity::from("0.5"), 2),
+            0,
+            2,
+            UnixNanos::from(1_000_000_000),
+        );
+        book_strategy.on_book(&book).unwrap();
+
+        let mut tick_strategy = new_strategy();
+        let tick = QuoteTick::new(
+            tick_strategy.instrument_id,
+            Price::from("50000.00"),
+            Price::from("50010.00"),
+            Quantity::from("1.5"),
+            Quantity::from("0.5"),
+            book.ts_last,
+            book.ts_last,
+        );
+        tick_strategy.on_quote(&tick).unwrap();
+
+        assert_eq!(tick_strategy.current_quote(), book_strategy.current_quote());
+        assert!(tick_strategy.current_quote().is_some());
+    }
+
+    #[test]
+    fn test_suppressed_quote_cancels_resting_orders() {
+        let mut strategy = NautilusAvellanedaStoikov::new(NautilusASConfig {
+            dry_run: true,
+            use_quote_ticks: true,
+            ..NautilusASConfig::new(ASConfig::default())
+        })
+        .with_clock(Arc::new(ManualClock::new(1_000_000_000)));
+        strategy.is_trading = true;
+
+        let tick = |bid: &str, ask: &str, ts_ns: u64| {
+            QuoteTick::new(
+                strategy.instrument_id,
+                Price::from(bid),
+                Price::from(ask),
+                Quantity::from("1.0"),
+                Quantity::from("1.0"),
+                UnixNanos::from(ts_ns),
+                UnixNanos::from(ts_ns),
+            )
+        };
+        let valid = tick("50000.00", "50010.00", 1_000_000_000);
+        let crossed = tick("50010.00", "50000.00", 2_000_000_000);
+
+        strategy.on_quote(&valid).unwrap();
+        let (bid_id, _) = strategy.resting_bid.expect("应已挂买单");
+        let (ask_id, _) = strategy.resting_ask.expect("应已挂卖单");
+        assert!(strategy.open_orders.contains(&bid_id));
+        assert!(strategy.open_orders.contains(&ask_id));
+
+        // 无效盘口：不报价并撤销两侧旧挂单
+        strategy.on_quote(&crossed).unwrap();
+        assert!(strategy.current_quote().is_none());
+        assert!(strategy.resting_bid.is_none());
+        assert!(strategy.resting_ask.is_none());
+        assert!(!strategy.open_orders.contains(&bid_id));
+        assert!(!strategy.open_orders.contains(&ask_id));
+        assert_eq!(strategy.open_orders.open_count(), 0);
+    }
+
+    #[test]
+    fn test_order_precision_from_config() {
+        let strategy = create_strategy_with_config(ASConfig {
+            price_precision: 1,
+            size_precision: 3,
+            ..ASConfig::default()
+        });
+        assert_eq!(strategy.order_price(3000.25).precision, 1);
+        assert_eq!(strategy.order_quantity(0.0126).precision, 3);
+        assert_eq!(strategy.order_quantity(0.0126), Quantity::from("0.013"));
+    }
+
+    #[test]
+    fn test_queued_cancel_keeps_resting_order() {
+        let clock = Arc::new(ManualClock::new(1_000_000_000));
+        let mut strategy = NautilusAvellanedaStoikov::new(NautilusASConfig {
+            dry_run: true,
+            use_quote_ticks: true,
+            min_cancel_interval_ns: 1_000_000_000,
+            ..NautilusASConfig::new(ASConfig::default())
+        })
+        .with_clock(clock.clone());
+        strategy.is_trading = true;
+
+        let instrument_id = strategy.instrument_id;
+        let tick = |bid: &str, ask: &str, ts_ns: u64| {
+            QuoteTick::new(
+                instrument_id,
+                Price::from(bid),
+                Price::from(ask),
+                Quantity::from("1.0"),
+                Quantity::from("1.0"),
+                UnixNanos::from(ts_ns),
+                UnixNanos::from(ts_ns),
+            )
+        };
+
+        strategy.on_quote(&tick("50000.00", "50010.00", 1_000_000_000)).unwrap();
+        let (ask_id, _) = strategy.resting_ask.unwrap();
+
+        // 不报价时买单立即撤销，卖单撤单受限速排队：卖单仍在挂单中，记录保留
+        strategy.on_quote(&tick("50010.00", "50000.00", 1_100_000_000)).unwrap();
+        assert!(strategy.resting_bid.is_none());
+        assert_eq!(strategy.resting_ask.map(|(id, _)| id), Some(ask_id));
+        assert!(strategy.open_orders.contains(&ask_id));
+        assert!(strategy.cancels.is_pending(&ask_id));
+
+        // 恢复报价：买侧重新挂单，卖侧等待排队撤单发出
+        strategy.on_quote(&tick("50000.00", "50010.00", 1_200_000_000)).unwrap();
+        assert!(strategy.resting_bid.is_some());
+        assert_eq!(strategy.resting_ask.map(|(id, _)| id), Some(ask_id));
+
+        // 间隔满后撤单发出，清除旧卖单并重新挂单
+        clock.advance(1_000_000_000);
+        strategy.on_quote(&tick("50000.00", "50010.00", 2_200_000_000)).unwrap();
+        assert!(!strategy.open_orders.contains(&ask_id));
+        let (new_ask_id, _) = strategy.resting_ask.unwrap();
+        assert_ne!(new_ask_id, ask_id);
+        assert!(strategy.open_orders.contains(&new_ask_id));
+    }
+
+    #[test]
+    fn test_fill_fee_stats() {
+        let mut strategy = NautilusAvellanedaStoikov::new(NautilusASConfig {
+            dry_run: true,
+            ..NautilusASConfig::new(ASConfig::default())
+        });
+        let fill = |side: OrderSide, liquidity_side: LiquiditySide, commission: &str| OrderFilled {
+            order_side: side,
+            liquidity_side,
+            last_qty: Quantity::from("0.010"),
+            last_px: Price::from("50000.00"),
+            commission: Some(Money::from(commission)),
+            ..Default::default()
+        };
+
+        strategy
+            .on_order_filled(&fill(OrderSide::Buy, LiquiditySide::Maker, "-0.05 USDT"))
+            .unwrap();
+        strategy
+            .on_order_filled(&fill(OrderSide::Sell, LiquiditySide::Taker, "0.25 USDT"))
+            .unwrap();
+
+        let stats = strategy.fill_fee_stats();
+        assert!((stats.maker_notional - 500.0).abs() < 1e-9);
+        assert!((stats.taker_notional - 500.0).abs() < 1e-9);
+        assert!((stats.commission - 0.2).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,103 @@
+//! 挂单数量追踪 - 遵守交易所的挂单数量上限
+//!
+//! 提交时登记剩余数量，成交扣减至0、撤单或拒单时移除。
+
+use ahash::AHashMap;
+use std::hash::Hash;
+
+/// 挂单追踪器
+#[derive(Debug, Clone)]
+pub struct OpenOrderTracker<K> {
+    /// 最大挂单数量（0表示不限制）
+    max_open_orders: usize,
+    /// 订单ID -> 剩余数量
+    open_orders: AHashMap<K, f64>,
+}
+
+impl<K: Eq + Hash> OpenOrderTracker<K> {
+    pub fn new(max_open_orders: usize) -> Self {
+        Self {
+            max_open_orders,
+            open_orders: AHashMap::new(),
+        }
+    }
+
+    /// 是否还能提交新订单
+    #[inline]
+    pub fn can_submit(&self) -> bool {
+        self.max_open_orders == 0 || self.open_orders.len() < self.max_open_orders
+    }
+
+    /// 登记已提交的订单
+    pub fn on_submitted(&mut self, order_id: K, quantity: f64) {
+        self.open_orders.insert(order_id, quantity);
+    }
+
+    /// 扣减成交数量，完全成交后移除
+    pub fn on_filled(&mut self, order_id: &K, quantity: f64) {
+        if let Some(remaining) = self.open_orders.get_mut(order_id) {
+            *remaining -= quantity;
+            if *remaining <= 1e-12 {
+                self.open_orders.remove(order_id);
+            }
+        }
+    }
+
+    /// 订单已关闭（撤单、拒单、过期）
+    pub fn on_closed(&mut self, order_id: &K) {
+        self.open_orders.remove(order_id);
+    }
+
+    /// 订单是否仍在挂单中
+    #[inline]
+    pub fn contains(&self, order_id: &K) -> bool {
+        self.open_orders.contains_key(order_id)
+    }
+
+    /// 清空所有挂单（撤销全部订单后调用）
+    pub fn clear(&mut self) {
+        self.open_orders.clear();
+    }
+
+    /// 当前挂单数量
+    pub fn open_count(&self) -> usize {
+        self.open_orders.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cap_prevents_submission() {
+        let mut tracker = OpenOrderTracker::new(2);
+        assert!(tracker.can_submit());
+
+        tracker.on_submitted(1u64, 1.0);
+        tracker.on_submitted(2u64, 1.0);
+        assert_eq!(tracker.open_count(), 2);
+        assert!(!tracker.can_submit());
+
+        // 部分成交仍占用名额
+        tracker.on_filled(&1, 0.4);
+        assert!(!tracker.can_submit());
+
+        tracker.on_filled(&1, 0.6);
+        assert!(tracker.can_submit());
+
+        tracker.on_submitted(3u64, 1.0);
+        assert!(!tracker.can_submit());
+        tracker.on_closed(&2);
+        assert!(tracker.can_submit());
+    }
+
+    #[test]
+    fn test_unlimited() {
+        let mut tracker = OpenOrderTracker::new(0);
+        for id in 0..100u64 {
+            tracker.on_submitted(id, 1.0);
+        }
+        assert!(tracker.can_submit());
+    }
+}
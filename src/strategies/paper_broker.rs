@@ -0,0 +1,235 @@
+//! 模拟撮合 - 不依赖 Nautilus 引擎的端到端策略测试
+//!
+//! 每次订单簿更新时先用新盘口撮合上一轮挂出的报价，再让策略重新报价：
+//! - 卖一价 <= 挂单买价：买单成交（以挂单价格）
+//! - 买一价 >= 挂单卖价：卖单成交（以挂单价格）
+//!
+//! 可设置报价延迟：新报价（含撤单）在延迟到期后才生效，期间旧报价仍会被撮合，
+//! 用于模拟行情快速变化时被动挂单被“捡走”的逆向选择。
+
+use crate::strategies::avellaneda_stoikov::{AvellanedaStoikov, OrderBookSnapshot, QuoteUpdate};
+use nautilus_model::enums::OrderSide;
+use std::collections::VecDeque;
+
+/// 模拟成交记录
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PaperFill {
+    pub side: OrderSide,
+    pub quantity: f64,
+    pub price: f64,
+    pub timestamp_ns: u64,
+}
+
+/// 模拟撮合器
+#[derive(Debug)]
+pub struct PaperBroker {
+    strategy: AvellanedaStoikov,
+    /// 当前挂出的报价
+    resting_quote: Option<QuoteUpdate>,
+    /// 等待生效的报价（None 表示撤单）及生效时间，按提交顺序排列
+    pending_quotes: VecDeque<(Option<QuoteUpdate>, u64)>,
+    /// 报价提交到生效的延迟（纳秒）
+    latency_ns: u64,
+    /// 最近一次盘口中间价（用于盯市）
+    mark_price: f64,
+    fills: Vec<PaperFill>,
+}
+
+impl PaperBroker {
+    pub fn new(strategy: AvellanedaStoikov) -> Self {
+        Self {
+            strategy,
+            resting_quote: None,
+            pending_quotes: VecDeque::new(),
+            latency_ns: 0,
+            mark_price: 0.0,
+            fills: Vec::new(),
+        }
+    }
+
+    /// 设置报价延迟（提交/撤单到生效）
+    pub fn with_latency(mut self, latency_ns: u64) -> Self {
+        self.latency_ns = latency_ns;
+        self
+    }
+
+    /// 处理订单簿更新：撮合挂单、回报成交并重新报价
+    pub fn on_orderbook_update(&mut self, snapshot: &OrderBookSnapshot) -> Option<QuoteUpdate> {
+        let timestamp_ns = snapshot.timestamp_ns.as_u64();
+
+        // 依次生效所有已到期的报价，最后到期的一笔成为当前挂单
+        while let Some(&(quote, active_at)) = self.pending_quotes.front() {
+            if timestamp_ns < active_at {
+                break;
+            }
+            self.resting_quote = quote;
+            self.pending_quotes.pop_front();
+        }
+
+        if let Some(quote) = self.resting_quote.take() {
+            self.resting_quote = self.match_quote(quote, snapshot);
+        }
+
+        if snapshot.best_bid > 0.0 && snapshot.best_ask > 0.0 {
+            self.mark_price = (snapshot.best_bid + snapshot.best_ask) * 0.5;
+        }
+
+        let quote = self.strategy.on_orderbook_update(snapshot);
+        if self.latency_ns == 0 {
+            self.resting_quote = quote;
+        } else {
+            self.pending_quotes
+                .push_back((quote, timestamp_ns + self.latency_ns));
+        }
+        quote
+    }
+
+    /// 用新盘口撮合挂单，返回剩余未成交的报价（已成交一侧数量置0）
+    fn match_quote(
+        &mut self,
+        mut quote: QuoteUpdate,
+        snapshot: &OrderBookSnapshot,
+    ) -> Option<QuoteUpdate> {
+        let timestamp_ns = snapshot.timestamp_ns.as_u64();
+
+        if quote.bid_size > 0.0 && snapshot.best_ask > 0.0 && snapshot.best_ask <= quote.bid_price {
+            self.fill(
+                OrderSide::Buy,
+                quote.bid_size,
+                quote.bid_price,
+                timestamp_ns,
+            );
+            quote.bid_size = 0.0;
+        }
+
+        if quote.ask_size > 0.0 && snapshot.best_bid > 0.0 && snapshot.best_bid >= quote.ask_price {
+            self.fill(
+                OrderSide::Sell,
+                quote.ask_size,
+                quote.ask_price,
+                timestamp_ns,
+            );
+            quote.ask_size = 0.0;
+        }
+
+        (quote.bid_size > 0.0 || quote.ask_size > 0.0).then_some(quote)
+    }
+
+    fn fill(&mut self, side: OrderSide, quantity: f64, price: f64, timestamp_ns: u64) {
+        self.strategy
+            .on_fill_at(side, quantity, price, timestamp_ns);
+        self.fills.push(PaperFill {
+            side,
+            quantity,
+            price,
+            timestamp_ns,
+        });
+    }
+
+    /// 当前挂出的报价
+    pub fn resting_quote(&self) -> Option<QuoteUpdate> {
+        self.resting_quote
+    }
+
+    /// 全部模拟成交
+    pub fn fills(&self) -> &[PaperFill] {
+        &self.fills
+    }
+
+    /// 当前库存
+    pub fn inventory(&self) -> f64 {
+        self.strategy.get_stats().current_inventory
+    }
+
+    /// 已实现盈亏
+    pub fn realized_pnl(&self) -> f64 {
+        self.strategy.pnl().realized_pnl()
+    }
+
+    /// 以最近中间价盯市的总盈亏
+    pub fn total_pnl(&self) -> f64 {
+        self.strategy.pnl().total_pnl(self.mark_price)
+    }
+
+    pub fn strategy(&self) -> &AvellanedaStoikov {
+        &self.strategy
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategies::avellaneda_stoikov::ASConfig;
+    use nautilus_core::UnixNanos;
+
+    fn snapshot(bid: f64, ask: f64, ts: u64) -> OrderBookSnapshot {
+        OrderBookSnapshot {
+            best_bid: bid,
+            best_ask: ask,
+            bid_volume: 1.0,
+            ask_volume: 1.0,
+            timestamp_ns: UnixNanos::new(ts),
+        }
+    }
+
+    #[test]
+    fn test_descending_market_fills_bid() {
+        let mut broker = PaperBroker::new(AvellanedaStoikov::new(ASConfig::default()));
+
+        let quote = broker
+            .on_orderbook_update(&snapshot(50000.0, 50010.0, 1))
+            .unwrap();
+        assert!(broker.fills().is_empty());
+
+        // 市场下跌穿过挂单买价
+        broker.on_orderbook_update(&snapshot(49900.0, 49910.0, 2));
+
+        assert_eq!(
+            broker.fills(),
+            &[PaperFill {
+                side: OrderSide::Buy,
+                quantity: quote.bid_size,
+                price: quote.bid_price,
+                timestamp_ns: 2,
+            }]
+        );
+        assert_eq!(broker.inventory(), quote.bid_size);
+        assert_eq!(broker.realized_pnl(), 0.0);
+
+        let expected_pnl = quote.bid_size * (49905.0 - quote.bid_price);
+        assert!((broker.total_pnl() - expected_pnl).abs() < 1e-9);
+        assert!(broker.total_pnl() < 0.0);
+    }
+
+    #[test]
+    fn test_latency_changes_fills() {
+        let run = |latency_ns: u64| {
+            let mut broker = PaperBroker::new(AvellanedaStoikov::new(ASConfig::default()))
+                .with_latency(latency_ns);
+            let mut ts = 0;
+            for (bid, ask) in [
+                (50000.0, 50010.0),
+                (49900.0, 49910.0),
+                (49800.0, 49810.0),
+                (49900.0, 49910.0),
+                (50000.0, 50010.0),
+            ] {
+                ts += 1_000_000;
+                broker.on_orderbook_update(&snapshot(bid, ask, ts));
+            }
+            broker.fills().to_vec()
+        };
+
+        let immediate = run(0);
+        let delayed = run(2_500_000);
+        assert!(!immediate.is_empty());
+        assert!(!delayed.is_empty());
+        assert_ne!(immediate, delayed);
+
+        // 无延迟时第一次下跌即成交；有延迟时报价尚未生效，首笔成交推迟到
+        // 1ms 提交的报价在 3.5ms 生效之后（连续报价排队生效，不会互相覆盖）
+        assert_eq!(immediate[0].timestamp_ns, 2_000_000);
+        assert_eq!(delayed[0].timestamp_ns, 4_000_000);
+        assert_eq!(delayed[0].side, OrderSide::Buy);
+    }
+}
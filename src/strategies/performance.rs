@@ -0,0 +1,123 @@
+//! 滚动绩效追踪 - 基于逐K线盯市权益变化计算 Sharpe / Sortino
+
+use std::collections::VecDeque;
+
+/// 滚动绩效追踪器
+///
+/// 记录每根K线的权益变化（计价货币），比率均为未年化的逐K线值
+#[derive(Debug, Clone)]
+pub struct PerformanceTracker {
+    capacity: usize,
+    returns: VecDeque<f64>,
+}
+
+impl PerformanceTracker {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            returns: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// 记录一期收益
+    #[inline]
+    pub fn record(&mut self, period_return: f64) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.returns.len() == self.capacity {
+            self.returns.pop_front();
+        }
+        self.returns.push_back(period_return);
+    }
+
+    /// 已记录的收益期数
+    pub fn len(&self) -> usize {
+        self.returns.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.returns.is_empty()
+    }
+
+    /// 最近 `window` 期收益
+    fn window(&self, window: usize) -> impl Iterator<Item = &f64> {
+        let n = window.min(self.returns.len());
+        self.returns.iter().skip(self.returns.len() - n)
+    }
+
+    /// 滚动 Sharpe 比率：mean / std，方差为0或数据不足时返回0
+    pub fn rolling_sharpe(&self, window: usize) -> f64 {
+        let n = window.min(self.returns.len());
+        if n < 2 {
+            return 0.0;
+        }
+
+        let mean = self.window(window).sum::<f64>() / n as f64;
+        let variance = self.window(window).map(|r| (r - mean).powi(2)).sum::<f64>() / n as f64;
+        if variance <= f64::EPSILON {
+            return 0.0;
+        }
+
+        mean / variance.sqrt()
+    }
+
+    /// 滚动 Sortino 比率：mean / 下行标准差，无下行波动或数据不足时返回0
+    pub fn rolling_sortino(&self, window: usize) -> f64 {
+        let n = window.min(self.returns.len());
+        if n < 2 {
+            return 0.0;
+        }
+
+        let mean = self.window(window).sum::<f64>() / n as f64;
+        let downside = self.window(window).map(|r| r.min(0.0).powi(2)).sum::<f64>() / n as f64;
+        if downside <= f64::EPSILON {
+            return 0.0;
+        }
+
+        mean / downside.sqrt()
+    }
+
+    /// 清空记录
+    pub fn clear(&mut self) {
+        self.returns.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_sharpe_and_sortino() {
+        let mut tracker = PerformanceTracker::new(10);
+        for r in [1.0, 2.0, 3.0, 4.0] {
+            tracker.record(r);
+        }
+        // mean 2.5, std sqrt(1.25)
+        assert!((tracker.rolling_sharpe(4) - 2.5 / 1.25f64.sqrt()).abs() < 1e-12);
+        // 无下行波动
+        assert_eq!(tracker.rolling_sortino(4), 0.0);
+
+        let mut tracker = PerformanceTracker::new(10);
+        for r in [2.0, -1.0, 3.0, -2.0] {
+            tracker.record(r);
+        }
+        // mean 0.5, downside sqrt((1 + 4) / 4)
+        assert!((tracker.rolling_sortino(4) - 0.5 / 1.25f64.sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_zero_variance_and_window() {
+        let mut tracker = PerformanceTracker::new(3);
+        for _ in 0..5 {
+            tracker.record(1.0);
+        }
+        assert_eq!(tracker.len(), 3);
+        assert_eq!(tracker.rolling_sharpe(3), 0.0);
+
+        tracker.record(4.0);
+        // 窗口只取最近2期: [1, 4]
+        assert!((tracker.rolling_sharpe(2) - 2.5 / 1.5).abs() < 1e-12);
+    }
+}
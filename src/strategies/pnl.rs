@@ -0,0 +1,343 @@
+//! 盈亏追踪 - 基于加权平均成本计算已实现与未实现盈亏
+//!
+//! 同时按做市商经典口径拆分盈亏：
+//! - 价差盈亏：每笔成交相对成交时中间价捕获的边际
+//! - 库存盈亏：持仓随中间价变动的盯市盈亏（总盈亏 - 价差盈亏）
+//!
+//! `PnlReport` 汇总成本基础与分时段已实现盈亏，供会计/税务导出。
+//!
+//! 反向合约（币本位）以合约张数（每张面值1个计价货币单位）记持仓，盈亏以基础货币计：
+//! 多头盈亏 = 张数 × (1/开仓价 - 1/平仓价)。内部把价格换算为 -1/价格 后
+//! 沿用线性合约的加权平均成本逻辑，平均开仓价即为调和平均。
+
+use nautilus_model::enums::OrderSide;
+use serde::{Deserialize, Serialize};
+
+/// 盈亏追踪器
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct PnlTracker {
+    /// 带符号持仓（多头为正）
+    position: f64,
+    /// 持仓加权平均开仓值（线性合约为价格，反向合约为 -1/价格）
+    avg_entry: f64,
+    /// 已实现盈亏
+    realized_pnl: f64,
+    /// 价差盈亏（相对成交时中间价的边际之和）
+    spread_pnl: f64,
+    /// 当前时段开始时的已实现盈亏
+    session_realized_start: f64,
+    /// 反向合约（盈亏以基础货币计）
+    inverse: bool,
+}
+
+/// 盈亏报告（可序列化导出）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PnlReport {
+    /// 时段起点（纳秒），时段尚未开始时为 None
+    pub session_start_ns: Option<u64>,
+    /// 报告时间（纳秒）
+    pub session_end_ns: u64,
+    /// 带符号持仓
+    pub position: f64,
+    /// 持仓加权平均成本
+    pub avg_cost: f64,
+    /// 持仓成本基础（|持仓| × 平均成本）
+    pub cost_basis: f64,
+    /// 计算未实现盈亏使用的标记价格
+    pub mark_price: f64,
+    /// 累计已实现盈亏
+    pub realized_pnl: f64,
+    /// 本时段已实现盈亏
+    pub session_realized_pnl: f64,
+    pub unrealized_pnl: f64,
+    pub spread_pnl: f64,
+    pub inventory_pnl: f64,
+}
+
+impl PnlTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 反向合约盈亏追踪器（持仓为合约张数，盈亏以基础货币计）
+    pub fn inverse() -> Self {
+        Self {
+            inverse: true,
+            ..Self::default()
+        }
+    }
+
+    /// 是否为反向合约
+    pub fn is_inverse(&self) -> bool {
+        self.inverse
+    }
+
+    /// 价格换算为线性盈亏口径的值：线性合约为价格本身，反向合约为 -1/价格
+    #[inline]
+    fn value(&self, price: f64) -> f64 {
+        if self.inverse {
+            -1.0 / price
+        } else {
+            price
+        }
+    }
+
+    /// 记录成交
+    pub fn on_fill(&mut self, side: OrderSide, quantity: f64, price: f64) {
+        let signed_qty = match side {
+            OrderSide::Buy => quantity,
+            OrderSide::Sell => -quantity,
+            OrderSide::NoOrderSide => return,
+        };
+        let value = self.value(price);
+
+        if self.position == 0.0 || self.position.signum() == signed_qty.signum() {
+            // 开仓或加仓：更新平均成本
+            let total = self.position.abs() + quantity;
+            self.avg_entry = (self.position.abs() * self.avg_entry + quantity * value) / total;
+            self.position += signed_qty;
+            return;
+        }
+
+        // 减仓或反手：平仓部分计入已实现盈亏
+        let closing = quantity.min(self.position.abs());
+        self.realized_pnl += closing * (value - self.avg_entry) * self.position.signum();
+        self.position += signed_qty;
+
+        if self.position.abs() <= f64::EPSILON {
+            self.position = 0.0;
+            self.avg_entry = 0.0;
+        } else if self.position.signum() == signed_qty.signum() {
+            // 反手后剩余部分以成交价建仓
+            self.avg_entry = value;
+        }
+    }
+
+    /// 记录成交，并按成交时中间价计入价差盈亏（中间价未知时不计）
+    pub fn on_fill_at_mid(&mut self, side: OrderSide, quantity: f64, price: f64, mid: f64) {
+        if mid > 0.0 {
+            let edge = self.value(mid) - self.value(price);
+            match side {
+                OrderSide::Buy => self.spread_pnl += quantity * edge,
+                OrderSide::Sell => self.spread_pnl -= quantity * edge,
+                OrderSide::NoOrderSide => {}
+            }
+        }
+        self.on_fill(side, quantity, price);
+    }
+
+    /// 直接设置持仓与平均成本（如同步已有持仓），不计入已实现盈亏和价差盈亏
+    pub fn set_position(&mut self, position: f64, avg_cost: f64) {
+        if position.abs() <= f64::EPSILON {
+            self.position = 0.0;
+            self.avg_entry = 0.0;
+        } else {
+            self.position = position;
+            self.avg_entry = self.value(avg_cost);
+        }
+    }
+
+    /// 带符号持仓
+    pub fn position(&self) -> f64 {
+        self.position
+    }
+
+    /// 持仓平均成本（反向合约为调和平均开仓价，空仓时为0）
+    pub fn avg_cost(&self) -> f64 {
+        if self.inverse && self.avg_entry != 0.0 {
+            -1.0 / self.avg_entry
+        } else {
+            self.avg_entry
+        }
+    }
+
+    /// 持仓成本基础：|持仓| × 加权平均成本；反向合约为 |张数| / 平均开仓价（基础货币）
+    pub fn cost_basis(&self) -> f64 {
+        self.position.abs() * self.avg_entry.abs()
+    }
+
+    /// 已实现盈亏
+    pub fn realized_pnl(&self) -> f64 {
+        self.realized_pnl
+    }
+
+    /// 开始新时段：以当前已实现盈亏为时段基准
+    pub fn start_session(&mut self) {
+        self.session_realized_start = self.realized_pnl;
+    }
+
+    /// 本时段已实现盈亏
+    pub fn session_realized_pnl(&self) -> f64 {
+        self.realized_pnl - self.session_realized_start
+    }
+
+    /// 以标记价格计算未实现盈亏
+    pub fn unrealized_pnl(&self, mark_price: f64) -> f64 {
+        if self.position == 0.0 || mark_price <= 0.0 {
+            return 0.0;
+        }
+        self.position * (self.value(mark_price) - self.avg_entry)
+    }
+
+    /// 总盈亏（已实现 + 未实现）
+    pub fn total_pnl(&self, mark_price: f64) -> f64 {
+        self.realized_pnl + self.unrealized_pnl(mark_price)
+    }
+
+    /// 价差盈亏
+    pub fn spread_pnl(&self) -> f64 {
+        self.spread_pnl
+    }
+
+    /// 库存盈亏：总盈亏中由持仓随中间价变动产生的部分
+    pub fn inventory_pnl(&self, mark_price: f64) -> f64 {
+        self.total_pnl(mark_price) - self.spread_pnl
+    }
+
+    /// 生成盈亏报告
+    pub fn report(&self, mark_price: f64, session_start_ns: Option<u64>, now_ns: u64) -> PnlReport {
+        PnlReport {
+            session_start_ns,
+            session_end_ns: now_ns,
+            position: self.position,
+            avg_cost: self.avg_cost(),
+            cost_basis: self.cost_basis(),
+            mark_price,
+            realized_pnl: self.realized_pnl,
+            session_realized_pnl: self.session_realized_pnl(),
+            unrealized_pnl: self.unrealized_pnl(mark_price),
+            spread_pnl: self.spread_pnl,
+            inventory_pnl: self.inventory_pnl(mark_price),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_realized_and_unrealized_pnl() {
+        let mut pnl = PnlTracker::new();
+
+        pnl.on_fill(OrderSide::Buy, 1.0, 100.0);
+        pnl.on_fill(OrderSide::Buy, 1.0, 102.0);
+        assert_eq!(pnl.avg_cost(), 101.0);
+        assert_eq!(pnl.unrealized_pnl(103.0), 4.0);
+
+        pnl.on_fill(OrderSide::Sell, 1.0, 104.0);
+        assert_eq!(pnl.realized_pnl(), 3.0);
+        assert_eq!(pnl.position(), 1.0);
+
+        // 反手：平掉1个多头，再建1个空头
+        pnl.on_fill(OrderSide::Sell, 2.0, 100.0);
+        assert_eq!(pnl.realized_pnl(), 2.0);
+        assert_eq!(pnl.position(), -1.0);
+        assert_eq!(pnl.avg_cost(), 100.0);
+        assert_eq!(pnl.unrealized_pnl(99.0), 1.0);
+    }
+
+    #[test]
+    fn test_spread_and_inventory_pnl() {
+        let mut pnl = PnlTracker::new();
+
+        // 中间价100时以99买入：捕获1的价差
+        pnl.on_fill_at_mid(OrderSide::Buy, 1.0, 99.0, 100.0);
+        assert_eq!(pnl.spread_pnl(), 1.0);
+        assert_eq!(pnl.inventory_pnl(100.0), 0.0);
+
+        // 中间价涨到102：持仓带来2的库存盈亏
+        assert_eq!(pnl.inventory_pnl(102.0), 2.0);
+
+        // 中间价102时以103卖出：再捕获1的价差
+        pnl.on_fill_at_mid(OrderSide::Sell, 1.0, 103.0, 102.0);
+        assert_eq!(pnl.spread_pnl(), 2.0);
+        assert_eq!(pnl.realized_pnl(), 4.0);
+        assert_eq!(pnl.inventory_pnl(102.0), 2.0);
+
+        // 空仓后中间价变动不再影响库存盈亏
+        assert_eq!(pnl.inventory_pnl(90.0), 2.0);
+    }
+
+    #[test]
+    fn test_cost_basis_and_report() {
+        let mut pnl = PnlTracker::new();
+
+        pnl.on_fill(OrderSide::Buy, 1.0, 100.0);
+        pnl.on_fill(OrderSide::Buy, 1.0, 102.0);
+        assert_eq!(pnl.avg_cost(), 101.0);
+        assert_eq!(pnl.cost_basis(), 202.0);
+
+        pnl.on_fill(OrderSide::Sell, 1.0, 104.0);
+        assert_eq!(pnl.avg_cost(), 101.0);
+        assert_eq!(pnl.cost_basis(), 101.0);
+        assert_eq!(pnl.realized_pnl(), 3.0);
+
+        // 新时段只统计之后的已实现盈亏
+        pnl.start_session();
+        pnl.on_fill(OrderSide::Sell, 1.0, 100.0);
+        assert_eq!(pnl.realized_pnl(), 2.0);
+        assert_eq!(pnl.session_realized_pnl(), -1.0);
+
+        let report = pnl.report(100.0, Some(1_000), 2_000);
+        assert_eq!(report.session_start_ns, Some(1_000));
+        assert_eq!(report.session_end_ns, 2_000);
+        assert_eq!(report.position, 0.0);
+        assert_eq!(report.cost_basis, 0.0);
+        assert_eq!(report.session_realized_pnl, -1.0);
+
+        let json = serde_json::to_string(&report).unwrap();
+        assert_eq!(serde_json::from_str::<PnlReport>(&json).unwrap(), report);
+    }
+
+    #[test]
+    fn test_inverse_round_trip() {
+        let mut pnl = PnlTracker::inverse();
+
+        // 1000张在 40000 买入、50000 卖出：盈亏 = 1000 × (1/40000 - 1/50000)
+        pnl.on_fill(OrderSide::Buy, 1000.0, 40000.0);
+        assert!((pnl.avg_cost() - 40000.0).abs() < 1e-9);
+        assert!((pnl.cost_basis() - 1000.0 / 40000.0).abs() < 1e-15);
+        let expected = 1000.0 * (1.0 / 40000.0 - 1.0 / 50000.0);
+        assert!((pnl.unrealized_pnl(50000.0) - expected).abs() < 1e-15);
+
+        pnl.on_fill(OrderSide::Sell, 1000.0, 50000.0);
+        assert!((pnl.realized_pnl() - expected).abs() < 1e-15);
+        assert_eq!(pnl.position(), 0.0);
+
+        // 分两笔开仓：平均开仓价为调和平均
+        pnl.on_fill(OrderSide::Sell, 100.0, 40000.0);
+        pnl.on_fill(OrderSide::Sell, 100.0, 60000.0);
+        assert!((pnl.avg_cost() - 48000.0).abs() < 1e-6);
+        // 空头在价格下跌时盈利
+        assert!(pnl.unrealized_pnl(45000.0) > 0.0);
+        pnl.on_fill(OrderSide::Buy, 200.0, 45000.0);
+        let expected_short =
+            100.0 * (1.0 / 45000.0 - 1.0 / 40000.0) + 100.0 * (1.0 / 45000.0 - 1.0 / 60000.0);
+        assert!((pnl.realized_pnl() - expected - expected_short).abs() < 1e-15);
+
+        // 价差盈亏同样以基础货币计
+        let mut pnl = PnlTracker::inverse();
+        pnl.on_fill_at_mid(OrderSide::Buy, 1000.0, 49990.0, 50000.0);
+        assert!((pnl.spread_pnl() - 1000.0 * (1.0 / 49990.0 - 1.0 / 50000.0)).abs() < 1e-15);
+    }
+
+    #[test]
+    fn test_set_position() {
+        let mut pnl = PnlTracker::inverse();
+        pnl.set_position(-1000.0, 40000.0);
+        assert_eq!(pnl.position(), -1000.0);
+        assert!((pnl.avg_cost() - 40000.0).abs() < 1e-9);
+        assert_eq!(pnl.realized_pnl(), 0.0);
+        assert_eq!(pnl.spread_pnl(), 0.0);
+
+        // 之后的成交按设置的成本结算
+        pnl.on_fill(OrderSide::Buy, 1000.0, 50000.0);
+        let expected = 1000.0 * (1.0 / 50000.0 - 1.0 / 40000.0);
+        assert!((pnl.realized_pnl() - expected).abs() < 1e-15);
+
+        pnl.set_position(0.0, 50000.0);
+        assert_eq!(pnl.position(), 0.0);
+        assert_eq!(pnl.avg_cost(), 0.0);
+    }
+}
@@ -0,0 +1,268 @@
+//! 多品种组合做市 - 按品种路由到独立的 Avellaneda-Stoikov 实例
+//!
+//! 组合层面可配置相关系数矩阵与风险预算：当组合风险 √(eᵀΣe) 超出预算时，
+//! 所有品种的价差按比例放大。
+
+use crate::strategies::avellaneda_stoikov::{
+    ASConfig, AvellanedaStoikov, Bar, OrderBookSnapshot, QuoteUpdate, StrategyStats,
+};
+use ahash::AHashMap;
+use nautilus_model::enums::OrderSide;
+use nautilus_model::identifiers::InstrumentId;
+use serde::{Deserialize, Serialize};
+
+/// 组合统计数据（各品种汇总）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PortfolioStats {
+    pub instruments: usize,
+    pub quote_updates: u64,
+    pub orderbook_updates: u64,
+    pub inventory_adjustments: u64,
+}
+
+/// 多品种做市组合
+#[derive(Debug, Default)]
+pub struct PortfolioMarketMaker {
+    strategies: AHashMap<InstrumentId, AvellanedaStoikov>,
+    /// 品种添加顺序（即相关系数矩阵的行列顺序）
+    instrument_order: Vec<InstrumentId>,
+    /// 相关系数矩阵（未设置时视为各品种独立）
+    correlation_matrix: Option<Vec<Vec<f64>>>,
+    /// 组合风险预算（计价货币标准差，未设置时不放大价差）
+    risk_budget: Option<f64>,
+}
+
+impl PortfolioMarketMaker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加品种，返回解析后的品种ID（已存在则替换）
+    pub fn add_instrument(&mut self, config: ASConfig) -> InstrumentId {
+        let instrument_id = InstrumentId::from(config.instrument_id.as_str());
+        if self
+            .strategies
+            .insert(instrument_id, AvellanedaStoikov::new(config))
+            .is_none()
+        {
+            self.instrument_order.push(instrument_id);
+        }
+        instrument_id
+    }
+
+    /// 设置相关系数矩阵，行列顺序与品种添加顺序一致
+    pub fn set_correlation_matrix(&mut self, correlation_matrix: Vec<Vec<f64>>) {
+        self.correlation_matrix = Some(correlation_matrix);
+    }
+
+    /// 设置组合风险预算
+    pub fn set_risk_budget(&mut self, risk_budget: f64) {
+        self.risk_budget = Some(risk_budget);
+    }
+
+    /// 组合风险：√(eᵀΣe)，其中 eᵢ = qᵢ·midᵢ·σᵢ 为计价货币风险敞口
+    pub fn portfolio_risk(&self) -> f64 {
+        let exposures: Vec<f64> = self
+            .instrument_order
+            .iter()
+            .map(|id| {
+                let stats = self.strategies[id].get_stats();
+                stats.current_inventory * stats.mid_price * stats.current_volatility
+            })
+            .collect();
+
+        let mut variance = 0.0;
+        for (i, ei) in exposures.iter().enumerate() {
+            for (j, ej) in exposures.iter().enumerate() {
+                variance += ei * ej * self.correlation(i, j);
+            }
+        }
+
+        variance.max(0.0).sqrt()
+    }
+
+    /// 相关系数，矩阵缺失的项按独立处理
+    #[inline]
+    fn correlation(&self, i: usize, j: usize) -> f64 {
+        let default = if i == j { 1.0 } else { 0.0 };
+        self.correlation_matrix
+            .as_ref()
+            .and_then(|m| m.get(i))
+            .and_then(|row| row.get(j))
+            .copied()
+            .unwrap_or(default)
+    }
+
+    /// 价差放大倍数：组合风险超预算时为 risk / budget，否则为1
+    pub fn spread_multiplier(&self) -> f64 {
+        match self.risk_budget {
+            Some(budget) if budget > 0.0 => (self.portfolio_risk() / budget).max(1.0),
+            _ => 1.0,
+        }
+    }
+
+    /// 获取单个品种的策略
+    pub fn strategy(&self, instrument_id: &InstrumentId) -> Option<&AvellanedaStoikov> {
+        self.strategies.get(instrument_id)
+    }
+
+    /// 路由订单簿更新
+    #[inline]
+    pub fn on_orderbook_update(
+        &mut self,
+        instrument_id: &InstrumentId,
+        snapshot: &OrderBookSnapshot,
+    ) -> Option<QuoteUpdate> {
+        let mut quote = self
+            .strategies
+            .get_mut(instrument_id)?
+            .on_orderbook_update(snapshot)?;
+
+        // 组合风险超预算时围绕保留价格放大价差
+        let multiplier = self.spread_multiplier();
+        if multiplier > 1.0 {
+            let r = quote.reservation_price;
+            quote.bid_price = r - (r - quote.bid_price) * multiplier;
+            quote.ask_price = r + (quote.ask_price - r) * multiplier;
+            quote.spread *= multiplier;
+            quote.spread_bps *= multiplier;
+        }
+
+        Some(quote)
+    }
+
+    /// 路由K线更新
+    #[inline]
+    pub fn on_bar(&mut self, instrument_id: &InstrumentId, bar: &Bar) {
+        if let Some(strategy) = self.strategies.get_mut(instrument_id) {
+            strategy.on_bar(bar);
+        }
+    }
+
+    /// 路由订单成交
+    #[inline]
+    pub fn on_fill(
+        &mut self,
+        instrument_id: &InstrumentId,
+        side: OrderSide,
+        quantity: f64,
+        price: f64,
+    ) {
+        match self.strategies.get_mut(instrument_id) {
+            Some(strategy) => strategy.on_fill_with_price(side, quantity, price),
+            None => tracing::warn!(%instrument_id, "Fill for unknown instrument"),
+        }
+    }
+
+    /// 各品种统计数据
+    pub fn instrument_stats(&self) -> Vec<(InstrumentId, StrategyStats)> {
+        self.strategies
+            .iter()
+            .map(|(id, strategy)| (*id, strategy.get_stats()))
+            .collect()
+    }
+
+    /// 汇总统计数据
+    pub fn get_stats(&self) -> PortfolioStats {
+        self.strategies
+            .values()
+            .map(AvellanedaStoikov::get_stats)
+            .fold(
+                PortfolioStats {
+                    instruments: self.strategies.len(),
+                    ..Default::default()
+                },
+                |mut acc, stats| {
+                    acc.quote_updates += stats.quote_updates;
+                    acc.orderbook_updates += stats.orderbook_updates;
+                    acc.inventory_adjustments += stats.inventory_adjustments;
+                    acc
+                },
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nautilus_core::UnixNanos;
+
+    fn create_config(instrument_id: &str) -> ASConfig {
+        ASConfig {
+            instrument_id: instrument_id.to_string(),
+            ..ASConfig::default()
+        }
+    }
+
+    fn create_snapshot(bid: f64, ask: f64) -> OrderBookSnapshot {
+        OrderBookSnapshot {
+            best_bid: bid,
+            best_ask: ask,
+            bid_volume: 1.0,
+            ask_volume: 1.0,
+            timestamp_ns: UnixNanos::new(1_000_000_000),
+        }
+    }
+
+    #[test]
+    fn test_routes_and_isolates_inventory() {
+        let mut portfolio = PortfolioMarketMaker::new();
+        let btc = portfolio.add_instrument(create_config("BTCUSDT.BINANCE"));
+        let eth = portfolio.add_instrument(create_config("ETHUSDT.BINANCE"));
+
+        let btc_quote = portfolio
+            .on_orderbook_update(&btc, &create_snapshot(50000.0, 50010.0))
+            .unwrap();
+        let eth_quote = portfolio
+            .on_orderbook_update(&eth, &create_snapshot(3000.0, 3001.0))
+            .unwrap();
+        assert!(btc_quote.reservation_price > 40000.0);
+        assert!(eth_quote.reservation_price < 4000.0);
+
+        portfolio.on_fill(&btc, OrderSide::Buy, 0.01, 50005.0);
+
+        let btc_stats = portfolio.strategy(&btc).unwrap().get_stats();
+        let eth_stats = portfolio.strategy(&eth).unwrap().get_stats();
+        assert_eq!(btc_stats.current_inventory, 0.01);
+        assert_eq!(eth_stats.current_inventory, 0.0);
+
+        let stats = portfolio.get_stats();
+        assert_eq!(stats.instruments, 2);
+        assert_eq!(stats.orderbook_updates, 2);
+        assert_eq!(stats.inventory_adjustments, 1);
+
+        // 未注册品种不产生报价
+        let unknown = InstrumentId::from("SOLUSDT.BINANCE");
+        assert!(portfolio
+            .on_orderbook_update(&unknown, &create_snapshot(100.0, 100.1))
+            .is_none());
+    }
+
+    #[test]
+    fn test_correlated_inventory_widens_spreads() {
+        let mut portfolio = PortfolioMarketMaker::new();
+        let a = portfolio.add_instrument(create_config("AAAUSDT.BINANCE"));
+        let b = portfolio.add_instrument(create_config("BBBUSDT.BINANCE"));
+        portfolio.set_correlation_matrix(vec![vec![1.0, 1.0], vec![1.0, 1.0]]);
+
+        let snapshot = create_snapshot(50000.0, 50010.0);
+        portfolio.on_orderbook_update(&a, &snapshot);
+        portfolio.on_orderbook_update(&b, &snapshot);
+
+        // 单品种敞口 e = 0.01 * 50005 * 0.01 ≈ 5，预算介于单品种与合计之间
+        portfolio.set_risk_budget(7.5);
+
+        portfolio.on_fill(&a, OrderSide::Buy, 0.01, 50005.0);
+        let single_risk = portfolio.portfolio_risk();
+        let single = portfolio.on_orderbook_update(&a, &snapshot).unwrap();
+        assert_eq!(portfolio.spread_multiplier(), 1.0);
+
+        portfolio.on_fill(&b, OrderSide::Buy, 0.01, 50005.0);
+        let combined_risk = portfolio.portfolio_risk();
+        let combined = portfolio.on_orderbook_update(&a, &snapshot).unwrap();
+
+        assert!((combined_risk - 2.0 * single_risk).abs() < 1e-9);
+        assert!(combined.spread > single.spread);
+        assert!(combined.ask_price - combined.bid_price > single.ask_price - single.bid_price);
+    }
+}
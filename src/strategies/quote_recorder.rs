@@ -0,0 +1,110 @@
+//! 报价记录 - 以 JSONL 格式追加写入报价流，便于离线回放和绘图
+
+use crate::error::StrategyResult;
+use crate::strategies::avellaneda_stoikov::QuoteUpdate;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// 一条报价记录（JSONL 中的一行）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct QuoteRecord {
+    pub timestamp_ns: u64,
+    #[serde(flatten)]
+    pub quote: QuoteUpdate,
+}
+
+/// JSONL 报价记录器
+#[derive(Debug)]
+pub struct QuoteRecorder<W: Write> {
+    writer: W,
+    records: u64,
+}
+
+impl QuoteRecorder<BufWriter<File>> {
+    /// 以追加模式打开文件（不存在时创建）
+    pub fn open(path: impl AsRef<Path>) -> StrategyResult<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self::new(BufWriter::new(file)))
+    }
+}
+
+impl<W: Write> QuoteRecorder<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer, records: 0 }
+    }
+
+    /// 追加一条报价记录
+    pub fn record(&mut self, timestamp_ns: u64, quote: &QuoteUpdate) -> StrategyResult<()> {
+        let record = QuoteRecord {
+            timestamp_ns,
+            quote: *quote,
+        };
+        serde_json::to_writer(&mut self.writer, &record)?;
+        self.writer.write_all(b"\n")?;
+        self.records += 1;
+        Ok(())
+    }
+
+    /// 已写入的记录数
+    pub fn records(&self) -> u64 {
+        self.records
+    }
+
+    /// 刷新缓冲区
+    pub fn flush(&mut self) -> StrategyResult<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    /// 取回底层写入器
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_records_jsonl() {
+        let quote = QuoteUpdate {
+            bid_price: 49995.0,
+            ask_price: 50015.0,
+            bid_size: 0.001,
+            ask_size: 0.002,
+            spread: 20.0,
+            spread_bps: 4.0,
+            bid_distance_bps: 2.0,
+            ask_distance_bps: 2.0,
+            reservation_price: 50005.0,
+            spread_clamped: None,
+        };
+
+        let mut recorder = QuoteRecorder::new(Vec::new());
+        recorder.record(1, &quote).unwrap();
+        recorder.record(2, &quote).unwrap();
+        assert_eq!(recorder.records(), 2);
+
+        let output = String::from_utf8(recorder.into_inner()).unwrap();
+        let records: Vec<QuoteRecord> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(
+            records,
+            vec![
+                QuoteRecord {
+                    timestamp_ns: 1,
+                    quote
+                },
+                QuoteRecord {
+                    timestamp_ns: 2,
+                    quote
+                },
+            ]
+        );
+    }
+}
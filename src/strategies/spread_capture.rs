@@ -0,0 +1,136 @@
+//! 价差捕获统计 - 按FIFO配对成交，记录每次往返交易实际捕获的价差
+//!
+//! 一笔成交可能与多个反向批次部分配对，直方图按配对数量加权，
+//! 大单往返不会因拆成多个批次而被多计次数
+
+use nautilus_model::enums::OrderSide;
+use std::collections::{BTreeMap, VecDeque};
+
+/// 直方图桶宽（基点）
+pub const SPREAD_CAPTURE_BUCKET_BPS: f64 = 10.0;
+
+/// 未平仓成交批次
+#[derive(Debug, Clone, Copy)]
+struct OpenLot {
+    side: OrderSide,
+    quantity: f64,
+    price: f64,
+}
+
+/// FIFO价差捕获追踪器
+#[derive(Debug, Default)]
+pub struct SpreadCaptureTracker {
+    /// 未平仓批次（同一时刻只会有同方向的批次）
+    open_lots: VecDeque<OpenLot>,
+    /// 桶索引 -> 往返数量
+    histogram: BTreeMap<i64, f64>,
+}
+
+impl SpreadCaptureTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录一笔成交，与反向未平仓批次按FIFO配对
+    pub fn on_fill(&mut self, side: OrderSide, quantity: f64, price: f64) {
+        let mut remaining = quantity;
+
+        while remaining > 0.0 {
+            let Some(lot) = self.open_lots.front_mut() else {
+                break;
+            };
+            if lot.side == side {
+                break;
+            }
+
+            let matched = remaining.min(lot.quantity);
+            let (buy_price, sell_price) = match side {
+                OrderSide::Sell => (lot.price, price),
+                _ => (price, lot.price),
+            };
+            let entry_price = lot.price;
+
+            lot.quantity -= matched;
+            remaining -= matched;
+            if lot.quantity <= f64::EPSILON {
+                self.open_lots.pop_front();
+            }
+
+            if entry_price > 0.0 {
+                self.record((sell_price - buy_price) / entry_price * 10000.0, matched);
+            }
+        }
+
+        if remaining > f64::EPSILON {
+            self.open_lots.push_back(OpenLot {
+                side,
+                quantity: remaining,
+                price,
+            });
+        }
+    }
+
+    #[inline]
+    fn record(&mut self, captured_bps: f64, quantity: f64) {
+        let bucket = (captured_bps / SPREAD_CAPTURE_BUCKET_BPS).floor() as i64;
+        *self.histogram.entry(bucket).or_insert(0.0) += quantity;
+    }
+
+    /// 价差捕获直方图：(桶下界bps, 往返数量)，按桶升序
+    pub fn histogram(&self) -> Vec<(f64, f64)> {
+        self.histogram
+            .iter()
+            .map(|(&bucket, &count)| (bucket as f64 * SPREAD_CAPTURE_BUCKET_BPS, count))
+            .collect()
+    }
+
+    /// 清空状态
+    pub fn clear(&mut self) {
+        self.open_lots.clear();
+        self.histogram.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_lands_in_bucket() {
+        let mut tracker = SpreadCaptureTracker::new();
+
+        tracker.on_fill(OrderSide::Buy, 1.0, 100.0);
+        assert!(tracker.histogram().is_empty());
+
+        // 100 -> 101 捕获 100bps
+        tracker.on_fill(OrderSide::Sell, 1.0, 101.0);
+        assert_eq!(tracker.histogram(), vec![(100.0, 1.0)]);
+    }
+
+    #[test]
+    fn test_fifo_partial_matching() {
+        let mut tracker = SpreadCaptureTracker::new();
+
+        tracker.on_fill(OrderSide::Sell, 1.0, 100.0);
+        tracker.on_fill(OrderSide::Sell, 1.0, 102.0);
+
+        // 买入1.5：先平100的空头(0bps)，再平102的一半(+196bps)
+        tracker.on_fill(OrderSide::Buy, 1.5, 100.0);
+        assert_eq!(tracker.histogram(), vec![(0.0, 1.0), (190.0, 0.5)]);
+    }
+
+    #[test]
+    fn test_partial_round_trip_weighted_by_quantity() {
+        let mut tracker = SpreadCaptureTracker::new();
+
+        // 买入2，分两次各卖出0.5：两次部分往返合计1个单位，剩余1仍未平仓
+        tracker.on_fill(OrderSide::Buy, 2.0, 100.0);
+        tracker.on_fill(OrderSide::Sell, 0.5, 101.0);
+        tracker.on_fill(OrderSide::Sell, 0.5, 101.0);
+        assert_eq!(tracker.histogram(), vec![(100.0, 1.0)]);
+
+        // 一笔卖出平掉剩余1并反手0.5，只按配对的1计入
+        tracker.on_fill(OrderSide::Sell, 1.5, 100.0);
+        assert_eq!(tracker.histogram(), vec![(0.0, 1.0), (100.0, 1.0)]);
+    }
+}
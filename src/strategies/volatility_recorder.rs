@@ -0,0 +1,109 @@
+//! 波动率序列记录 - 保存每次K线/成交后报价使用的波动率，便于调参时离线查看
+//!
+//! 只保留最近 `capacity` 个样本，长时间运行时内存占用固定。
+
+use crate::error::StrategyResult;
+use crate::ring_buffer::RingBuffer;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// 默认保留的样本数量
+pub const DEFAULT_CAPACITY: usize = 10_000;
+
+/// 波动率序列记录器（保留最近的样本，写满后覆盖最旧样本）
+#[derive(Debug, Clone)]
+pub struct VolatilityRecorder {
+    /// (时间戳纳秒, 波动率)，按记录顺序
+    samples: RingBuffer<(u64, f64)>,
+}
+
+impl Default for VolatilityRecorder {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY)
+    }
+}
+
+impl VolatilityRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 指定最多保留的样本数量
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            samples: RingBuffer::new(capacity),
+        }
+    }
+
+    /// 追加一个样本（已满时丢弃最旧样本）
+    #[inline]
+    pub fn record(&mut self, timestamp_ns: u64, volatility: f64) {
+        self.samples.push((timestamp_ns, volatility));
+    }
+
+    /// 已记录的样本（从旧到新）
+    pub fn samples(&self) -> impl Iterator<Item = (u64, f64)> + '_ {
+        self.samples.iter().copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// 以 CSV 格式写出（表头 `timestamp_ns,volatility`）
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> StrategyResult<()> {
+        writeln!(writer, "timestamp_ns,volatility")?;
+        for (timestamp_ns, volatility) in self.samples() {
+            writeln!(writer, "{timestamp_ns},{volatility}")?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// 导出到 CSV 文件（覆盖已有文件）
+    pub fn save_csv(&self, path: impl AsRef<Path>) -> StrategyResult<()> {
+        self.write_csv(BufWriter::new(File::create(path)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_export() {
+        let mut recorder = VolatilityRecorder::new();
+        recorder.record(1_000, 0.02);
+        recorder.record(2_000, 0.025);
+
+        let mut csv = Vec::new();
+        recorder.write_csv(&mut csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "timestamp_ns,volatility\n1000,0.02\n2000,0.025\n"
+        );
+    }
+
+    #[test]
+    fn test_capacity_keeps_latest_samples() {
+        let mut recorder = VolatilityRecorder::with_capacity(2);
+        for i in 1..=3 {
+            recorder.record(i * 1_000, i as f64 * 0.01);
+        }
+
+        assert_eq!(recorder.len(), 2);
+        assert_eq!(
+            recorder.samples().collect::<Vec<_>>(),
+            vec![(2_000, 0.02), (3_000, 0.03)]
+        );
+    }
+}
@@ -0,0 +1,140 @@
+//! 行情看门狗 - 检测订单簿更新中断
+//!
+//! 状态机只依赖外部传入的时间戳，便于用模拟时钟测试：
+//! - Waiting: 尚未收到任何订单簿
+//! - Healthy: 最近一次订单簿未超时
+//! - Stale:   超过 `max_book_age_ns` 未收到订单簿，需要撤单并重新订阅
+
+/// 看门狗状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchdogState {
+    Waiting,
+    Healthy,
+    Stale,
+}
+
+/// 看门狗动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchdogAction {
+    /// 撤销所有订单并重新订阅行情
+    CancelAndResubscribe,
+}
+
+/// 订单簿更新看门狗
+#[derive(Debug, Clone)]
+pub struct BookWatchdog {
+    max_book_age_ns: u64,
+    state: WatchdogState,
+    /// 最近一次订单簿时间
+    last_book_ns: u64,
+    /// 最近一次触发动作的时间（用于持续中断时重试）
+    last_action_ns: u64,
+}
+
+impl BookWatchdog {
+    pub fn new(max_book_age_ns: u64) -> Self {
+        Self {
+            max_book_age_ns,
+            state: WatchdogState::Waiting,
+            last_book_ns: 0,
+            last_action_ns: 0,
+        }
+    }
+
+    pub fn state(&self) -> WatchdogState {
+        self.state
+    }
+
+    /// 记录收到订单簿
+    #[inline]
+    pub fn on_book(&mut self, now_ns: u64) {
+        if self.state == WatchdogState::Stale {
+            tracing::info!("Order book feed recovered");
+        }
+        self.state = WatchdogState::Healthy;
+        self.last_book_ns = now_ns;
+    }
+
+    /// 定时检查，返回需要执行的动作
+    ///
+    /// 进入 Stale 时触发一次；若中断持续，每隔 `max_book_age_ns` 重试一次
+    pub fn check(&mut self, now_ns: u64) -> Option<WatchdogAction> {
+        match self.state {
+            WatchdogState::Waiting => None,
+            WatchdogState::Healthy => {
+                if now_ns.saturating_sub(self.last_book_ns) > self.max_book_age_ns {
+                    tracing::warn!(
+                        age_ns = now_ns.saturating_sub(self.last_book_ns),
+                        max_age_ns = self.max_book_age_ns,
+                        "Order book feed stale"
+                    );
+                    self.state = WatchdogState::Stale;
+                    self.last_action_ns = now_ns;
+                    Some(WatchdogAction::CancelAndResubscribe)
+                } else {
+                    None
+                }
+            }
+            WatchdogState::Stale => {
+                if now_ns.saturating_sub(self.last_action_ns) > self.max_book_age_ns {
+                    self.last_action_ns = now_ns;
+                    Some(WatchdogAction::CancelAndResubscribe)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECOND: u64 = 1_000_000_000;
+
+    /// 测试用模拟时钟
+    struct MockClock {
+        now_ns: u64,
+    }
+
+    impl MockClock {
+        fn advance(&mut self, ns: u64) -> u64 {
+            self.now_ns += ns;
+            self.now_ns
+        }
+    }
+
+    #[test]
+    fn test_watchdog_state_machine() {
+        let mut clock = MockClock { now_ns: 0 };
+        let mut watchdog = BookWatchdog::new(5 * SECOND);
+
+        // 未收到订单簿前不触发
+        assert_eq!(watchdog.check(clock.advance(10 * SECOND)), None);
+        assert_eq!(watchdog.state(), WatchdogState::Waiting);
+
+        watchdog.on_book(clock.now_ns);
+        assert_eq!(watchdog.check(clock.advance(5 * SECOND)), None);
+        assert_eq!(watchdog.state(), WatchdogState::Healthy);
+
+        // 超时进入 Stale 并触发一次
+        assert_eq!(
+            watchdog.check(clock.advance(1)),
+            Some(WatchdogAction::CancelAndResubscribe)
+        );
+        assert_eq!(watchdog.state(), WatchdogState::Stale);
+        assert_eq!(watchdog.check(clock.advance(SECOND)), None);
+
+        // 持续中断时按间隔重试
+        assert_eq!(
+            watchdog.check(clock.advance(5 * SECOND)),
+            Some(WatchdogAction::CancelAndResubscribe)
+        );
+
+        // 行情恢复
+        watchdog.on_book(clock.advance(SECOND));
+        assert_eq!(watchdog.state(), WatchdogState::Healthy);
+        assert_eq!(watchdog.check(clock.advance(SECOND)), None);
+    }
+}
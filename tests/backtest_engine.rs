@@ -0,0 +1,220 @@
+//! 回测引擎集成测试
+//!
+//! 在 Nautilus 回测引擎中注册 `NautilusAvellanedaStoikov`，用合成行情驱动一轮完整回测，
+//! 验证注册、订阅、行情回调到基础策略、下单到交易所的整条链路（回测/实盘程序都依赖
+//! 这条链路）。QuoteTick 和订单簿增量两种行情路径各跑一轮。
+
+use ahash::AHashMap;
+use nautilus_backtest::{config::BacktestEngineConfig, engine::BacktestEngine};
+use nautilus_common::actor::registry::try_get_actor_unchecked;
+use nautilus_execution::models::{
+    fee::{FeeModelAny, MakerTakerFeeModel},
+    fill::FillModel,
+};
+use nautilus_model::data::{order::BookOrder, Data, OrderBookDelta, QuoteTick};
+use nautilus_model::enums::{AccountType, BookAction, BookType, OmsType, OrderSide, RecordFlag};
+use nautilus_model::identifiers::{InstrumentId, Venue};
+use nautilus_model::instruments::{stubs::crypto_perpetual_ethusdt, Instrument};
+use nautilus_model::types::{Money, Price, Quantity};
+use nautilus_strategies_rust::strategies::avellaneda_stoikov::{ASConfig, OrderBookSnapshot};
+use nautilus_strategies_rust::strategies::nautilus_compatible::{
+    NautilusASConfig, NautilusAvellanedaStoikov,
+};
+use nautilus_strategies_rust::synthetic::SyntheticMarket;
+use rust_decimal::prelude::ToPrimitive;
+
+/// 合成行情快照数量
+const TICKS: usize = 500;
+
+fn add_venue(engine: &mut BacktestEngine, book_type: BookType) {
+    engine
+        .add_venue(
+            Venue::from("BINANCE"),
+            OmsType::Netting,
+            AccountType::Margin,
+            book_type,
+            vec![Money::from("1000000 USDT")],
+            None,
+            None,
+            AHashMap::new(),
+            vec![],
+            FillModel::default(),
+            FeeModelAny::MakerTaker(MakerTakerFeeModel),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+}
+
+/// 合成快照转换为 QuoteTick（按品种精度取整）
+fn quote_tick(
+    instrument_id: InstrumentId,
+    snapshot: &OrderBookSnapshot,
+    price_precision: u8,
+    size_precision: u8,
+) -> QuoteTick {
+    QuoteTick::new(
+        instrument_id,
+        Price::new(snapshot.best_bid, price_precision),
+        Price::new(snapshot.best_ask, price_precision),
+        Quantity::new(snapshot.bid_volume, size_precision),
+        Quantity::new(snapshot.ask_volume, size_precision),
+        snapshot.timestamp_ns,
+        snapshot.timestamp_ns,
+    )
+}
+
+/// 合成快照转换为订单簿增量：清空后挂入买一、卖一（最后一条带 F_LAST 标记）
+fn book_deltas(
+    instrument_id: InstrumentId,
+    snapshot: &OrderBookSnapshot,
+    sequence: u64,
+    price_precision: u8,
+    size_precision: u8,
+) -> Vec<Data> {
+    let ts = snapshot.timestamp_ns;
+    let level = |side: OrderSide, price: f64, size: f64, flags: u8| {
+        let order = BookOrder::new(
+            side,
+            Price::new(price, price_precision),
+            Quantity::new(size, size_precision),
+            0,
+        );
+        OrderBookDelta::new(
+            instrument_id,
+            BookAction::Add,
+            order,
+            flags,
+            sequence,
+            ts,
+            ts,
+        )
+    };
+
+    vec![
+        Data::Delta(OrderBookDelta::clear(instrument_id, sequence, ts, ts)),
+        Data::Delta(level(
+            OrderSide::Buy,
+            snapshot.best_bid,
+            snapshot.bid_volume,
+            0,
+        )),
+        Data::Delta(level(
+            OrderSide::Sell,
+            snapshot.best_ask,
+            snapshot.ask_volume,
+            RecordFlag::F_LAST as u8,
+        )),
+    ]
+}
+
+/// 用合成行情跑一轮回测，检查行情、报价、下单和手续费
+fn run_backtest(use_quote_ticks: bool) {
+    let mut engine = BacktestEngine::new(BacktestEngineConfig::default()).unwrap();
+    let book_type = if use_quote_ticks {
+        BookType::L1_MBP
+    } else {
+        BookType::L2_MBP
+    };
+    add_venue(&mut engine, book_type);
+
+    let instrument = crypto_perpetual_ethusdt();
+    let instrument_id = instrument.id();
+    let price_precision = instrument.price_precision();
+    let size_precision = instrument.size_precision();
+    let maker_fee = instrument.maker_fee().to_f64().unwrap();
+    let taker_fee = instrument.taker_fee().to_f64().unwrap();
+    engine.add_instrument(instrument.into()).unwrap();
+
+    let base_config = ASConfig {
+        instrument_id: instrument_id.to_string(),
+        price_precision: u32::from(price_precision),
+        size_precision: u32::from(size_precision),
+        base_order_size: 0.01,
+        ..ASConfig::default()
+    };
+    let config = NautilusASConfig {
+        use_quote_ticks,
+        ..NautilusASConfig::new(base_config)
+    };
+    let strategy_id = config.strategy_config.strategy_id.unwrap();
+    engine
+        .add_strategy(NautilusAvellanedaStoikov::try_new(config).unwrap())
+        .unwrap();
+
+    let snapshots = SyntheticMarket::new(7, 0.0, 0.0005)
+        .with_initial_price(3000.0)
+        .snapshots()
+        .take(TICKS);
+    let data: Vec<Data> = if use_quote_ticks {
+        snapshots
+            .map(|snapshot| {
+                Data::Quote(quote_tick(
+                    instrument_id,
+                    &snapshot,
+                    price_precision,
+                    size_precision,
+                ))
+            })
+            .collect()
+    } else {
+        snapshots
+            .enumerate()
+            .flat_map(|(i, snapshot)| {
+                book_deltas(
+                    instrument_id,
+                    &snapshot,
+                    i as u64 + 1,
+                    price_precision,
+                    size_precision,
+                )
+            })
+            .collect()
+    };
+    engine.add_data(data, None, true, true);
+
+    engine.run();
+
+    let strategy = try_get_actor_unchecked::<NautilusAvellanedaStoikov>(&strategy_id.inner())
+        .expect("策略应已注册到引擎");
+    let stats = strategy.get_stats();
+    assert!(stats.orderbook_updates > 0, "未收到行情: {:?}", stats);
+    assert!(stats.quote_updates > 0, "未生成报价: {:?}", stats);
+    assert!(stats.orderbook_updates <= TICKS as u64);
+    assert!(stats.mid_price > 0.0);
+    assert!(strategy.accepted_orders() > 0, "没有订单被交易所接受");
+
+    // 引擎成交回报中的手续费与按品种费率计算的一致
+    let fees = strategy.fill_fee_stats();
+    let expected = fees.maker_notional * maker_fee + fees.taker_notional * taker_fee;
+    assert!(
+        (fees.commission - expected).abs() < 1e-4,
+        "手续费不一致: {:?}, 预期 {}",
+        fees,
+        expected
+    );
+}
+
+#[test]
+fn test_strategy_runs_in_backtest_engine() {
+    run_backtest(true);
+}
+
+#[test]
+fn test_strategy_runs_on_book_deltas() {
+    run_backtest(false);
+}